@@ -10,8 +10,9 @@ use common::{LockAction, LockActionType};
 use log::{error, info};
 use yas::capture::{Capturer, GenericCapturer, WindowsCapturer};
 use yas::game_info::{self, GameInfo};
-use yas::utils::{color_distance, press_any_key_to_continue};
-use yas::window_info::{self, FromWindowInfoRepository, WindowInfoRepository};
+use yas::utils::press_any_key_to_continue;
+use yas::vision::find_color;
+use yas::window_info::{self, FromWindowInfoRepository, WindowInfoConfig, WindowInfoRepository};
 use yas::{system_control::SystemControl, utils};
 use yas_genshin::application::ArtifactScannerApplication;
 use yas_genshin::scanner::artifact_scanner::ArtifactScannerWindowInfo;
@@ -34,9 +35,18 @@ pub fn main() {
     command = <AritfactLockConfig as Args>::augment_args_for_update(command);
     command = <GenshinArtifactScannerConfig as Args>::augment_args_for_update(command);
     command = <GenshinRepositoryScannerLogicConfig as Args>::augment_args_for_update(command);
+    command = <WindowInfoConfig as Args>::augment_args_for_update(command);
 
     let matches = command.get_matches();
 
+    let window_info_config = WindowInfoConfig::from_arg_matches(&matches).unwrap();
+    if window_info_config.dump_window_info {
+        let game_info = ArtifactScannerApplication::get_game_info().unwrap();
+        let window_info_repository = ArtifactScannerApplication::load_window_info_repository(&matches, &game_info).unwrap();
+        window_info_config.dump_if_required(&window_info_repository, &game_info).unwrap();
+        return;
+    }
+
     let lock_filename: std::path::PathBuf = Path::new(".").join("lock.json");
     if !lock_filename.exists() {
         error!("no lock.json");
@@ -94,8 +104,8 @@ pub struct AritfactLockConfig {
 
 impl ArtifactLock {
     pub fn new(arg_matches: &clap::ArgMatches) -> Self {
-        let window_info_repository = ArtifactScannerApplication::get_window_info_repository();
         let game_info = ArtifactScannerApplication::get_game_info().unwrap();
+        let window_info_repository = ArtifactScannerApplication::load_window_info_repository(arg_matches, &game_info).unwrap();
         let scanner = GenshinArtifactScanner::from_arg_matches(
             &window_info_repository,
             arg_matches,
@@ -158,6 +168,12 @@ impl ArtifactLock {
         let mut start_art; // 当页第一个圣遗物
         let mut end_art; // 当页最后一个圣遗物
 
+        object
+            .borrow()
+            .scanner
+            .check_screen_state()
+            .map_err(|e| e.to_string())?;
+
         let total_arts: i32 = object
             .borrow_mut()
             .scanner
@@ -299,25 +315,17 @@ impl ArtifactLock {
         let pos_x = self.window_info.lock_pos.x + self.window_info.artifact_panel_offset.width;
         let pos_y = self.window_info.lock_pos.y + self.window_info.artifact_panel_offset.height;
 
-        let mut locked = false;
-        'sq: for dx in -1..1 {
-            for dy in -10..10 {
-                if pos_y as i32 + dy < 0 || (pos_y as i32 + dy) as u32 >= game_image.height() {
-                    continue;
-                }
-
-                let color =
-                    game_image.get_pixel((pos_x as i32 + dx) as u32, (pos_y as i32 + dy) as u32);
-
-                if color_distance(color, &Rgb([255, 138, 117])) < 30 {
-                    if self.lock_config.verbose {
-                        info!("locked at ({}, {})", pos_x as i32 + dx, pos_y as i32 + dy);
-                    }
-                    locked = true;
-                    break 'sq;
-                }
-            }
+        let region = yas::positioning::Rect {
+            left: pos_x as i32 - 1,
+            top: pos_y as i32 - 10,
+            width: 2,
+            height: 20,
+        };
+        let found = find_color(&game_image, region, &Rgb([255, 138, 117]), 30);
+        if let (Some(pos), true) = (found, self.lock_config.verbose) {
+            info!("locked at ({}, {})", pos.x, pos.y);
         }
+        let locked = found.is_some();
         if self.lock_config.verbose {
             game_image.save(format!("{}-{}-{}.png", r, c, locked));
         }
@@ -9,8 +9,9 @@ use log::{error, info};
 use yas::capture::{Capturer, GenericCapturer, WindowsCapturer};
 use yas::game_info::GameInfo;
 use yas::positioning::Pos;
-use yas::utils::{color_distance, press_any_key_to_continue};
-use yas::window_info::FromWindowInfoRepository;
+use yas::utils::press_any_key_to_continue;
+use yas::vision::ColorClassifier;
+use yas::window_info::{FromWindowInfoRepository, WindowInfoConfig};
 use yas::{system_control::SystemControl, utils};
 use yas_starrail::application::RelicScannerApplication;
 use yas_starrail::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
@@ -32,9 +33,18 @@ pub fn main() {
     command = <RelicLockConfig as Args>::augment_args_for_update(command);
     command = <StarRailRelicScannerConfig as Args>::augment_args_for_update(command);
     command = <StarRailRepositoryScannerLogicConfig as Args>::augment_args_for_update(command);
+    command = <WindowInfoConfig as Args>::augment_args_for_update(command);
 
     let matches = command.get_matches();
 
+    let window_info_config = WindowInfoConfig::from_arg_matches(&matches).unwrap();
+    if window_info_config.dump_window_info {
+        let game_info = RelicScannerApplication::get_game_info().unwrap();
+        let window_info_repository = RelicScannerApplication::load_window_info_repository(&matches, &game_info).unwrap();
+        window_info_config.dump_if_required(&window_info_repository, &game_info).unwrap();
+        return;
+    }
+
     let lock_filename: std::path::PathBuf = Path::new(".").join("lock.json");
     if !lock_filename.exists() {
         error!("no lock.json");
@@ -92,8 +102,8 @@ pub struct RelicLockConfig {
 
 impl RelicLock {
     pub fn new(arg_matches: &clap::ArgMatches) -> Self {
-        let window_info_repository = RelicScannerApplication::get_window_info_repository();
         let game_info = RelicScannerApplication::get_game_info().unwrap();
+        let window_info_repository = RelicScannerApplication::load_window_info_repository(arg_matches, &game_info).unwrap();
         let scanner = StarRailRelicScanner::from_arg_matches(
             &window_info_repository,
             arg_matches,
@@ -152,6 +162,12 @@ impl RelicLock {
         let mut start_art; // 当页第一个圣遗物
         let mut end_art; // 当页最后一个圣遗物
 
+        object
+            .borrow()
+            .scanner
+            .check_screen_state()
+            .map_err(|e| e.to_string())?;
+
         let total_arts: i32 = object
             .borrow_mut()
             .scanner
@@ -295,15 +311,13 @@ impl RelicLock {
         };
         let color = self.capturer.capture_color(pos).unwrap();
 
-        let (index, _) = [
+        let index = ColorClassifier::from_colors(&[
             Rgb([18, 18, 18]),    // locked
             Rgb([249, 249, 249]), // unlocked
             Rgb([116, 108, 99]),  // discard
-        ]
-        .iter()
-        .enumerate()
-        .min_by_key(|&(_, match_color)| color_distance(match_color, &color))
-        .unwrap();
+        ])
+        .classify(&color)
+        .label;
 
         index == 0
     }
@@ -0,0 +1,221 @@
+use std::io::{stdin, stdout, Write};
+
+use anyhow::Result;
+use clap::{command, Args, FromArgMatches, ValueEnum};
+use image::RgbImage;
+use log::{error, info, warn};
+
+use yas::auto_layout::{LandmarkDefinition, LandmarkSet};
+use yas::calibration::{parse_value, Calibration};
+use yas::game_info::{Platform, UI};
+use yas::ocr::{ImageToText, OCRConfig};
+use yas::window_info::{load_window_info_repo_from_path, WindowInfoKey, WindowInfoKeys, WindowInfoRepository, WindowInfoTemplatePerSize};
+use yas_genshin::application::ArtifactScannerApplication;
+use yas_genshin::scanner::artifact_scanner::ArtifactScannerWindowInfo;
+use yas_genshin::scanner::GenshinArtifactScanner;
+use yas_genshin::scanner_controller::repository_layout::GenshinRepositoryScanControllerWindowInfo;
+use yas_starrail::application::RelicScannerApplication;
+use yas_starrail::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
+use yas_starrail::scanner::relic_scanner::StarRailRelicScanner;
+use yas_starrail::scanner_controller::repository_layout::StarRailRepositoryScanControllerWindowInfo;
+use yas_wutheringwaves::application::WWEchoScannerApplication;
+use yas_wutheringwaves::scanner::{EchoScannerWindowInfo, WWEchoScanner};
+use yas_wutheringwaves::scanner_controller::WWRepositoryLayoutWindowinfo;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum Game {
+    Genshin,
+    Starrail,
+    Ww,
+}
+
+#[derive(Clone, clap::Args)]
+struct CalibrateConfig {
+    #[arg(id = "game", long = "game", help = "游戏", value_enum)]
+    game: Game,
+
+    /// A screenshot of the game window only, the window size is taken from it
+    #[arg(id = "screenshot", long = "screenshot", help = "游戏窗口截图", value_name = "FILE")]
+    screenshot: String,
+
+    /// A window info file or directory to check, merged over the built-in window info
+    #[arg(id = "window-info", long = "window-info", help = "待校验的窗口信息文件或目录", value_name = "PATH")]
+    window_info: Option<String>,
+
+    #[arg(id = "output", long = "output", short, help = "输出的窗口信息文件，默认为 windows<宽>x<高>.json", value_name = "FILE")]
+    output: Option<String>,
+
+    #[arg(id = "overlay", long = "overlay", help = "标注区域后的截图", value_name = "FILE", default_value_t = String::from("calibration.png"))]
+    overlay: String,
+
+    #[arg(id = "no-ocr", long = "no-ocr", help = "不识别文字区域")]
+    no_ocr: bool,
+
+    /// Write the window info at once, without adjusting or confirming the regions
+    #[arg(id = "no-interactive", long = "no-interactive", help = "不进入交互模式，直接写出窗口信息")]
+    no_interactive: bool,
+
+    #[arg(id = "mobile", long = "mobile", help = "截图为移动端界面")]
+    mobile: bool,
+
+    /// Crop landmark templates from the screenshot, for `--auto-layout` of the scanners
+    #[arg(id = "export-landmarks", long = "export-landmarks", help = "从截图导出地标模板到目录，供扫描器的 --auto-layout --landmarks 使用", value_name = "DIR")]
+    export_landmarks: Option<String>,
+}
+
+struct GameResources {
+    keys: Vec<WindowInfoKey>,
+    repo: WindowInfoRepository,
+    ocr: Box<dyn ImageToText<RgbImage> + Send>,
+    landmarks: Vec<LandmarkDefinition>,
+}
+
+fn get_game_resources(game: Game) -> Result<GameResources> {
+    Ok(match game {
+        Game::Genshin => GameResources {
+            keys: [
+                ArtifactScannerWindowInfo::window_info_keys(),
+                GenshinRepositoryScanControllerWindowInfo::window_info_keys(),
+            ].concat(),
+            repo: ArtifactScannerApplication::get_window_info_repository(),
+            ocr: GenshinArtifactScanner::get_image_to_text(&OCRConfig::default())?,
+            landmarks: ArtifactScannerApplication::get_landmark_definitions(),
+        },
+        Game::Starrail => GameResources {
+            keys: [
+                RelicScannerWindowInfo::window_info_keys(),
+                StarRailRepositoryScanControllerWindowInfo::window_info_keys(),
+            ].concat(),
+            repo: RelicScannerApplication::get_window_info_repository(),
+            ocr: StarRailRelicScanner::get_image_to_text(&OCRConfig::default())?,
+            landmarks: RelicScannerApplication::get_landmark_definitions(),
+        },
+        Game::Ww => GameResources {
+            keys: [
+                EchoScannerWindowInfo::window_info_keys(),
+                WWRepositoryLayoutWindowinfo::window_info_keys(),
+            ].concat(),
+            repo: WWEchoScannerApplication::get_window_info_repository(),
+            ocr: WWEchoScanner::get_image_to_text(&OCRConfig::default())?,
+            landmarks: WWEchoScannerApplication::get_landmark_definitions(),
+        },
+    })
+}
+
+fn run(config: &CalibrateConfig) -> Result<()> {
+    let image = image::open(&config.screenshot)?.to_rgb8();
+    info!("截图大小: {}x{}", image.width(), image.height());
+
+    let GameResources { keys, mut repo, ocr, landmarks } = get_game_resources(config.game)?;
+    if let Some(path) = config.window_info.as_ref() {
+        repo.merge_inplace(&load_window_info_repo_from_path(path)?);
+    }
+
+    let ui = if config.mobile { UI::Mobile } else { UI::Desktop };
+    let platform = Platform::current();
+    let output = config.output.clone()
+        .unwrap_or(format!("windows{}x{}.json", image.width(), image.height()));
+
+    if let Some(dir) = config.export_landmarks.as_ref() {
+        LandmarkSet::export(&image, &landmarks, &repo, ui, platform)?.save(dir)?;
+        info!("地标模板已保存至 {}", dir);
+    }
+
+    let mut calibration = Calibration::new(image, &keys, &repo, ui, platform);
+    let ocr: Option<&dyn ImageToText<RgbImage>> = if config.no_ocr { None } else { Some(ocr.as_ref()) };
+    if let Some(ocr) = ocr {
+        calibration.run_ocr(ocr)?;
+    }
+    calibration.log_report();
+
+    calibration.render_overlay().save(&config.overlay)?;
+    info!("标注截图已保存至 {}", config.overlay);
+
+    let template = if config.no_interactive {
+        calibration.to_template(&repo)?
+    } else {
+        match interact(&mut calibration, &repo, ocr, &config.overlay)? {
+            Some(template) => template,
+            None => {
+                info!("已退出，未写出窗口信息");
+                return Ok(());
+            },
+        }
+    };
+    std::fs::write(&output, serde_json::to_string_pretty(&template)?)?;
+    info!("窗口信息已保存至 {}，修改后可使用 --window-info 再次校验", output);
+
+    Ok(())
+}
+
+/// Let the user adjust the entries until they confirm, None if they quit
+fn interact(
+    calibration: &mut Calibration,
+    repo: &WindowInfoRepository,
+    ocr: Option<&dyn ImageToText<RgbImage>>,
+    overlay: &str,
+) -> Result<Option<WindowInfoTemplatePerSize>> {
+    info!("输入 \"<编号> <数值>\" 调整区域，如 \"3 100,200,300,40\"（矩形为 左,上,宽,高，位置为 x,y）");
+    info!("输入 \"<编号>\" 查看，\"l\" 列出全部，回车确认并写出，\"q\" 退出");
+
+    loop {
+        print!("> ");
+        stdout().flush()?;
+        let mut line = String::new();
+        if stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "" => match calibration.to_template(repo) {
+                Ok(template) => return Ok(Some(template)),
+                Err(e) => warn!("{}，请先调整", e),
+            },
+            "q" => return Ok(None),
+            "l" => calibration.log_report(),
+            _ => {
+                let index = match command.parse::<usize>() {
+                    Ok(i) if i < calibration.entries().len() => i,
+                    _ => {
+                        warn!("无效的输入: {}", line);
+                        continue;
+                    },
+                };
+                if rest.trim().is_empty() {
+                    calibration.log_entry(index);
+                    continue;
+                }
+
+                let value = match parse_value(calibration.entries()[index].value, rest) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        warn!("{}", e);
+                        continue;
+                    },
+                };
+                calibration.set_value(index, value)?;
+                if let Some(ocr) = ocr {
+                    calibration.run_ocr_on(index, ocr)?;
+                }
+                calibration.log_entry(index);
+                calibration.render_overlay().save(overlay)?;
+            },
+        }
+    }
+}
+
+pub fn main() {
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    let mut command = command!();
+    command = <CalibrateConfig as Args>::augment_args_for_update(command);
+    let matches = command.get_matches();
+
+    if let Err(e) = CalibrateConfig::from_arg_matches(&matches).map_err(Into::into).and_then(|config| run(&config)) {
+        error!("error: {}", e);
+    }
+}
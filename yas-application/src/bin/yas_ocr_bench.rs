@@ -0,0 +1,168 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use clap::{command, Args, FromArgMatches, ValueEnum};
+use image::RgbImage;
+use log::{error, info, warn};
+
+use yas::ocr::{load_ocr_samples, ImageToText, OCRBackend, OCRConfig, OCRResult};
+use yas_genshin::scanner::GenshinArtifactScanner;
+use yas_starrail::scanner::relic_scanner::StarRailRelicScanner;
+use yas_wutheringwaves::scanner::WWEchoScanner;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum Game {
+    Genshin,
+    Starrail,
+    Ww,
+}
+
+#[derive(Clone, clap::Args)]
+struct BenchConfig {
+    #[arg(id = "game", long = "game", help = "使用该游戏的 OCR 模型", value_enum, default_value_t = Game::Genshin)]
+    game: Game,
+
+    #[arg(id = "ocr-model", long = "ocr-model", help = "OCR 模型：yas、ppocr，或 onnx 模型文件路径", value_name = "MODEL", default_value_t = String::from("yas"))]
+    model: String,
+
+    /// A directory of cropped text regions, e.g. the crops exported to `low_confidence/`,
+    /// the synthetic fixtures by default so that the bench can be reproduced
+    #[arg(id = "images", long = "images", help = "文字区域截图所在目录", value_name = "DIR", default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../yas/fixtures/ocr"))]
+    images: String,
+
+    #[arg(id = "batch-sizes", long = "batch-sizes", help = "要比较的批大小", value_name = "SIZES", value_delimiter = ',', default_values_t = vec![1, 4, 9, 16])]
+    batch_sizes: Vec<usize>,
+
+    #[arg(id = "ocr-threads", long = "ocr-threads", help = "OCR 推理线程数", value_name = "THREADS", default_value_t = yas::ocr::DEFAULT_INTRA_THREADS)]
+    intra_threads: usize,
+
+    #[arg(id = "rounds", long = "rounds", help = "每个批大小重复识别的轮数", value_name = "ROUNDS", default_value_t = 5)]
+    rounds: usize,
+
+    /// Recognize the images with both backends and fail on any different string
+    #[arg(id = "compare-backends", long = "compare-backends", help = "比较 ort 与 tract 后端的识别结果，不一致时报错")]
+    compare_backends: bool,
+
+    #[arg(id = "report", long = "report", help = "将测试结果以 Markdown 表格写入该文件", value_name = "FILE")]
+    report: Option<PathBuf>,
+}
+
+fn get_model(game: Game, config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    match game {
+        Game::Genshin => GenshinArtifactScanner::get_image_to_text(config),
+        Game::Starrail => StarRailRelicScanner::get_image_to_text(config),
+        Game::Ww => WWEchoScanner::get_image_to_text(config),
+    }
+}
+
+fn load_images(dir: &Path) -> Result<Vec<RgbImage>> {
+    Ok(load_ocr_samples(dir)?.into_iter().map(|(_, image)| image).collect())
+}
+
+fn recognize_all(model: &dyn ImageToText<RgbImage>, images: &[RgbImage], batch_size: usize) -> Result<Vec<OCRResult>> {
+    let inputs = images.iter().map(|im| (im, None)).collect::<Vec<_>>();
+    let mut results = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(batch_size.max(1)) {
+        results.extend(model.batch_image_to_text(batch, false)?);
+    }
+    Ok(results)
+}
+
+fn compare_backends(config: &BenchConfig, images: &[RgbImage]) -> Result<()> {
+    let mut results = Vec::new();
+    for backend in [OCRBackend::Ort, OCRBackend::Tract] {
+        let ocr_config = OCRConfig {
+            intra_threads: config.intra_threads,
+            backend: Some(backend),
+            model: config.model.clone(),
+            ..OCRConfig::default()
+        };
+        let model = get_model(config.game, &ocr_config)?;
+        results.push(recognize_all(model.as_ref(), images, 1)?);
+    }
+
+    let mut mismatches = 0;
+    for (index, (ort, tract)) in results[0].iter().zip(results[1].iter()).enumerate() {
+        if ort.text != tract.text {
+            mismatches += 1;
+            warn!("第 {} 张图片结果不一致: ort \"{}\"，tract \"{}\"", index, ort.text, tract.text);
+        }
+    }
+
+    if mismatches > 0 {
+        return Err(anyhow!("{} 张图片中有 {} 张在两个后端的识别结果不一致", images.len(), mismatches));
+    }
+    info!("{} 张图片在两个后端的识别结果一致", images.len());
+    Ok(())
+}
+
+fn run(config: &BenchConfig) -> Result<()> {
+    let images = load_images(Path::new(&config.images))?;
+    if images.is_empty() {
+        return Err(anyhow!("目录中没有图片: {}", config.images));
+    }
+    if config.compare_backends {
+        return compare_backends(config, &images);
+    }
+    info!("共 {} 张图片，每个批大小识别 {} 轮", images.len(), config.rounds);
+
+    let ocr_config = OCRConfig {
+        intra_threads: config.intra_threads,
+        model: config.model.clone(),
+        ..OCRConfig::default()
+    };
+    let model = get_model(config.game, &ocr_config)?;
+
+    let mut report = String::new();
+    writeln!(report, "game: {:?}, model: {}, backend: {:?}, threads: {}, images: {} x {} rounds\n",
+        config.game, config.model, ocr_config.backend(), config.intra_threads, images.len(), config.rounds.max(1))?;
+    writeln!(report, "| batch size | images/s | speedup | mismatches |")?;
+    writeln!(report, "|---:|---:|---:|---:|")?;
+
+    // warm up, and take the results as the reference of the other batch sizes
+    let reference = recognize_all(model.as_ref(), &images, 1)?;
+
+    let mut baseline = None;
+    for &batch_size in config.batch_sizes.iter() {
+        let start = Instant::now();
+        let mut results = Vec::new();
+        for _ in 0..config.rounds.max(1) {
+            results = recognize_all(model.as_ref(), &images, batch_size)?;
+        }
+        let elapsed = start.elapsed();
+
+        let throughput = (images.len() * config.rounds.max(1)) as f64 / elapsed.as_secs_f64();
+        let speedup = throughput / *baseline.get_or_insert(throughput);
+        info!("批大小 {:>3}: {:>8.1} 张/秒，{:.2} 倍", batch_size, throughput, speedup);
+
+        let mismatches = results.iter().zip(reference.iter()).filter(|(a, b)| a.text != b.text).count();
+        if mismatches > 0 {
+            warn!("批大小 {} 有 {} 个结果与逐个识别不一致", batch_size, mismatches);
+        }
+        writeln!(report, "| {} | {:.1} | {:.2} | {} |", batch_size, throughput, speedup, mismatches)?;
+    }
+
+    if let Some(path) = config.report.as_ref() {
+        std::fs::write(path, report)?;
+        info!("测试结果已写入 {}", path.display());
+    }
+    Ok(())
+}
+
+pub fn main() {
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    let mut command = command!();
+    command = <BenchConfig as Args>::augment_args_for_update(command);
+    let matches = command.get_matches();
+
+    let config = BenchConfig::from_arg_matches(&matches).unwrap();
+    if let Err(e) = run(&config) {
+        error!("error: {}", e);
+        std::process::exit(1);
+    }
+}
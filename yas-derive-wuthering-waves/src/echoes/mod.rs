@@ -1,3 +0,0 @@
-pub use echo_item::EchoDataItem;
-
-mod echo_item;
\ No newline at end of file
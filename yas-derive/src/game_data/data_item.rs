@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// One variant of a generated enum, e.g. an artifact set, a stat or a character
+#[derive(Deserialize)]
+pub struct DataItem {
+    /// the name of the variant
+    pub name: String,
+    /// language -> names shown in the game, the first one is the canonical name
+    #[serde(default)]
+    pub names: BTreeMap<String, Vec<String>>,
+    /// the chinese name in the older format of echoes.json, the same as `names["zh-cn"]`
+    #[serde(default)]
+    pub name_chs: Option<String>,
+    /// export format -> key, e.g. "good" -> "GladiatorsFinale"
+    #[serde(default)]
+    pub keys: BTreeMap<String, String>,
+    /// slot -> language -> names of the piece in the slot
+    #[serde(default)]
+    pub pieces: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    /// for stats which share the name of another one, whether the value is a percentage
+    #[serde(default)]
+    pub percentage: Option<bool>,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+impl DataItem {
+    pub fn names(&self) -> BTreeMap<String, Vec<String>> {
+        let mut names = self.names.clone();
+        if let Some(name_chs) = self.name_chs.as_ref() {
+            let zh_cn = names.entry(String::from("zh-cn")).or_default();
+            if !zh_cn.contains(name_chs) {
+                zh_cn.insert(0, name_chs.clone());
+            }
+        }
+        names
+    }
+}
@@ -0,0 +1,30 @@
+/// `#[game_data(pieces = ArtifactSlot, keys(good, mona))]`
+#[derive(Default)]
+pub struct GameDataAttributes {
+    /// the slot enum whose variants are the keys of `pieces`
+    pub pieces: Option<syn::Path>,
+    /// export formats which every item must have a key of
+    pub keys: Vec<syn::Ident>,
+}
+
+impl GameDataAttributes {
+    pub fn from_attr(attr: &syn::Attribute) -> syn::parse::Result<Self> {
+        let mut result: GameDataAttributes = Default::default();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("pieces") {
+                result.pieces = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("keys") {
+                return meta.parse_nested_meta(|format| {
+                    result.keys.push(format.path.require_ident()?.clone());
+                    Ok(())
+                });
+            }
+
+            Err(meta.error("unrecognized game_data"))
+        })?;
+
+        Ok(result)
+    }
+}
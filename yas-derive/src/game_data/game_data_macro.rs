@@ -0,0 +1,338 @@
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Token};
+
+use crate::game_data::{DataItem, GameDataAttributes};
+
+/// `#[attrs] pub enum Name = "data/file.json";`, the path is relative to the crate
+struct GameDataInput {
+    attrs: Vec<syn::Attribute>,
+    game_data: GameDataAttributes,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    path: syn::LitStr,
+}
+
+impl Parse for GameDataInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = Vec::new();
+        let mut game_data = GameDataAttributes::default();
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            if attr.path().is_ident("game_data") {
+                game_data = GameDataAttributes::from_attr(&attr)?;
+            } else {
+                attrs.push(attr);
+            }
+        }
+
+        let vis = input.parse()?;
+        let _enum: Token![enum] = input.parse()?;
+        let ident = input.parse()?;
+        let _eq: Token![=] = input.parse()?;
+        let path = input.parse()?;
+        let _semi: Token![;] = input.parse()?;
+
+        Ok(GameDataInput { attrs, game_data, vis, ident, path })
+    }
+}
+
+/// e.g. "zh-cn" -> NAMES_ZH_CN
+fn language_const(prefix: &str, lang: &str) -> syn::Ident {
+    format_ident!("{}_{}", prefix, lang.to_uppercase().replace('-', "_"))
+}
+
+fn variant(item: &DataItem) -> syn::Ident {
+    syn::Ident::new(&item.name, Span::call_site())
+}
+
+/// all names of a language without duplicates, in the order of the data
+fn names_of_language<'a>(names: impl Iterator<Item = (&'a String, &'a Vec<String>)>, lang: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for (_, name) in names.filter(|(l, _)| l.as_str() == lang) {
+        for n in name.iter() {
+            if !result.contains(n) {
+                result.push(n.clone());
+            }
+        }
+    }
+    result
+}
+
+/// constants of all names of each language, and a function to get them by language
+fn names_lookup(prefix: &str, function: &syn::Ident, languages: &BTreeSet<String>, names: &[(String, Vec<String>)]) -> TokenStream {
+    let mut consts = Vec::new();
+    let mut arms = Vec::new();
+    for lang in languages.iter() {
+        let const_name = language_const(prefix, lang);
+        let lang_names = names_of_language(names.iter().map(|(l, n)| (l, n)), lang);
+        consts.push(quote! {
+            pub const #const_name: &'static [&'static str] = &[#(#lang_names),*];
+        });
+        arms.push(quote! { #lang => Self::#const_name, });
+    }
+
+    quote! {
+        #(#consts)*
+
+        pub fn #function(lang: &str) -> &'static [&'static str] {
+            match lang {
+                #(#arms)*
+                _ => &[],
+            }
+        }
+    }
+}
+
+fn name_impl(data: &[DataItem], languages: &BTreeSet<String>) -> TokenStream {
+    let mut all_names = Vec::new();
+    let mut from_name_arms = Vec::new();
+    let mut seen = HashSet::new();
+    let mut name_arms = Vec::new();
+    for item in data.iter() {
+        let v = variant(item);
+        for (lang, names) in item.names().into_iter() {
+            for name in names.iter() {
+                // the first item wins if a name is shared, e.g. flat and percentage stats
+                if seen.insert((lang.clone(), name.clone())) {
+                    from_name_arms.push(quote! { (#lang, #name) => Some(Self::#v), });
+                }
+            }
+            if let Some(first) = names.first() {
+                name_arms.push(quote! { (Self::#v, #lang) => Some(#first), });
+            }
+            all_names.push((lang, names));
+        }
+    }
+
+    let names_of = names_lookup("NAMES", &format_ident!("names_of"), languages, &all_names);
+
+    quote! {
+        #names_of
+
+        pub fn from_name(lang: &str, name: &str) -> Option<Self> {
+            match (lang, name) {
+                #(#from_name_arms)*
+                _ => None,
+            }
+        }
+
+        /// The canonical name in the language
+        pub fn name(&self, lang: &str) -> Option<&'static str> {
+            match (self, lang) {
+                #(#name_arms)*
+                _ => None,
+            }
+        }
+    }
+}
+
+fn stat_name_impl(data: &[DataItem]) -> TokenStream {
+    if data.iter().all(|item| item.percentage.is_none()) {
+        return quote! {};
+    }
+
+    let mut arms = Vec::new();
+    let mut seen = HashSet::new();
+    for item in data.iter() {
+        let v = variant(item);
+        for (lang, names) in item.names().into_iter() {
+            for name in names.iter() {
+                if !seen.insert((lang.clone(), name.clone(), item.percentage)) {
+                    continue;
+                }
+                let percentage = match item.percentage {
+                    Some(p) => quote! { #p },
+                    None => quote! { _ },
+                };
+                arms.push(quote! { (#lang, #name, #percentage) => Some(Self::#v), });
+            }
+        }
+    }
+
+    quote! {
+        /// Stats such as ATK and ATK% share the name, and are told apart by whether the value is a percentage
+        pub fn from_stat_name(lang: &str, name: &str, is_percentage: bool) -> Option<Self> {
+            match (lang, name, is_percentage) {
+                #(#arms)*
+                _ => None,
+            }
+        }
+    }
+}
+
+fn piece_impl(data: &[DataItem], slot: Option<&syn::Path>) -> syn::Result<TokenStream> {
+    let slot = match slot {
+        Some(v) => v,
+        None => {
+            if let Some(item) = data.iter().find(|item| !item.pieces.is_empty()) {
+                let message = format!("{} has pieces but the slot enum is not given by #[game_data(pieces = ...)]", item.name);
+                return Err(syn::Error::new(Span::call_site(), message));
+            }
+            return Ok(quote! {});
+        }
+    };
+
+    let mut languages = BTreeSet::new();
+    let mut all_names = Vec::new();
+    let mut arms = Vec::new();
+    let mut seen = HashSet::new();
+    for item in data.iter() {
+        let v = variant(item);
+        for (slot_name, pieces) in item.pieces.iter() {
+            let slot_variant = syn::Ident::new(slot_name, Span::call_site());
+            for (lang, names) in pieces.iter() {
+                languages.insert(lang.clone());
+                for name in names.iter() {
+                    if seen.insert((lang.clone(), name.clone())) {
+                        arms.push(quote! { (#lang, #name) => Some((Self::#v, #slot::#slot_variant)), });
+                    }
+                }
+                all_names.push((lang.clone(), names.clone()));
+            }
+        }
+    }
+
+    let piece_names_of = names_lookup("PIECE_NAMES", &format_ident!("piece_names_of"), &languages, &all_names);
+
+    Ok(quote! {
+        #piece_names_of
+
+        /// The set and the slot of a piece by its name
+        pub fn from_piece_name(lang: &str, name: &str) -> Option<(Self, #slot)> {
+            match (lang, name) {
+                #(#arms)*
+                _ => None,
+            }
+        }
+    })
+}
+
+fn key_impl(data: &[DataItem], required: &[syn::Ident]) -> syn::Result<TokenStream> {
+    let mut key_arms = Vec::new();
+    let mut from_key_arms = Vec::new();
+    let mut seen = HashSet::new();
+    for item in data.iter() {
+        let v = variant(item);
+        for (format, key) in item.keys.iter() {
+            key_arms.push(quote! { (Self::#v, #format) => Some(#key), });
+            if seen.insert((format.clone(), key.clone())) {
+                from_key_arms.push(quote! { (#format, #key) => Some(Self::#v), });
+            }
+        }
+    }
+
+    let mut required_functions = Vec::new();
+    for format in required.iter() {
+        let format_name = format.to_string();
+        let mut arms = Vec::new();
+        for item in data.iter() {
+            let v = variant(item);
+            let key = item.keys.get(&format_name).ok_or_else(|| {
+                syn::Error::new(format.span(), format!("{} has no {} key", item.name, format_name))
+            })?;
+            arms.push(quote! { Self::#v => #key, });
+        }
+
+        let function = format_ident!("{}_key", format);
+        required_functions.push(quote! {
+            pub fn #function(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        /// The key in an export format, e.g. "good"
+        pub fn key(&self, format: &str) -> Option<&'static str> {
+            match (self, format) {
+                #(#key_arms)*
+                _ => None,
+            }
+        }
+
+        pub fn from_key(format: &str, key: &str) -> Option<Self> {
+            match (format, key) {
+                #(#from_key_arms)*
+                _ => None,
+            }
+        }
+
+        #(#required_functions)*
+    })
+}
+
+fn color_impl(data: &[DataItem]) -> TokenStream {
+    let colors = data.iter()
+        .filter_map(|item| {
+            let v = variant(item);
+            item.color.map(|[r, g, b]| quote! { (Self::#v, [#r, #g, #b]) })
+        })
+        .collect::<Vec<_>>();
+    if colors.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        /// The known colors of the items which have one
+        pub const COLORS: &'static [(Self, [u8; 3])] = &[#(#colors),*];
+    }
+}
+
+fn expand(input: GameDataInput) -> syn::Result<TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(manifest_dir).join(input.path.value());
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| syn::Error::new_spanned(&input.path, format!("cannot read {}: {}", path.display(), e)))?;
+    let data: Vec<DataItem> = serde_json::from_str(&content)
+        .map_err(|e| syn::Error::new_spanned(&input.path, format!("cannot parse {}: {}", path.display(), e)))?;
+
+    let languages = data.iter()
+        .flat_map(|item| item.names().into_keys())
+        .collect::<BTreeSet<_>>();
+
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let variants = data.iter().map(variant).collect::<Vec<_>>();
+    let name_impl = name_impl(&data, &languages);
+    let stat_name_impl = stat_name_impl(&data);
+    let piece_impl = piece_impl(&data, input.game_data.pieces.as_ref())?;
+    let key_impl = key_impl(&data, &input.game_data.keys)?;
+    let color_impl = color_impl(&data);
+    // rebuild when the data changes
+    let path_string = path.display().to_string();
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis enum #ident {
+            #(#variants),*
+        }
+
+        const _: &[u8] = include_bytes!(#path_string);
+
+        impl #ident {
+            pub const ALL: &'static [Self] = &[#(Self::#variants),*];
+
+            #name_impl
+            #stat_name_impl
+            #piece_impl
+            #key_impl
+            #color_impl
+        }
+    })
+}
+
+pub fn yas_game_data(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as GameDataInput);
+
+    match expand(input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
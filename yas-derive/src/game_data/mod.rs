@@ -0,0 +1,7 @@
+mod data_item;
+mod game_data_macro;
+mod game_data_attributes;
+
+pub use data_item::DataItem;
+pub use game_data_attributes::GameDataAttributes;
+pub use game_data_macro::yas_game_data;
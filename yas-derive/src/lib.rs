@@ -1,6 +1,13 @@
 extern crate proc_macro;
+mod game_data;
 mod window_info;
 #[proc_macro_derive(YasWindowInfo, attributes(window_info))]
 pub fn yas_window_info(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     window_info::yas_window_info(input)
 }
+
+/// Generates an enum along with its names, pieces, export keys and colors from a json file of the crate
+#[proc_macro]
+pub fn yas_game_data(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    game_data::yas_game_data(input)
+}
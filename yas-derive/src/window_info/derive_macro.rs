@@ -2,12 +2,22 @@ use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 use crate::window_info::WindowInfoNestedAttributes;
 
+fn is_option_type(ty: &syn::Type) -> bool {
+    if let syn::Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            return segment.ident == "Option";
+        }
+    }
+    false
+}
+
 pub fn yas_window_info(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = &input.ident;
 
     if let syn::Data::Struct(data_struct) = &input.data {
         let mut fields = Vec::new();
+        let mut keys = Vec::new();
         for field in data_struct.fields.iter() {
             let name = field.ident.as_ref().unwrap();
 
@@ -21,10 +31,23 @@ pub fn yas_window_info(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
                 }
             }
 
+            let optional = is_option_type(&field.ty);
+            keys.push(quote! {
+                yas::window_info::WindowInfoKey { name: #window_info_key, optional: #optional }
+            });
+
+            // optional fields are left as None if the key does not exist
+            if optional {
+                fields.push(quote! {
+                    #name: repo.get_auto_scale(#window_info_key, window_size, ui, platform)
+                });
+                continue;
+            }
+
             fields.push(quote! {
                 #name: match repo.get_auto_scale(#window_info_key, window_size, ui, platform) {
                     None => {
-                        return Err(anyhow::anyhow!("cannot find window info key \"{}\"", #window_info_key));
+                        return Err(anyhow::anyhow!(repo.describe_missing(#window_info_key, window_size, ui, platform)));
                     },
                     Some(value) => value
                 }
@@ -44,6 +67,12 @@ pub fn yas_window_info(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
                     })
                 }
             }
+
+            impl yas::window_info::WindowInfoKeys for #struct_name {
+                fn window_info_keys() -> Vec<yas::window_info::WindowInfoKey> {
+                    vec![#(#keys),*]
+                }
+            }
         };
 
         return trait_impl.into();
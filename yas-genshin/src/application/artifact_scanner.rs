@@ -1,14 +1,19 @@
 use anyhow::Result;
 use anyhow::anyhow;
-use clap::{command, ArgMatches, Args};
+use clap::{command, ArgMatches, Args, FromArgMatches};
 use log::info;
 
-use yas::export::{AssetEmitter, ExportAssets};
+use yas::export::{AssetEmitter, ExportAssets, RejectedItems};
+use yas::auto_layout::{LandmarkCrop, LandmarkDefinition};
+use yas::correction::CorrectionConfig;
 use yas::game_info::{GameInfo, GameInfoBuilder};
-use yas::window_info::{load_window_info_repo, WindowInfoRepository};
+use yas::rating::{RatingConfig, RatingReport};
+use yas::validation::ValidationConfig;
+use yas::window_info::{load_window_info_repo, WindowInfoConfig, WindowInfoRepository};
 
-use crate::artifact::GenshinArtifact;
+use crate::artifact::{GenshinArtifact, GenshinArtifactCorrector, GenshinArtifactValidator};
 use crate::export::artifact::{ExportArtifactConfig, GenshinArtifactExporter};
+use crate::level_up::{LevelUpConfig, LevelUpReport};
 use crate::scanner::{GenshinArtifactScanner, GenshinArtifactScannerConfig};
 use crate::scanner_controller::repository_layout::GenshinRepositoryScannerLogicConfig;
 
@@ -28,6 +33,11 @@ impl ArtifactScannerApplication {
         cmd = <ExportArtifactConfig as Args>::augment_args_for_update(cmd);
         cmd = <GenshinArtifactScannerConfig as Args>::augment_args_for_update(cmd);
         cmd = <GenshinRepositoryScannerLogicConfig as Args>::augment_args_for_update(cmd);
+        cmd = <CorrectionConfig as Args>::augment_args_for_update(cmd);
+        cmd = <ValidationConfig as Args>::augment_args_for_update(cmd);
+        cmd = <RatingConfig as Args>::augment_args_for_update(cmd);
+        cmd = <LevelUpConfig as Args>::augment_args_for_update(cmd);
+        cmd = <WindowInfoConfig as Args>::augment_args_for_update(cmd);
         cmd
     }
 
@@ -41,12 +51,59 @@ impl ArtifactScannerApplication {
         )
     }
 
+    /// The built-in window info, overridden by `--window-info` and `--auto-layout` if given
+    pub fn load_window_info_repository(arg_matches: &ArgMatches, game_info: &GameInfo) -> Result<WindowInfoRepository> {
+        let config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        let mut repo = config.load_repository(Self::get_window_info_repository())?;
+        config.apply_auto_layout(&mut repo, game_info)?;
+        Ok(repo)
+    }
+
     // fn init() {
     //     env_logger::Builder::new()
     //         .filter_level(log::LevelFilter::Info)
     //         .init();
     // }
 
+    /// Landmarks for `--auto-layout`, the grid and the panel move independently when the aspect ratio changes
+    pub fn get_landmark_definitions() -> Vec<LandmarkDefinition> {
+        vec![
+            LandmarkDefinition {
+                name: "count_label",
+                crop: LandmarkCrop::Rect("genshin_artifact_item_count_rect"),
+                keys: &["genshin_artifact_item_count_rect"],
+            },
+            LandmarkDefinition {
+                name: "grid_first_cell",
+                crop: LandmarkCrop::Around("genshin_repository_scan_margin_pos", 40.0),
+                keys: &["genshin_repository_scan_margin_pos", "genshin_repository_flag_pos"],
+            },
+            LandmarkDefinition {
+                name: "panel",
+                crop: LandmarkCrop::Rect("genshin_repository_panel_rect"),
+                keys: &[
+                    "genshin_repository_panel_rect",
+                    "genshin_repository_pool_rect",
+                    "genshin_artifact_star_pos",
+                    "genshin_artifact_title_rect",
+                    "genshin_artifact_main_stat_name_rect",
+                    "genshin_artifact_main_stat_value_rect",
+                    "genshin_artifact_sub_stat1_rect",
+                    "genshin_artifact_sub_stat2_rect",
+                    "genshin_artifact_sub_stat3_rect",
+                    "genshin_artifact_sub_stat4_rect",
+                    "genshin_artifact_level_rect",
+                    "genshin_artifact_item_equip_rect",
+                ],
+            },
+            LandmarkDefinition {
+                name: "lock_icon",
+                crop: LandmarkCrop::Around("genshin_artifact_lock_pos", 16.0),
+                keys: &["genshin_artifact_lock_pos"],
+            },
+        ]
+    }
+
     pub fn get_game_info() -> Result<GameInfo> {
         let game_info = GameInfoBuilder::new()
             .add_local_window_name("原神")
@@ -60,13 +117,23 @@ impl ArtifactScannerApplication {
 impl ArtifactScannerApplication {
     pub fn run(&self) -> Result<()> {
         let arg_matches = &self.arg_matches;
-        let window_info_repository = Self::get_window_info_repository();
         let game_info = Self::get_game_info()?;
+        let window_info_repository = Self::load_window_info_repository(arg_matches, &game_info)?;
 
         info!("window: {:?}", game_info.window);
         info!("ui: {:?}", game_info.ui);
         info!("cloud: {}", game_info.is_cloud);
         info!("resolution family: {:?}", game_info.resolution_family);
+        window_info_repository.log_resolution_summary(
+            game_info.window.to_rect_usize().size(),
+            game_info.ui,
+            game_info.platform,
+        );
+
+        let window_info_config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        if window_info_config.dump_if_required(&window_info_repository, &game_info)? {
+            return Ok(());
+        }
 
         #[cfg(target_os = "windows")]
         {
@@ -82,15 +149,60 @@ impl ArtifactScannerApplication {
             game_info.clone()
         )?;
 
-        let result = scanner.scan()?;
-        let artifacts = result
-            .iter()
-            .flat_map(GenshinArtifact::try_from)
-            .collect::<Vec<_>>();
+        let mut result = scanner.scan()?;
 
-        let exporter = GenshinArtifactExporter::new(arg_matches, &artifacts)?;
+        let correction_config = CorrectionConfig::from_arg_matches(arg_matches)?;
+        let game_language = scanner.game_language();
+        let mut corrector = GenshinArtifactCorrector::with_language(correction_config.max_distance, game_language);
+        for (index, item) in result.iter_mut().enumerate() {
+            corrector.correct(index, item);
+        }
+        corrector.report().log();
+
+        let validation_config = ValidationConfig::from_arg_matches(arg_matches)?;
+        let mut validator = GenshinArtifactValidator::new(!validation_config.no_autocorrect);
+        let mut artifacts = Vec::new();
+        let mut positions = Vec::new();
+        let mut scan_indices = Vec::new();
+        let mut rejected = RejectedItems::new();
+        for (index, item) in result.iter().enumerate() {
+            match GenshinArtifact::from_scan_result(item, game_language) {
+                Ok((mut v, equip_error)) => {
+                    if let Some(e) = equip_error {
+                        rejected.push_dropped_field(index, e);
+                    }
+                    if !validation_config.no_validation {
+                        validator.validate(index, &mut v);
+                    }
+                    artifacts.push(v);
+                    positions.push(scanner.item_positions()[index]);
+                    scan_indices.push(index);
+                },
+                Err(e) => rejected.push(index, e),
+            }
+        }
+        validator.report().log();
+
+        let exporter = GenshinArtifactExporter::new(arg_matches, &artifacts)?
+            .with_corrections(corrector.report(), &scan_indices);
         let mut export_assets = ExportAssets::new();
         exporter.emit(&mut export_assets);
+        scanner.low_confidence_report().emit_to(&exporter.output_dir, &mut export_assets);
+        corrector.report().emit_to(&exporter.output_dir, &mut export_assets);
+        validator.report().emit_to(&exporter.output_dir, &mut export_assets);
+        if exporter.output_rejected {
+            rejected.emit_to(&exporter.output_dir, &mut export_assets);
+        }
+        let rating_config = RatingConfig::from_arg_matches(arg_matches)?;
+        let rating_report = RatingReport::new(&exporter.profiles, &artifacts, rating_config.top);
+        rating_report.emit_to(&exporter.output_dir, &mut export_assets);
+        let level_up_config = LevelUpConfig::from_arg_matches(arg_matches)?;
+        let level_up_report = if level_up_config.simulate {
+            LevelUpReport::new(&artifacts, &positions, &exporter.profiles, &level_up_config)
+        } else {
+            LevelUpReport::default()
+        };
+        level_up_report.emit_to(&exporter.output_dir, level_up_config.output_lock_plan, &mut export_assets);
 
         let stats = export_assets.save();
         info!("保存结果：");
@@ -100,6 +212,9 @@ impl ArtifactScannerApplication {
             info!("{}", line);
         }
         info!("Yas 识别结束，共识别到 {} 件圣遗物。", result.len());
+        rejected.log();
+        rating_report.log();
+        level_up_report.log();
 
         Ok(())
     }
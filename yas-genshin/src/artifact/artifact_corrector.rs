@@ -0,0 +1,60 @@
+use yas::correction::{CorrectionReport, Dictionary, TextCorrector};
+use yas::game_language::GameLanguage;
+
+use crate::artifact::{artifact_names, character_names, equip_label, stat_names, strip_equip_label, sub_stat_names};
+use crate::scanner::GenshinArtifactScanResult;
+
+/// Snaps the names in artifact scan results to the known ones, before they are converted to artifacts
+pub struct GenshinArtifactCorrector {
+    corrector: TextCorrector,
+    titles: Dictionary,
+    stat_names: Dictionary,
+    sub_stat_names: Dictionary,
+    characters: Dictionary,
+    language: GameLanguage,
+}
+
+impl GenshinArtifactCorrector {
+    pub fn new(max_distance: usize) -> GenshinArtifactCorrector {
+        GenshinArtifactCorrector::with_language(max_distance, GameLanguage::ZhCn)
+    }
+
+    /// The names are of the game client in `language`
+    pub fn with_language(max_distance: usize, language: GameLanguage) -> GenshinArtifactCorrector {
+        GenshinArtifactCorrector {
+            corrector: TextCorrector::new(max_distance),
+            titles: Dictionary::new(artifact_names(language)),
+            stat_names: Dictionary::new(stat_names(language)),
+            sub_stat_names: Dictionary::new(sub_stat_names(language)),
+            characters: Dictionary::new(character_names(language)),
+            language,
+        }
+    }
+
+    /// `index` is the position of the result in the scan results
+    pub fn correct(&mut self, index: usize, result: &mut GenshinArtifactScanResult) {
+        let corrector = &mut self.corrector;
+
+        result.name = corrector.correct(index, "title", &result.name, &self.titles);
+        result.main_stat_name = corrector.correct(index, "main_stat_name", &result.main_stat_name, &self.stat_names);
+
+        // e.g. "暴击率+3.9%", only the name is corrected
+        for (i, sub_stat) in result.sub_stat.iter_mut().enumerate() {
+            if let Some((name, value)) = sub_stat.split_once('+') {
+                let field = format!("sub_stat_{}", i + 1);
+                let name = corrector.correct(index, &field, name, &self.sub_stat_names);
+                *sub_stat = format!("{}+{}", name, value);
+            }
+        }
+
+        if let Some(name) = strip_equip_label(&result.equip, self.language) {
+            let name = corrector.correct(index, "equip", name, &self.characters);
+            let (prefix, suffix) = equip_label(self.language);
+            result.equip = format!("{}{}{}", prefix, name, suffix);
+        }
+    }
+
+    pub fn report(&self) -> &CorrectionReport {
+        &self.corrector.report
+    }
+}
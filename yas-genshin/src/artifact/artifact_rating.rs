@@ -0,0 +1,49 @@
+use yas::rating::Rateable;
+
+use crate::artifact::{ArtifactStat, ArtifactStatName, GenshinArtifact};
+
+impl GenshinArtifact {
+    /// The sub stats which add to the stats, i.e. without the one not activated yet
+    fn activated_sub_stats(&self) -> impl Iterator<Item = &ArtifactStat> {
+        [&self.sub_stat_1, &self.sub_stat_2, &self.sub_stat_3, &self.sub_stat_4]
+            .into_iter()
+            .flatten()
+            .filter(|stat| stat.activated)
+    }
+}
+
+/// The keys in weight profiles are those of GOOD, e.g. `critRate_`, `atk`
+/// the rolls are counted in the highest rolls of 5 star artifacts, so that artifacts of any rarity compare
+impl Rateable for GenshinArtifact {
+    fn rated_stats(&self) -> Vec<(String, f64)> {
+        self.activated_sub_stats()
+            .filter_map(|stat| {
+                let highest = stat.name.sub_stat_tiers(5)?[3];
+                Some((String::from(stat.name.to_good()), stat.value / highest))
+            })
+            .collect()
+    }
+
+    fn stat_keys() -> Vec<String> {
+        ArtifactStatName::ALL.iter().map(|name| String::from(name.to_good())).collect()
+    }
+
+    fn crit_value(&self) -> f64 {
+        let value = self.activated_sub_stats()
+            .map(|stat| match stat.name {
+                ArtifactStatName::Critical => stat.value * 2.0,
+                ArtifactStatName::CriticalDamage => stat.value,
+                _ => 0.0,
+            })
+            .sum::<f64>();
+        value * 100.0
+    }
+
+    fn describe(&self) -> String {
+        let mut s = format!("{} {} {}星 +{}", self.set_name, self.slot, self.star, self.level);
+        if let Some(equip) = &self.equip {
+            s = s + " " + equip;
+        }
+        s
+    }
+}
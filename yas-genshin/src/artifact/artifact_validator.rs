@@ -0,0 +1,157 @@
+use yas::validation::{StatValidator, StatValues, ValidationReport};
+
+use crate::artifact::{ArtifactRollAnalysis, GenshinArtifact};
+
+/// Checks the stat values of artifacts against the main stat tables and the sub stat rolls
+pub struct GenshinArtifactValidator {
+    validator: StatValidator,
+}
+
+impl GenshinArtifactValidator {
+    pub fn new(autocorrect: bool) -> GenshinArtifactValidator {
+        GenshinArtifactValidator {
+            validator: StatValidator::new(autocorrect),
+        }
+    }
+
+    /// `index` is the position of the artifact in the scan results
+    pub fn validate(&mut self, index: usize, artifact: &mut GenshinArtifact) {
+        let star = artifact.star;
+        let level = artifact.level;
+
+        let sub_stats = [
+            &mut artifact.sub_stat_1,
+            &mut artifact.sub_stat_2,
+            &mut artifact.sub_stat_3,
+            &mut artifact.sub_stat_4,
+        ];
+        for (i, sub_stat) in sub_stats.into_iter().enumerate() {
+            let stat = match sub_stat {
+                Some(v) => v,
+                None => continue,
+            };
+            if let Some(values) = stat.name.sub_stat_values(star, level) {
+                stat.value = self.validator.validate(
+                    index,
+                    &format!("sub_stat_{}", i + 1),
+                    &stat.name.to_string(),
+                    stat.value,
+                    &values,
+                    stat.name.display_unit(),
+                );
+            }
+        }
+
+        self.validate_main_stat(index, artifact);
+    }
+
+    /// The main stat is snapped to the table only when the level is trusted,
+    /// that is the sub stat rolls agree with it and the value is not that of another level,
+    /// otherwise the level may be the misreading, and the value is only flagged
+    fn validate_main_stat(&mut self, index: usize, artifact: &mut GenshinArtifact) {
+        let name = artifact.main_stat.name.clone();
+        let value = artifact.main_stat.value;
+        let expected = match name.main_stat_value(artifact.star, artifact.level) {
+            Some(v) => v,
+            None => return,
+        };
+        let levels = name.main_stat_levels(artifact.star, value);
+        if levels.contains(&artifact.level) {
+            return;
+        }
+
+        // every activated sub stat is made up of rolls which add up to the level
+        let analysis = ArtifactRollAnalysis::analyze(artifact);
+        let sub_stats = [&artifact.sub_stat_1, &artifact.sub_stat_2, &artifact.sub_stat_3, &artifact.sub_stat_4];
+        let rolls_agree = analysis.sub_stats.iter().any(|s| s.is_some())
+            && sub_stats.iter().zip(analysis.sub_stats.iter())
+                .all(|(stat, rolls)| rolls.is_some() || !stat.as_ref().is_some_and(|s| s.activated));
+        let trusted = rolls_agree && levels.is_empty();
+        let tolerance = name.display_unit() * 1.01;
+        if trusted {
+            artifact.main_stat.value = self.validator.validate(
+                index,
+                "main_stat",
+                &name.to_string(),
+                value,
+                &StatValues::Discrete(vec![expected]),
+                tolerance,
+            );
+        } else {
+            self.validator.flag(index, "main_stat", &name.to_string(), value);
+        }
+    }
+
+    pub fn report(&self) -> &ValidationReport {
+        &self.validator.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{ArtifactSetName, ArtifactSlot, ArtifactStat, ArtifactStatName};
+
+    fn stat(name: ArtifactStatName, value: f64) -> Option<ArtifactStat> {
+        Some(ArtifactStat { name, value, activated: true })
+    }
+
+    fn artifact(main_stat_value: f64, crit_rate: f64) -> GenshinArtifact {
+        GenshinArtifact {
+            set_name: ArtifactSetName::ArchaicPetra,
+            slot: ArtifactSlot::Flower,
+            star: 5,
+            lock: false,
+            level: 0,
+            main_stat: ArtifactStat { name: ArtifactStatName::Hp, value: main_stat_value, activated: true },
+            sub_stat_1: stat(ArtifactStatName::Critical, crit_rate),
+            sub_stat_2: stat(ArtifactStatName::CriticalDamage, 0.078),
+            sub_stat_3: stat(ArtifactStatName::AtkPercentage, 0.058),
+            sub_stat_4: stat(ArtifactStatName::Def, 23.0),
+            equip: None,
+        }
+    }
+
+    #[test]
+    fn test_main_stat_in_table() {
+        let mut validator = GenshinArtifactValidator::new(true);
+        let mut a = artifact(717.0, 0.039);
+        validator.validate(0, &mut a);
+
+        assert_eq!(a.main_stat.value, 717.0);
+        assert!(validator.report().items.is_empty());
+    }
+
+    #[test]
+    fn test_main_stat_snapped_when_level_trusted() {
+        let mut validator = GenshinArtifactValidator::new(true);
+        let mut a = artifact(711.0, 0.039);
+        validator.validate(0, &mut a);
+
+        assert_eq!(a.main_stat.value, 717.0);
+        assert_eq!(validator.report().items[0].corrected, Some(717.0));
+    }
+
+    #[test]
+    fn test_main_stat_of_another_level() {
+        let mut validator = GenshinArtifactValidator::new(true);
+        // the value of level 1, the level may be the misreading
+        let mut a = artifact(920.0, 0.039);
+        validator.validate(0, &mut a);
+
+        assert_eq!(a.main_stat.value, 920.0);
+        assert_eq!(validator.report().items[0].corrected, None);
+    }
+
+    #[test]
+    fn test_main_stat_flagged_when_rolls_disagree() {
+        let mut validator = GenshinArtifactValidator::new(true);
+        // two rolls of crit rate cannot be at level 0, so crit rate cannot be decomposed
+        let mut a = artifact(711.0, 0.078);
+        validator.validate(0, &mut a);
+
+        assert_eq!(a.main_stat.value, 711.0);
+        let issue = validator.report().items.iter().find(|v| v.field == "main_stat").unwrap();
+        assert_eq!(issue.corrected, None);
+    }
+}
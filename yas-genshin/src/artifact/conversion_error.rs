@@ -0,0 +1,37 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Why a scanned artifact cannot be converted, with the recognized text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArtifactConversionError {
+    UnknownSetTitle {
+        title: String,
+    },
+    BadMainStat {
+        name: String,
+        value: String,
+    },
+    MalformedSubStat {
+        /// starting from 1
+        index: usize,
+        text: String,
+    },
+    UnknownEquip {
+        equip: String,
+    },
+}
+
+impl fmt::Display for ArtifactConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactConversionError::UnknownSetTitle { title } => write!(f, "未知的圣遗物名称 \"{}\"", title),
+            ArtifactConversionError::BadMainStat { name, value } => write!(f, "无法解析主词条 \"{}\" \"{}\"", name, value),
+            ArtifactConversionError::MalformedSubStat { index, text } => write!(f, "无法解析第 {} 个副词条 \"{}\"", index, text),
+            ArtifactConversionError::UnknownEquip { equip } => write!(f, "未知的装备角色 \"{}\"", equip),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactConversionError {}
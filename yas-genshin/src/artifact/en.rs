@@ -0,0 +1,19 @@
+use crate::artifact::{ArtifactSetName, ArtifactStatName};
+
+/// Names of all artifacts of the English client
+pub const ARTIFACT_NAMES_EN: &[&str] = ArtifactSetName::PIECE_NAMES_EN;
+
+/// Names of all stats of the English client
+pub const STAT_NAMES_EN: &[&str] = ArtifactStatName::NAMES_EN;
+
+#[rustfmt::skip]
+pub const SUB_STAT_NAMES_EN: &[&str] = &[
+    "CRIT Rate", "CRIT DMG", "ATK", "HP",
+    "DEF", "Elemental Mastery", "Energy Recharge",
+];
+
+/// The slots shown under the title
+#[rustfmt::skip]
+pub const SLOT_NAMES_EN: &[&str] = &[
+    "Flower of Life", "Plume of Death", "Sands of Eon", "Goblet of Eonothem", "Circlet of Logos",
+];
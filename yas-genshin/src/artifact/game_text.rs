@@ -0,0 +1,86 @@
+use yas::game_language::GameLanguage;
+
+use crate::artifact::{
+    ARTIFACT_NAMES_EN, ARTIFACT_NAMES_ZH_CN, SLOT_NAMES_EN, STAT_NAMES_EN, STAT_NAMES_ZH_CN, SUB_STAT_NAMES_EN,
+    SUB_STAT_NAMES_ZH_CN, UNACTIVATED_MARKS, UNACTIVATED_MARKS_EN,
+};
+use crate::character::GenshinCharacter;
+
+pub fn artifact_names(language: GameLanguage) -> &'static [&'static str] {
+    match language {
+        GameLanguage::ZhCn => ARTIFACT_NAMES_ZH_CN,
+        GameLanguage::En => ARTIFACT_NAMES_EN,
+    }
+}
+
+pub fn stat_names(language: GameLanguage) -> &'static [&'static str] {
+    match language {
+        GameLanguage::ZhCn => STAT_NAMES_ZH_CN,
+        GameLanguage::En => STAT_NAMES_EN,
+    }
+}
+
+pub fn sub_stat_names(language: GameLanguage) -> &'static [&'static str] {
+    match language {
+        GameLanguage::ZhCn => SUB_STAT_NAMES_ZH_CN,
+        GameLanguage::En => SUB_STAT_NAMES_EN,
+    }
+}
+
+pub fn character_names(language: GameLanguage) -> &'static [&'static str] {
+    GenshinCharacter::names_of(language.code())
+}
+
+pub fn unactivated_marks(language: GameLanguage) -> &'static [&'static str] {
+    match language {
+        GameLanguage::ZhCn => &UNACTIVATED_MARKS,
+        GameLanguage::En => &UNACTIVATED_MARKS_EN,
+    }
+}
+
+/// The text before and after the name of the character an artifact is equipped to,
+/// e.g. "芙宁娜已装备", "Equipped: Furina"
+pub fn equip_label(language: GameLanguage) -> (&'static str, &'static str) {
+    match language {
+        GameLanguage::ZhCn => ("", "已装备"),
+        GameLanguage::En => ("Equipped: ", ""),
+    }
+}
+
+/// The character name in an equip label, None if the text is not one
+pub fn strip_equip_label(text: &str, language: GameLanguage) -> Option<&str> {
+    let (prefix, suffix) = equip_label(language);
+    text.strip_prefix(prefix)?.strip_suffix(suffix).map(|name| name.trim())
+}
+
+/// Join the second line of a wrapped English title, e.g. "In Remembrance of" and "Viridescent Fields"
+/// the line is the slot if the title fits in one line
+pub fn join_title_lines(first: &str, second: &str) -> String {
+    let (first, second) = (first.trim(), second.trim());
+    let is_slot = SLOT_NAMES_EN.iter().any(|slot| slot.eq_ignore_ascii_case(second));
+    if second.is_empty() || is_slot || ARTIFACT_NAMES_EN.contains(&first) {
+        String::from(first)
+    } else {
+        format!("{} {}", first, second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_equip_label() {
+        assert_eq!(strip_equip_label("Equipped: Furina", GameLanguage::En), Some("Furina"));
+        assert_eq!(strip_equip_label("芙宁娜已装备", GameLanguage::ZhCn), Some("芙宁娜"));
+        assert_eq!(strip_equip_label("Furina", GameLanguage::En), None);
+    }
+
+    #[test]
+    fn test_join_title_lines() {
+        assert_eq!(join_title_lines("In Remembrance of", "Viridescent Fields"), "In Remembrance of Viridescent Fields");
+        // the title fits in one line, the second line is the slot
+        assert_eq!(join_title_lines("Gladiator's Nostalgia", "Flower of Life"), "Gladiator's Nostalgia");
+        assert_eq!(join_title_lines("Gladiator's Nostalgia", ""), "Gladiator's Nostalgia");
+    }
+}
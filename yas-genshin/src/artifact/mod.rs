@@ -3,6 +3,25 @@ pub use artifact::ArtifactSlot;
 pub use artifact::ArtifactStat;
 pub use artifact::ArtifactStatName;
 pub use artifact::GenshinArtifact;
+pub use artifact::{UNACTIVATED_MARKS, UNACTIVATED_MARKS_EN};
+pub use artifact_corrector::GenshinArtifactCorrector;
+pub use artifact_validator::GenshinArtifactValidator;
+pub use conversion_error::ArtifactConversionError;
+pub use en::{ARTIFACT_NAMES_EN, SLOT_NAMES_EN, STAT_NAMES_EN, SUB_STAT_NAMES_EN};
+pub use game_text::{
+    artifact_names, character_names, equip_label, join_title_lines, stat_names, strip_equip_label, sub_stat_names,
+    unactivated_marks,
+};
+pub use roll_analysis::{ArtifactRollAnalysis, SubStatRolls, TIER_PERCENTAGES};
+pub use zh_cn::{ARTIFACT_NAMES_ZH_CN, STAT_NAMES_ZH_CN, SUB_STAT_NAMES_ZH_CN};
 
 mod artifact;
+mod artifact_rating;
+mod artifact_corrector;
+mod artifact_validator;
+mod conversion_error;
+mod en;
+mod game_text;
+mod roll_analysis;
+mod stat_table;
 mod zh_cn;
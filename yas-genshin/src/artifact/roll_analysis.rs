@@ -0,0 +1,176 @@
+use yas::validation::choose_roll_counts;
+
+use crate::artifact::{ArtifactStat, GenshinArtifact};
+
+/// A roll of tier `i` adds `TIER_PERCENTAGES[i]`% of the highest roll
+pub const TIER_PERCENTAGES: [u32; 4] = [70, 80, 90, 100];
+
+/// How a sub stat value is made up of rolls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubStatRolls {
+    /// the number of rolls of each tier, from the lowest to the highest
+    pub tiers: [usize; 4],
+}
+
+impl SubStatRolls {
+    /// The number of rolls, including the one when the sub stat is added
+    pub fn count(&self) -> usize {
+        self.tiers.iter().sum()
+    }
+
+    /// The tier of every roll as a percentage of the highest roll, from high to low, e.g. [100, 90, 70]
+    pub fn roll_percentages(&self) -> Vec<u32> {
+        (0..4).rev()
+            .flat_map(|i| std::iter::repeat(TIER_PERCENTAGES[i]).take(self.tiers[i]))
+            .collect()
+    }
+}
+
+/// The rolls of the sub stats of an artifact, and how many sub stats it started with
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactRollAnalysis {
+    /// in the order of the sub stats, None if the sub stat is absent, not activated or its value cannot be decomposed
+    pub sub_stats: [Option<SubStatRolls>; 4],
+    /// the number of sub stats at level 0, None if it cannot be told
+    pub initial_sub_stats: Option<usize>,
+}
+
+/// The displayed value is rounded, and the tiers are rounded to 2 decimals
+const TOLERANCE_UNITS: f64 = 0.6;
+
+/// For every roll count, the tier counts which add up closest to the value, and how far they are
+fn decompose(stat: &ArtifactStat, star: i32, max_rolls: usize) -> Vec<(SubStatRolls, f64)> {
+    let tiers = match stat.name.sub_stat_tiers(star) {
+        Some(v) => v,
+        None => return Vec::new(),
+    };
+    let tolerance = stat.name.display_unit() * TOLERANCE_UNITS;
+
+    let mut result = Vec::new();
+    for rolls in 1..=max_rolls {
+        let mut best: Option<(SubStatRolls, f64)> = None;
+        for a in 0..=rolls {
+            for b in 0..=rolls - a {
+                for c in 0..=rolls - a - b {
+                    let d = rolls - a - b - c;
+                    let sum = tiers[0] * a as f64 + tiers[1] * b as f64 + tiers[2] * c as f64 + tiers[3] * d as f64;
+                    let error = (sum - stat.value).abs();
+                    if error <= tolerance && best.as_ref().map_or(true, |(_, e)| error < *e) {
+                        best = Some((SubStatRolls { tiers: [a, b, c, d] }, error));
+                    }
+                }
+            }
+        }
+        result.extend(best);
+    }
+    result
+}
+
+impl ArtifactRollAnalysis {
+    /// Decompose the sub stats into rolls so that the total number of rolls agrees with the level
+    /// an artifact of `star` starts with `star - 2` or `star - 1` sub stats, and gets a roll every 4 levels,
+    /// which adds a sub stat until there are 4
+    pub fn analyze(artifact: &GenshinArtifact) -> ArtifactRollAnalysis {
+        let star = artifact.star;
+        if star < 3 {
+            return ArtifactRollAnalysis::default();
+        }
+
+        let upgrades = (artifact.level.clamp(0, star * 4) / 4) as usize;
+        let sub_stats = [&artifact.sub_stat_1, &artifact.sub_stat_2, &artifact.sub_stat_3, &artifact.sub_stat_4];
+        // a sub stat which is not activated yet has no roll
+        let candidates = sub_stats.iter()
+            .map(|s| s.as_ref().filter(|stat| stat.activated).map(|stat| decompose(stat, star, 1 + upgrades)))
+            .collect::<Vec<_>>();
+        let roll_counts = candidates.iter()
+            .map(|c| c.as_ref().map(|c| c.iter().map(|(r, e)| (r.count(), *e)).collect()))
+            .collect::<Vec<_>>();
+        let initial_counts = [star as usize - 2, star as usize - 1];
+        let choice = match choose_roll_counts(&roll_counts, &initial_counts, upgrades) {
+            Some(v) => v,
+            None => return ArtifactRollAnalysis::default(),
+        };
+
+        let mut result = ArtifactRollAnalysis {
+            sub_stats: Default::default(),
+            initial_sub_stats: choice.initial_sub_stats,
+        };
+        for (i, (c, index)) in candidates.into_iter().zip(choice.chosen).enumerate() {
+            if let (Some(c), Some(index)) = (c, index) {
+                result.sub_stats[i] = Some(c[index].0.clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::{ArtifactSetName, ArtifactSlot, ArtifactStatName};
+
+    fn stat(name: ArtifactStatName, value: f64) -> Option<ArtifactStat> {
+        Some(ArtifactStat { name, value, activated: true })
+    }
+
+    fn artifact(level: i32, sub_stats: [Option<ArtifactStat>; 4]) -> GenshinArtifact {
+        let [sub_stat_1, sub_stat_2, sub_stat_3, sub_stat_4] = sub_stats;
+        GenshinArtifact {
+            set_name: ArtifactSetName::ArchaicPetra,
+            slot: ArtifactSlot::Flower,
+            star: 5,
+            lock: false,
+            level,
+            main_stat: ArtifactStat { name: ArtifactStatName::Hp, value: 717.0, activated: true },
+            sub_stat_1,
+            sub_stat_2,
+            sub_stat_3,
+            sub_stat_4,
+            equip: None,
+        }
+    }
+
+    fn rolls(tiers: [usize; 4]) -> Option<SubStatRolls> {
+        Some(SubStatRolls { tiers })
+    }
+
+    #[test]
+    fn test_single_high_rolls() {
+        let analysis = ArtifactRollAnalysis::analyze(&artifact(0, [
+            stat(ArtifactStatName::Critical, 0.039),
+            stat(ArtifactStatName::CriticalDamage, 0.078),
+            stat(ArtifactStatName::AtkPercentage, 0.058),
+            stat(ArtifactStatName::Def, 23.0),
+        ]));
+
+        assert_eq!(analysis.sub_stats, [rolls([0, 0, 0, 1]), rolls([0, 0, 0, 1]), rolls([0, 0, 0, 1]), rolls([0, 0, 0, 1])]);
+        assert_eq!(analysis.initial_sub_stats, Some(4));
+    }
+
+    #[test]
+    fn test_upgraded() {
+        // 3.9% + 3.5% crit rate, and a sub stat added at level 4
+        let analysis = ArtifactRollAnalysis::analyze(&artifact(4, [
+            stat(ArtifactStatName::Critical, 0.074),
+            stat(ArtifactStatName::CriticalDamage, 0.078),
+            stat(ArtifactStatName::AtkPercentage, 0.058),
+            stat(ArtifactStatName::Def, 23.0),
+        ]));
+
+        assert_eq!(analysis.sub_stats[0], rolls([0, 0, 1, 1]));
+        assert_eq!(analysis.initial_sub_stats, Some(4));
+        assert_eq!(analysis.sub_stats[0].as_ref().unwrap().roll_percentages(), vec![100, 90]);
+    }
+
+    #[test]
+    fn test_undecomposable_sub_stat_is_skipped() {
+        let analysis = ArtifactRollAnalysis::analyze(&artifact(0, [
+            stat(ArtifactStatName::Critical, 0.039),
+            stat(ArtifactStatName::CriticalDamage, 0.5),
+            stat(ArtifactStatName::AtkPercentage, 0.058),
+            stat(ArtifactStatName::Def, 23.0),
+        ]));
+
+        assert_eq!(analysis.sub_stats, [rolls([0, 0, 0, 1]), None, rolls([0, 0, 0, 1]), rolls([0, 0, 0, 1])]);
+    }
+}
@@ -0,0 +1,161 @@
+use yas::validation::StatValues;
+
+use crate::artifact::ArtifactStatName;
+
+/// The displayed main stat values of 5 star artifacts from level 0 to 20, percentages are in %
+#[rustfmt::skip]
+mod five_star {
+    pub const HP: [f64; 21] = [717.0, 920.0, 1123.0, 1326.0, 1530.0, 1733.0, 1936.0, 2139.0, 2342.0, 2545.0, 2749.0, 2952.0, 3155.0, 3358.0, 3561.0, 3764.0, 3967.0, 4171.0, 4374.0, 4577.0, 4780.0];
+    pub const ATK: [f64; 21] = [47.0, 60.0, 73.0, 86.0, 100.0, 113.0, 126.0, 139.0, 152.0, 166.0, 179.0, 192.0, 205.0, 219.0, 232.0, 245.0, 258.0, 272.0, 285.0, 298.0, 311.0];
+    pub const HP_ATK_ELEMENTAL: [f64; 21] = [7.0, 9.0, 11.0, 12.9, 14.9, 16.9, 18.9, 20.9, 22.8, 24.8, 26.8, 28.8, 30.8, 32.8, 34.7, 36.7, 38.7, 40.7, 42.7, 44.6, 46.6];
+    pub const DEF_PHYSICAL: [f64; 21] = [8.7, 11.2, 13.7, 16.2, 18.6, 21.1, 23.6, 26.1, 28.6, 31.0, 33.5, 36.0, 38.5, 40.9, 43.4, 45.9, 48.4, 50.8, 53.3, 55.8, 58.3];
+    pub const ELEMENTAL_MASTERY: [f64; 21] = [28.0, 36.0, 44.0, 52.0, 60.0, 68.0, 76.0, 84.0, 91.0, 99.0, 107.0, 115.0, 123.0, 131.0, 139.0, 147.0, 155.0, 163.0, 171.0, 179.0, 187.0];
+    pub const RECHARGE: [f64; 21] = [7.8, 10.0, 12.2, 14.4, 16.6, 18.8, 21.0, 23.2, 25.4, 27.6, 29.8, 32.0, 34.2, 36.4, 38.6, 40.8, 43.0, 45.2, 47.4, 49.6, 51.8];
+    pub const CRITICAL: [f64; 21] = [4.7, 6.0, 7.3, 8.6, 9.9, 11.3, 12.6, 13.9, 15.2, 16.6, 17.9, 19.2, 20.5, 21.8, 23.2, 24.5, 25.8, 27.1, 28.4, 29.8, 31.1];
+    pub const CRITICAL_DAMAGE: [f64; 21] = [9.3, 12.0, 14.6, 17.3, 19.9, 22.5, 25.2, 27.8, 30.5, 33.1, 35.7, 38.4, 41.0, 43.7, 46.3, 49.0, 51.6, 54.2, 56.9, 59.5, 62.2];
+    pub const HEALING: [f64; 21] = [5.4, 6.9, 8.4, 10.0, 11.5, 13.0, 14.5, 16.1, 17.6, 19.1, 20.6, 22.1, 23.7, 25.2, 26.7, 28.2, 29.8, 31.3, 32.8, 34.3, 35.9];
+}
+
+/// The displayed main stat values of 4 star artifacts from level 0 to 16, percentages are in %
+#[rustfmt::skip]
+mod four_star {
+    pub const HP: [f64; 17] = [645.0, 828.0, 1011.0, 1194.0, 1377.0, 1559.0, 1742.0, 1925.0, 2108.0, 2291.0, 2474.0, 2657.0, 2839.0, 3022.0, 3205.0, 3388.0, 3571.0];
+    pub const ATK: [f64; 17] = [42.0, 54.0, 66.0, 78.0, 90.0, 102.0, 113.0, 125.0, 137.0, 149.0, 161.0, 173.0, 185.0, 197.0, 209.0, 221.0, 232.0];
+    pub const HP_ATK_ELEMENTAL: [f64; 17] = [6.3, 8.1, 9.9, 11.6, 13.4, 15.2, 17.0, 18.8, 20.6, 22.3, 24.1, 25.9, 27.7, 29.5, 31.3, 33.0, 34.8];
+    pub const DEF_PHYSICAL: [f64; 17] = [7.9, 10.1, 12.3, 14.6, 16.8, 19.0, 21.2, 23.5, 25.7, 27.9, 30.2, 32.4, 34.6, 36.8, 39.1, 41.3, 43.5];
+    pub const ELEMENTAL_MASTERY: [f64; 17] = [25.0, 32.0, 39.0, 47.0, 54.0, 61.0, 68.0, 75.0, 82.0, 89.0, 97.0, 104.0, 111.0, 118.0, 125.0, 132.0, 139.0];
+    pub const RECHARGE: [f64; 17] = [7.0, 9.0, 11.0, 12.9, 14.9, 16.9, 18.9, 20.9, 22.8, 24.8, 26.8, 28.8, 30.8, 32.8, 34.7, 36.7, 38.7];
+    pub const CRITICAL: [f64; 17] = [4.2, 5.4, 6.6, 7.8, 8.9, 10.1, 11.3, 12.5, 13.7, 14.9, 16.1, 17.3, 18.5, 19.7, 20.8, 22.0, 23.2];
+    pub const CRITICAL_DAMAGE: [f64; 17] = [8.4, 10.8, 13.1, 15.5, 17.9, 20.3, 22.7, 25.0, 27.4, 29.8, 32.2, 34.5, 36.9, 39.3, 41.7, 44.1, 46.4];
+    pub const HEALING: [f64; 17] = [4.8, 6.2, 7.6, 9.0, 10.3, 11.7, 13.1, 14.4, 15.8, 17.2, 18.6, 19.9, 21.3, 22.7, 24.0, 25.4, 26.8];
+}
+
+impl ArtifactStatName {
+    pub fn is_percentage(&self) -> bool {
+        !matches!(
+            self,
+            ArtifactStatName::Hp | ArtifactStatName::Atk | ArtifactStatName::Def | ArtifactStatName::ElementalMastery
+        )
+    }
+
+    /// The difference between two adjacent displayed values, in the unit of `ArtifactStat::value`
+    pub fn display_unit(&self) -> f64 {
+        if self.is_percentage() { 0.001 } else { 1.0 }
+    }
+
+    /// The displayed main stat values of an artifact of `star` by level, percentages are in %
+    fn main_stat_table(&self, star: i32) -> Option<&'static [f64]> {
+        use ArtifactStatName::*;
+        let table: &'static [f64] = match (star, self) {
+            (5, Hp) => &five_star::HP,
+            (5, Atk) => &five_star::ATK,
+            (5, HpPercentage | AtkPercentage) => &five_star::HP_ATK_ELEMENTAL,
+            (5, ElectroBonus | PyroBonus | HydroBonus | CryoBonus | AnemoBonus | GeoBonus | DendroBonus) => &five_star::HP_ATK_ELEMENTAL,
+            (5, DefPercentage | PhysicalBonus) => &five_star::DEF_PHYSICAL,
+            (5, ElementalMastery) => &five_star::ELEMENTAL_MASTERY,
+            (5, Recharge) => &five_star::RECHARGE,
+            (5, Critical) => &five_star::CRITICAL,
+            (5, CriticalDamage) => &five_star::CRITICAL_DAMAGE,
+            (5, HealingBonus) => &five_star::HEALING,
+            (4, Hp) => &four_star::HP,
+            (4, Atk) => &four_star::ATK,
+            (4, HpPercentage | AtkPercentage) => &four_star::HP_ATK_ELEMENTAL,
+            (4, ElectroBonus | PyroBonus | HydroBonus | CryoBonus | AnemoBonus | GeoBonus | DendroBonus) => &four_star::HP_ATK_ELEMENTAL,
+            (4, DefPercentage | PhysicalBonus) => &four_star::DEF_PHYSICAL,
+            (4, ElementalMastery) => &four_star::ELEMENTAL_MASTERY,
+            (4, Recharge) => &four_star::RECHARGE,
+            (4, Critical) => &four_star::CRITICAL,
+            (4, CriticalDamage) => &four_star::CRITICAL_DAMAGE,
+            (4, HealingBonus) => &four_star::HEALING,
+            _ => return None,
+        };
+        Some(table)
+    }
+
+    /// The displayed main stat value of an artifact of `star` at `level`, None if it is not known
+    pub fn main_stat_value(&self, star: i32, level: i32) -> Option<f64> {
+        let table = self.main_stat_table(star)?;
+        let value = *table.get(usize::try_from(level).ok()?)?;
+        Some(if self.is_percentage() { value / 100.0 } else { value })
+    }
+
+    /// The levels at which an artifact of `star` displays `value` as this main stat
+    pub fn main_stat_levels(&self, star: i32, value: f64) -> Vec<i32> {
+        let table = match self.main_stat_table(star) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+        let unit = if self.is_percentage() { 100.0 } else { 1.0 };
+        // the game may round the last digit either way
+        let tolerance = self.display_unit() * 1.01;
+        (0..table.len() as i32)
+            .filter(|&level| (table[level as usize] / unit - value).abs() <= tolerance)
+            .collect()
+    }
+
+    /// The four values a roll of this sub stat can add, from low to high, None if they are not known
+    #[rustfmt::skip]
+    pub fn sub_stat_tiers(&self, star: i32) -> Option<[f64; 4]> {
+        use ArtifactStatName::*;
+        let tiers = match self {
+            Hp => [209.13, 239.0, 268.88, 298.75],
+            Atk => [13.62, 15.56, 17.51, 19.45],
+            Def => [16.2, 18.52, 20.83, 23.15],
+            HpPercentage | AtkPercentage => [4.08, 4.66, 5.25, 5.83],
+            DefPercentage => [5.1, 5.83, 6.56, 7.29],
+            ElementalMastery => [16.32, 18.65, 20.98, 23.31],
+            Recharge => [4.53, 5.18, 5.83, 6.48],
+            Critical => [2.72, 3.11, 3.5, 3.89],
+            CriticalDamage => [5.44, 6.22, 6.99, 7.77],
+            _ => return None,
+        };
+        // the rolls of 4 star artifacts are 80% of those of 5 star ones
+        let scale = match star {
+            5 => 1.0,
+            4 => 0.8,
+            _ => return None,
+        };
+        let unit = if self.is_percentage() { 100.0 } else { 1.0 };
+
+        Some(tiers.map(|v| v * scale / unit))
+    }
+
+    /// The relative chance of this stat to be drawn as a new sub stat, None if it is never a sub stat
+    pub fn sub_stat_draw_weight(&self) -> Option<u32> {
+        use ArtifactStatName::*;
+        match self {
+            Hp | Atk | Def => Some(6),
+            HpPercentage | AtkPercentage | DefPercentage | Recharge | ElementalMastery => Some(4),
+            Critical | CriticalDamage => Some(3),
+            _ => None,
+        }
+    }
+
+    /// The values this sub stat can have on an artifact of `star` at `level`
+    /// every 4 levels one of the sub stats is rolled once more
+    pub fn sub_stat_values(&self, star: i32, level: i32) -> Option<StatValues> {
+        let tiers = self.sub_stat_tiers(star)?;
+        let max_rolls = 1 + (level.clamp(0, star * 4) / 4) as usize;
+        Some(StatValues::from_rolls(&tiers, max_rolls))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_stat_value() {
+        assert_eq!(ArtifactStatName::Hp.main_stat_value(5, 20), Some(4780.0));
+        assert!((ArtifactStatName::Critical.main_stat_value(5, 20).unwrap() - 0.311).abs() < 1e-9);
+        assert_eq!(ArtifactStatName::Atk.main_stat_value(4, 16), Some(232.0));
+        assert_eq!(ArtifactStatName::Atk.main_stat_value(4, 17), None);
+        assert_eq!(ArtifactStatName::Def.main_stat_value(5, 0), None);
+    }
+
+    #[test]
+    fn test_main_stat_levels() {
+        assert_eq!(ArtifactStatName::CriticalDamage.main_stat_levels(5, 0.622), vec![20]);
+        assert_eq!(ArtifactStatName::Hp.main_stat_levels(5, 4730.0), Vec::<i32>::new());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::artifact::ArtifactSlot;
+use crate::artifact::{ArtifactSetName, ArtifactSlot, ArtifactStatName};
 
 impl ArtifactSlot {
     pub fn to_zh_cn(&self) -> &'static str {
@@ -11,3 +11,15 @@ impl ArtifactSlot {
         }
     }
 }
+
+/// Names of all artifacts that `ArtifactSetName::from_zh_cn` accepts
+pub const ARTIFACT_NAMES_ZH_CN: &[&str] = ArtifactSetName::PIECE_NAMES_ZH_CN;
+
+/// Names of all stats that `ArtifactStatName::from_zh_cn` accepts
+pub const STAT_NAMES_ZH_CN: &[&str] = ArtifactStatName::NAMES_ZH_CN;
+
+#[rustfmt::skip]
+pub const SUB_STAT_NAMES_ZH_CN: &[&str] = &[
+    "暴击率", "暴击伤害", "攻击力", "生命值",
+    "防御力", "元素精通", "元素充能效率",
+];
@@ -1,97 +1,14 @@
 use std::collections::HashSet;
 
 use lazy_static::lazy_static;
+use strum_macros::Display;
+
+yas_derive::yas_game_data! {
+    #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, Display)]
+    #[game_data(keys(good))]
+    pub enum GenshinCharacter = "data/characters.json";
+}
 
 lazy_static! {
-    pub static ref CHARACTER_NAMES: HashSet<&'static str> = HashSet::from([
-        "迪卢克",
-        "可莉",
-        "胡桃",
-        "宵宫",
-        "安柏",
-        "班尼特",
-        "香菱",
-        "辛焱",
-        "烟绯",
-        "托马",
-        "莫娜",
-        "达达利亚",
-        "珊瑚宫心海",
-        "神里绫人",
-        "夜兰",
-        "妮露",
-        "芭芭拉",
-        "行秋",
-        "坎蒂丝",
-        "琴",
-        "温迪",
-        "魈",
-        "旅行者",
-        "枫原万叶",
-        "流浪者",
-        "砂糖",
-        "早柚",
-        "鹿野院平藏",
-        "珐露珊",
-        "刻晴",
-        "雷电将军",
-        "八重神子",
-        "赛诺",
-        "北斗",
-        "丽莎",
-        "雷泽",
-        "菲谢尔",
-        "九条裟罗",
-        "久岐忍",
-        "多莉",
-        "七七",
-        "甘雨",
-        "神里绫华",
-        "优菈",
-        "埃洛伊",
-        "申鹤",
-        "凯亚",
-        "重云",
-        "迪奥娜",
-        "罗莎莉亚",
-        "莱依拉",
-        "钟离",
-        "阿贝多",
-        "荒泷一斗",
-        "诺艾尔",
-        "凝光",
-        "云堇",
-        "五郎",
-        "提纳里",
-        "纳西妲",
-        "柯莱",
-        "白术",
-        "卡维",
-        "瑶瑶",
-        "艾尔海森",
-        "迪希雅",
-        "米卡",
-        "琳妮特",
-        "林尼",
-        "菲米尼",
-        "芙宁娜",
-        "那维莱特",
-        "娜维娅",
-        "绮良良",
-        "莱欧斯利",
-        "夏洛蒂",
-        "夏沃蕾",
-        "嘉明",
-        "闲云",
-        "千织",
-        "阿蕾奇诺",
-        "赛索斯",
-        "克洛琳德",
-        "希格雯",
-        "艾梅丽埃",
-        "卡齐娜",
-        "玛拉妮",
-        "基尼奇",
-        "希诺宁",
-    ]);
+    pub static ref CHARACTER_NAMES: HashSet<&'static str> = GenshinCharacter::NAMES_ZH_CN.iter().copied().collect();
 }
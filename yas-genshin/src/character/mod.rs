@@ -1,4 +1,3 @@
-pub use character_names::CHARACTER_NAMES;
+pub use character_names::{GenshinCharacter, CHARACTER_NAMES};
 
 mod character_names;
-
@@ -1,13 +1,20 @@
 use serde::{Serialize, Serializer};
-use crate::artifact::GenshinArtifact;
+use yas::correction::{corrections_of, Correction};
+use yas::rating::{Rateable, WeightProfile};
+use crate::artifact::{ArtifactRollAnalysis, GenshinArtifact};
 
 pub struct GenshinArtifactCSVFormat<'a> {
     artifacts: &'a [GenshinArtifact],
+    /// a score column is added for each profile
+    profiles: &'a [WeightProfile],
+    corrections: &'a [Vec<Correction>],
 }
 
 /// CSV format:
-/// set name, slot, star, level, main stat name, main stat value, [sub state name, sub state value]*4, equip
-fn single_artifact_to_string(artifact: &GenshinArtifact) -> String {
+/// set name, slot, star, level, main stat name, main stat value, [sub state name, sub state value]*4, equip,
+/// [sub stat roll count, sub stat roll tiers]*4, initial sub stat count, whether the 4th sub stat is not activated,
+/// crit value, corrections of the recognized names, [score]*profiles
+fn single_artifact_to_string(artifact: &GenshinArtifact, profiles: &[WeightProfile], corrections: &[Correction]) -> String {
     let mut s = String::new();
     s = s + &artifact.set_name.to_string();
     s = s + "," + &artifact.slot.to_string();
@@ -45,22 +52,61 @@ fn single_artifact_to_string(artifact: &GenshinArtifact) -> String {
         s += ","
     }
 
+    // the tiers are percentages of the highest roll, e.g. "100+90+70"
+    let analysis = ArtifactRollAnalysis::analyze(artifact);
+    for rolls in analysis.sub_stats.iter() {
+        if let Some(rolls) = rolls {
+            let tiers = rolls.roll_percentages().iter().map(|t| t.to_string()).collect::<Vec<_>>();
+            s = s + "," + &format!("{}", rolls.count());
+            s = s + "," + &tiers.join("+");
+        } else {
+            s += ",,";
+        }
+    }
+    if let Some(n) = analysis.initial_sub_stats {
+        s = s + "," + &format!("{}", n);
+    } else {
+        s += ","
+    }
+    match &artifact.sub_stat_4 {
+        Some(sub) if !sub.activated => s += ",待激活",
+        _ => s += ",",
+    }
+
+    s = s + "," + &format!("{:.1}", artifact.crit_value());
+    // quoted, the recognized text may contain commas
+    let corrections = corrections.iter().map(|c| c.describe()).collect::<Vec<_>>().join("; ");
+    if corrections.is_empty() {
+        s += ",";
+    } else {
+        s = s + ",\"" + &corrections.replace('"', "\"\"") + "\"";
+    }
+    for profile in profiles.iter() {
+        s = s + "," + &format!("{:.2}", profile.score(artifact));
+    }
+
     s
 }
 
 impl<'a> GenshinArtifactCSVFormat<'a> {
-    pub fn new(artifacts: &'a [GenshinArtifact]) -> Self {
+    pub fn new(artifacts: &'a [GenshinArtifact], profiles: &'a [WeightProfile], corrections: &'a [Vec<Correction>]) -> Self {
         Self {
-            artifacts
+            artifacts,
+            profiles,
+            corrections,
         }
     }
 
     pub fn to_csv_string(&self) -> String {
-        let header = "套装,部位,星级,等级,主词条名,主词条值,副词条名1,副词条值1,副词条名2,副词条值2,副词条名3,副词条值3,副词条名4,副词条值4,装备";
-        let mut result = String::from(header) + "\n";
+        let header = "套装,部位,星级,等级,主词条名,主词条值,副词条名1,副词条值1,副词条名2,副词条值2,副词条名3,副词条值3,副词条名4,副词条值4,装备,副词条次数1,副词条档位1,副词条次数2,副词条档位2,副词条次数3,副词条档位3,副词条次数4,副词条档位4,初始副词条数,副词条4状态,暴击值,纠正记录";
+        let mut result = String::from(header);
+        for profile in self.profiles.iter() {
+            result = result + ",评分(" + &profile.name + ")";
+        }
+        result += "\n";
 
-        for artifact in self.artifacts.iter() {
-            let line = single_artifact_to_string(artifact);
+        for (index, artifact) in self.artifacts.iter().enumerate() {
+            let line = single_artifact_to_string(artifact, self.profiles, corrections_of(self.corrections, index));
             result = result + &line + "\n";
         }
 
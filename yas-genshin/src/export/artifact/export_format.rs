@@ -6,6 +6,8 @@ pub enum GenshinArtifactExportFormat {
     MingyuLab,
     Good,
     CSV,
+    /// The scanned artifacts along with the rolls of the sub stats
+    Extended,
     /// Export all formats
     All,
 }
@@ -3,12 +3,15 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::FromArgMatches;
 
+use yas::correction::{Correction, CorrectionReport};
 use yas::export::{AssetEmitter, ExportAssets};
+use yas::rating::{RatingConfig, WeightProfile};
 
 use crate::artifact::GenshinArtifact;
 use crate::export::artifact::{ExportArtifactConfig, GenshinArtifactExportFormat};
 use crate::export::artifact::csv::GenshinArtifactCSVFormat;
 
+use super::extended::ExtendedFormat;
 use super::good::GOODFormat;
 use super::mingyu_lab::MingyuLabFormat;
 use super::mona_uranai::MonaFormat;
@@ -17,6 +20,12 @@ pub struct GenshinArtifactExporter<'a> {
     pub format: GenshinArtifactExportFormat,
     pub results: Option<&'a [GenshinArtifact]>,
     pub output_dir: PathBuf,
+    /// whether the items which cannot be converted are written to `rejected.json`
+    pub output_rejected: bool,
+    /// the artifacts are scored against these in the CSV export, given by `--rating-profiles`
+    pub profiles: Vec<WeightProfile>,
+    /// the corrections of the recognized names of every artifact, attached to the artifacts in the exports
+    pub corrections: Vec<Vec<Correction>>,
 }
 
 impl <'a> GenshinArtifactExporter<'a> {
@@ -25,9 +34,18 @@ impl <'a> GenshinArtifactExporter<'a> {
         Ok(Self {
             format: config.format,
             results: Some(results),
-            output_dir: PathBuf::from(&config.output_dir)
+            output_dir: PathBuf::from(&config.output_dir),
+            output_rejected: config.output_rejected,
+            profiles: RatingConfig::from_arg_matches(arg_matches)?.load_profiles::<GenshinArtifact>()?,
+            corrections: Vec::new(),
         })
     }
+
+    /// Attach the corrections to the artifacts, `scan_indices` holds the index in the scan results of every artifact
+    pub fn with_corrections(mut self, report: &CorrectionReport, scan_indices: &[usize]) -> Self {
+        self.corrections = report.by_exported_item(scan_indices);
+        self
+    }
 }
 
 impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
@@ -41,7 +59,7 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
         match self.format {
             GenshinArtifactExportFormat::Mona => {
                 let path = self.output_dir.join("mona.json");
-                let value = MonaFormat::new(results);
+                let value = MonaFormat::new(results, &self.corrections);
                 let contents = serde_json::to_string(&value).unwrap();
 
                 export_assets.add_asset(
@@ -63,7 +81,7 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
             },
             GenshinArtifactExportFormat::Good => {
                 let path = self.output_dir.join("good.json");
-                let value = GOODFormat::new(results);
+                let value = GOODFormat::new(results, &self.corrections);
                 let contents = serde_json::to_string(&value).unwrap();
 
                 export_assets.add_asset(
@@ -74,7 +92,7 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
             },
             GenshinArtifactExportFormat::CSV => {
                 let path = self.output_dir.join("artifacts.csv");
-                let value = GenshinArtifactCSVFormat::new(results);
+                let value = GenshinArtifactCSVFormat::new(results, &self.profiles, &self.corrections);
                 let contents = value.to_csv_string();
                 export_assets.add_asset(
                     Some(String::from("artifacts csv format")),
@@ -83,11 +101,22 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
                     Some(String::from("CSV格式圣遗物"))
                 );
             },
+            GenshinArtifactExportFormat::Extended => {
+                let path = self.output_dir.join("artifacts_extended.json");
+                let value = ExtendedFormat::new(results, &self.corrections);
+                let contents = serde_json::to_string(&value).unwrap();
+
+                export_assets.add_asset(
+                    Some(String::from("extended")),
+                    path,
+                    contents.into_bytes(),
+                    Some(String::from("带副词条强化分析的圣遗物格式")));
+            },
             GenshinArtifactExportFormat::All => {
                 // mona
                 {
                     let path = self.output_dir.join("mona.json");
-                    let value = MonaFormat::new(results);
+                    let value = MonaFormat::new(results, &self.corrections);
                     let contents = serde_json::to_string(&value).unwrap();
 
                     export_assets.add_asset(
@@ -111,7 +140,7 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
                 // good
                 {
                     let path = self.output_dir.join("good.json");
-                    let value = GOODFormat::new(results);
+                    let value = GOODFormat::new(results, &self.corrections);
                     let contents = serde_json::to_string(&value).unwrap();
 
                     export_assets.add_asset(
@@ -120,10 +149,22 @@ impl<'a> AssetEmitter for GenshinArtifactExporter<'a> {
                         contents.into_bytes(),
                         Some(String::from("GOOD圣遗物格式")));
                 }
+                // extended
+                {
+                    let path = self.output_dir.join("artifacts_extended.json");
+                    let value = ExtendedFormat::new(results, &self.corrections);
+                    let contents = serde_json::to_string(&value).unwrap();
+
+                    export_assets.add_asset(
+                        Some(String::from("extended")),
+                        path,
+                        contents.into_bytes(),
+                        Some(String::from("带副词条强化分析的圣遗物格式")));
+                }
                 // csv
                 {
                     let path = self.output_dir.join("artifacts.csv");
-                    let value = GenshinArtifactCSVFormat::new(results);
+                    let value = GenshinArtifactCSVFormat::new(results, &self.profiles, &self.corrections);
                     let contents = value.to_csv_string();
                     export_assets.add_asset(
                         Some(String::from("csv")),
@@ -0,0 +1,97 @@
+use serde::Serialize;
+
+use yas::correction::{corrections_of, Correction};
+
+use crate::artifact::{ArtifactRollAnalysis, ArtifactStat, GenshinArtifact};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtendedStat {
+    name: String,
+    value: f64,
+}
+
+impl ExtendedStat {
+    fn new(stat: &ArtifactStat) -> ExtendedStat {
+        ExtendedStat {
+            name: stat.name.to_string(),
+            value: stat.value,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtendedSubStat {
+    #[serde(flatten)]
+    stat: ExtendedStat,
+    activated: bool,
+    /// None if the value cannot be decomposed into rolls
+    rolls: Option<usize>,
+    /// the tier of every roll as a percentage of the highest roll
+    roll_tiers: Option<Vec<u32>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtendedArtifact<'a> {
+    set_name: String,
+    slot: String,
+    star: i32,
+    level: i32,
+    lock: bool,
+    equip: Option<&'a str>,
+    main_stat: ExtendedStat,
+    sub_stats: Vec<ExtendedSubStat>,
+    initial_sub_stats: Option<usize>,
+    /// the corrections of the recognized names
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    corrections: &'a [Correction],
+}
+
+impl<'a> ExtendedArtifact<'a> {
+    fn new(artifact: &'a GenshinArtifact, corrections: &'a [Correction]) -> ExtendedArtifact<'a> {
+        let analysis = ArtifactRollAnalysis::analyze(artifact);
+        let sub_stats = [&artifact.sub_stat_1, &artifact.sub_stat_2, &artifact.sub_stat_3, &artifact.sub_stat_4];
+
+        ExtendedArtifact {
+            set_name: artifact.set_name.to_string(),
+            slot: artifact.slot.to_string(),
+            star: artifact.star,
+            level: artifact.level,
+            lock: artifact.lock,
+            equip: artifact.equip.as_deref(),
+            main_stat: ExtendedStat::new(&artifact.main_stat),
+            sub_stats: sub_stats.iter()
+                .zip(analysis.sub_stats.iter())
+                .filter_map(|(stat, rolls)| Some(ExtendedSubStat {
+                    stat: ExtendedStat::new(stat.as_ref()?),
+                    activated: stat.as_ref()?.activated,
+                    rolls: rolls.as_ref().map(|r| r.count()),
+                    roll_tiers: rolls.as_ref().map(|r| r.roll_percentages()),
+                }))
+                .collect(),
+            initial_sub_stats: analysis.initial_sub_stats,
+            corrections,
+        }
+    }
+}
+
+/// The artifacts along with the rolls of their sub stats, percentages are fractions as they are parsed
+#[derive(Serialize)]
+pub struct ExtendedFormat<'a> {
+    version: u32,
+    artifacts: Vec<ExtendedArtifact<'a>>,
+}
+
+impl<'a> ExtendedFormat<'a> {
+    pub fn new(results: &'a [GenshinArtifact], corrections: &'a [Vec<Correction>]) -> ExtendedFormat<'a> {
+        ExtendedFormat {
+            version: 1,
+            artifacts: results.iter()
+                .enumerate()
+                .map(|(index, artifact)| ExtendedArtifact::new(artifact, corrections_of(corrections, index)))
+                .collect(),
+        }
+    }
+}
@@ -9,3 +9,4 @@ mod exporter;
 mod export_format;
 mod config;
 mod csv;
+mod extended;
@@ -2,83 +2,27 @@ use std::convert::From;
 
 use serde::ser::{Serialize, SerializeMap, Serializer};
 
+use yas::correction::{corrections_of, Correction};
+
 use crate::artifact::{
     ArtifactSetName, ArtifactSlot, ArtifactStat, ArtifactStatName, GenshinArtifact,
 };
 
-type MonaArtifact = GenshinArtifact;
+struct MonaArtifact<'a> {
+    artifact: &'a GenshinArtifact,
+    /// written as `yasCorrections` if any, which Mona ignores
+    corrections: &'a [Correction],
+}
 
 impl ArtifactStatName {
     pub fn to_mona(&self) -> String {
-        let temp = match self {
-            ArtifactStatName::HealingBonus => "cureEffect",
-            ArtifactStatName::CriticalDamage => "criticalDamage",
-            ArtifactStatName::Critical => "critical",
-            ArtifactStatName::Atk => "attackStatic",
-            ArtifactStatName::AtkPercentage => "attackPercentage",
-            ArtifactStatName::ElementalMastery => "elementalMastery",
-            ArtifactStatName::Recharge => "recharge",
-            ArtifactStatName::HpPercentage => "lifePercentage",
-            ArtifactStatName::Hp => "lifeStatic",
-            ArtifactStatName::DefPercentage => "defendPercentage",
-            ArtifactStatName::Def => "defendStatic",
-            ArtifactStatName::ElectroBonus => "thunderBonus",
-            ArtifactStatName::PyroBonus => "fireBonus",
-            ArtifactStatName::HydroBonus => "waterBonus",
-            ArtifactStatName::CryoBonus => "iceBonus",
-            ArtifactStatName::AnemoBonus => "windBonus",
-            ArtifactStatName::GeoBonus => "rockBonus",
-            ArtifactStatName::PhysicalBonus => "physicalBonus",
-            ArtifactStatName::DendroBonus => "dendroBonus",
-        };
-        String::from(temp)
+        String::from(self.mona_key())
     }
 }
 
 impl ArtifactSetName {
     pub fn to_mona(&self) -> String {
-        let same = self.to_string();
-        let temp = match self {
-            ArtifactSetName::ArchaicPetra => "archaicPetra",
-            ArtifactSetName::HeartOfDepth => "heartOfDepth",
-            ArtifactSetName::BlizzardStrayer => "blizzardStrayer",
-            ArtifactSetName::RetracingBolide => "retracingBolide",
-            ArtifactSetName::NoblesseOblige => "noblesseOblige",
-            ArtifactSetName::GladiatorFinale => "gladiatorFinale",
-            ArtifactSetName::MaidenBeloved => "maidenBeloved",
-            ArtifactSetName::ViridescentVenerer => "viridescentVenerer",
-            ArtifactSetName::LavaWalker => "lavaWalker",
-            ArtifactSetName::CrimsonWitch => "crimsonWitch",
-            ArtifactSetName::ThunderSmoother => "thunderSmoother",
-            ArtifactSetName::ThunderingFury => "thunderingFury",
-            ArtifactSetName::BloodstainedChivalry => "bloodstainedChivalry",
-            ArtifactSetName::WandererTroupe => "wandererTroupe",
-            ArtifactSetName::Scholar => "scholar",
-            ArtifactSetName::Gambler => "gambler",
-            ArtifactSetName::TinyMiracle => "tinyMiracle",
-            ArtifactSetName::MartialArtist => "martialArtist",
-            ArtifactSetName::BraveHeart => "braveHeart",
-            ArtifactSetName::ResolutionOfSojourner => "resolutionOfSojourner",
-            ArtifactSetName::DefenderWill => "defenderWill",
-            ArtifactSetName::Berserker => "berserker",
-            ArtifactSetName::Instructor => "instructor",
-            ArtifactSetName::Exile => "exile",
-            ArtifactSetName::Adventurer => "adventurer",
-            ArtifactSetName::LuckyDog => "luckyDog",
-            ArtifactSetName::TravelingDoctor => "travelingDoctor",
-            ArtifactSetName::PrayersForWisdom => "prayersForWisdom",
-            ArtifactSetName::PrayersToSpringtime => "prayersToSpringtime",
-            ArtifactSetName::PrayersForIllumination => "prayersForIllumination",
-            ArtifactSetName::PrayersForDestiny => "prayersForDestiny",
-            ArtifactSetName::PaleFlame => "paleFlame",
-            ArtifactSetName::TenacityOfTheMillelith => "tenacityOfTheMillelith",
-            ArtifactSetName::EmblemOfSeveredFate => "emblemOfSeveredFate",
-            ArtifactSetName::ShimenawaReminiscence => "shimenawaReminiscence",
-            ArtifactSetName::HuskOfOpulentDreams => "huskOfOpulentDreams",
-            ArtifactSetName::OceanHuedClam => "oceanHuedClam",
-            _ => same.as_str(),
-        };
-        String::from(temp)
+        String::from(self.mona_key())
     }
 }
 
@@ -107,31 +51,35 @@ impl Serialize for ArtifactStat {
     }
 }
 
-impl Serialize for MonaArtifact {
+impl<'a> Serialize for MonaArtifact<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut root = serializer.serialize_map(Some(7))?;
+        let artifact = self.artifact;
+        let mut root = serializer.serialize_map(None)?;
 
-        root.serialize_entry("setName", &self.set_name.to_mona())
+        root.serialize_entry("setName", &artifact.set_name.to_mona())
             .unwrap();
-        root.serialize_entry("position", &self.slot.to_mona())
+        root.serialize_entry("position", &artifact.slot.to_mona())
             .unwrap();
-        root.serialize_entry("mainTag", &self.main_stat).unwrap();
+        root.serialize_entry("mainTag", &artifact.main_stat).unwrap();
 
         let mut sub_stats: Vec<&ArtifactStat> = vec![];
-        if let Some(ref s) = self.sub_stat_1 {
+        if let Some(ref s) = artifact.sub_stat_1 {
             sub_stats.push(s);
         }
-        if let Some(ref s) = self.sub_stat_2 {
+        if let Some(ref s) = artifact.sub_stat_2 {
             sub_stats.push(s);
         }
-        if let Some(ref s) = self.sub_stat_3 {
+        if let Some(ref s) = artifact.sub_stat_3 {
             sub_stats.push(s);
         }
-        if let Some(ref s) = self.sub_stat_4 {
-            sub_stats.push(s);
+        // a sub stat which is not activated yet adds nothing, Mona has no place for it
+        if let Some(ref s) = artifact.sub_stat_4 {
+            if s.activated {
+                sub_stats.push(s);
+            }
         }
         // let mut subs = serializer.serialize_seq(Some(sub_stats.len()))?;
         //
@@ -143,9 +91,12 @@ impl Serialize for MonaArtifact {
 
         root.serialize_entry("normalTags", &sub_stats)?;
         root.serialize_entry("omit", &false)?;
-        root.serialize_entry("level", &self.level)?;
-        root.serialize_entry("star", &self.star)?;
-        root.serialize_entry("equip", &self.equip)?;
+        root.serialize_entry("level", &artifact.level)?;
+        root.serialize_entry("star", &artifact.star)?;
+        root.serialize_entry("equip", &artifact.equip)?;
+        if !self.corrections.is_empty() {
+            root.serialize_entry("yasCorrections", self.corrections)?;
+        }
         // let random_id = thread_rng().gen::<u64>();
         // root.serialize_entry("id", &random_id);
 
@@ -155,11 +106,11 @@ impl Serialize for MonaArtifact {
 
 pub struct MonaFormat<'a> {
     version: String,
-    flower: Vec<&'a MonaArtifact>,
-    feather: Vec<&'a MonaArtifact>,
-    cup: Vec<&'a MonaArtifact>,
-    sand: Vec<&'a MonaArtifact>,
-    head: Vec<&'a MonaArtifact>,
+    flower: Vec<MonaArtifact<'a>>,
+    feather: Vec<MonaArtifact<'a>>,
+    cup: Vec<MonaArtifact<'a>>,
+    sand: Vec<MonaArtifact<'a>>,
+    head: Vec<MonaArtifact<'a>>,
 }
 
 impl<'a> Serialize for MonaFormat<'a> {
@@ -179,15 +130,16 @@ impl<'a> Serialize for MonaFormat<'a> {
 }
 
 impl<'a> MonaFormat<'a> {
-    pub fn new(results: &[GenshinArtifact]) -> MonaFormat {
-        let mut flower: Vec<&MonaArtifact> = Vec::new();
-        let mut feather: Vec<&MonaArtifact> = Vec::new();
-        let mut cup: Vec<&MonaArtifact> = Vec::new();
-        let mut sand: Vec<&MonaArtifact> = Vec::new();
-        let mut head: Vec<&MonaArtifact> = Vec::new();
-
-        for art in results.iter() {
-            match art.slot {
+    pub fn new(results: &'a [GenshinArtifact], corrections: &'a [Vec<Correction>]) -> MonaFormat<'a> {
+        let mut flower: Vec<MonaArtifact> = Vec::new();
+        let mut feather: Vec<MonaArtifact> = Vec::new();
+        let mut cup: Vec<MonaArtifact> = Vec::new();
+        let mut sand: Vec<MonaArtifact> = Vec::new();
+        let mut head: Vec<MonaArtifact> = Vec::new();
+
+        for (index, artifact) in results.iter().enumerate() {
+            let art = MonaArtifact { artifact, corrections: corrections_of(corrections, index) };
+            match artifact.slot {
                 ArtifactSlot::Flower => flower.push(art),
                 ArtifactSlot::Feather => feather.push(art),
                 ArtifactSlot::Sand => sand.push(art),
@@ -0,0 +1,22 @@
+#[derive(Clone, clap::Args)]
+pub struct LevelUpConfig {
+    /// Simulate the artifacts which are not at the max level against the rating profiles
+    #[arg(id = "level-up-simulation", long = "level-up-simulation", help = "模拟未满级圣遗物强化至满级后的评分，需配合 --rating-profiles 使用")]
+    pub simulate: bool,
+
+    /// The score at the max level which makes an artifact worth having
+    #[arg(id = "level-up-threshold", long = "level-up-threshold", help = "满级后评分达到该值视为成型", value_name = "SCORE", default_value_t = 5.0)]
+    pub threshold: f64,
+
+    /// The chance to reach the threshold from which an artifact is worth leveling
+    #[arg(id = "level-up-probability", long = "level-up-probability", help = "成型概率达到该值时建议强化", value_name = "PROBABILITY", default_value_t = 0.3)]
+    pub level_probability: f64,
+
+    /// The chance to reach the threshold from which an artifact is worth keeping
+    #[arg(id = "keep-probability", long = "keep-probability", help = "成型概率达到该值时建议上锁保留，否则建议作为狗粮", value_name = "PROBABILITY", default_value_t = 0.1)]
+    pub keep_probability: f64,
+
+    /// Write the artifacts to keep which are not locked as a lock plan of `lock_artifact`
+    #[arg(id = "output-lock-plan", long = "output-lock-plan", help = "将建议保留但未上锁的圣遗物输出至 lock.json，供 lock_artifact 加锁")]
+    pub output_lock_plan: bool,
+}
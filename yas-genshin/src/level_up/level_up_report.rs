@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use log::info;
+use serde::Serialize;
+use yas::export::ExportAssets;
+use yas::rating::{Rateable, WeightProfile};
+
+use crate::artifact::GenshinArtifact;
+use crate::level_up::{LevelUpConfig, LevelUpSimulator};
+
+/// How many artifacts worth leveling are logged, the rest are in the report file
+const LOGGED_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelUpAdvice {
+    Level,
+    Lock,
+    Trash,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactLevelUp {
+    /// the position of the artifact in the repository, starting from 0
+    pub position: usize,
+    pub description: String,
+    /// the profile the artifact is most likely to reach the threshold for
+    pub profile: String,
+    pub score: f64,
+    pub expected_score: f64,
+    /// the chance to reach the threshold at the max level
+    pub probability: f64,
+    pub advice: LevelUpAdvice,
+}
+
+/// The lock.json format v2 of `lock_artifact`
+#[derive(Serialize)]
+struct LockPlan {
+    version: i32,
+    flip_indices: Vec<usize>,
+    lock_indices: Vec<usize>,
+    unlock_indices: Vec<usize>,
+    validation: Vec<()>,
+}
+
+/// The advice for every artifact which is not at the max level
+#[derive(Debug, Clone, Default)]
+pub struct LevelUpReport {
+    pub items: Vec<ArtifactLevelUp>,
+    /// the positions of the artifacts to keep which are not locked
+    pub to_lock: Vec<usize>,
+}
+
+impl LevelUpReport {
+    /// `positions` are the positions of `artifacts` in the repository
+    pub fn new(artifacts: &[GenshinArtifact], positions: &[usize], profiles: &[WeightProfile], config: &LevelUpConfig) -> LevelUpReport {
+        let mut report = LevelUpReport::default();
+
+        for (artifact, &position) in artifacts.iter().zip(positions.iter()) {
+            let best = profiles.iter()
+                .filter_map(|profile| {
+                    let distribution = LevelUpSimulator::simulate(artifact, profile)?;
+                    Some((profile, distribution.probability_at_least(config.threshold), distribution.expected()))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1).then(a.2.total_cmp(&b.2)));
+            let (profile, probability, expected_score) = match best {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let advice = if probability >= config.level_probability {
+                LevelUpAdvice::Level
+            } else if probability >= config.keep_probability {
+                LevelUpAdvice::Lock
+            } else {
+                LevelUpAdvice::Trash
+            };
+            if advice != LevelUpAdvice::Trash && !artifact.lock {
+                report.to_lock.push(position);
+            }
+
+            report.items.push(ArtifactLevelUp {
+                position,
+                description: artifact.describe(),
+                profile: profile.name.clone(),
+                score: profile.score(artifact),
+                expected_score,
+                probability,
+                advice,
+            });
+        }
+
+        report
+    }
+
+    fn count(&self, advice: LevelUpAdvice) -> usize {
+        self.items.iter().filter(|item| item.advice == advice).count()
+    }
+
+    pub fn log(&self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        info!(
+            "强化模拟：建议强化 {} 件，建议上锁保留 {} 件，建议作为狗粮 {} 件",
+            self.count(LevelUpAdvice::Level),
+            self.count(LevelUpAdvice::Lock),
+            self.count(LevelUpAdvice::Trash),
+        );
+
+        let mut to_level = self.items.iter()
+            .filter(|item| item.advice == LevelUpAdvice::Level)
+            .collect::<Vec<_>>();
+        to_level.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        for item in to_level.iter().take(LOGGED_ITEMS) {
+            info!(
+                "第 {} 个物品 {}：{} 成型概率 {:.1}%，期望评分 {:.2}",
+                item.position + 1, item.description, item.profile, item.probability * 100.0, item.expected_score
+            );
+        }
+    }
+
+    /// Add `level_up.json` to the export assets, and `lock.json` if `lock_plan`
+    pub fn emit_to(&self, output_dir: &Path, lock_plan: bool, export_assets: &mut ExportAssets) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let contents = serde_json::to_string_pretty(&self.items).unwrap();
+        export_assets.add_asset(
+            Some(String::from("level_up")),
+            output_dir.join("level_up.json"),
+            contents.into_bytes(),
+            Some(String::from("圣遗物强化模拟")),
+        );
+
+        if lock_plan {
+            let plan = LockPlan {
+                version: 2,
+                flip_indices: Vec::new(),
+                lock_indices: self.to_lock.clone(),
+                unlock_indices: Vec::new(),
+                validation: Vec::new(),
+            };
+            let contents = serde_json::to_string(&plan).unwrap();
+            export_assets.add_asset(
+                Some(String::from("lock")),
+                output_dir.join("lock.json"),
+                contents.into_bytes(),
+                Some(String::from("建议保留的圣遗物加锁计划")),
+            );
+        }
+    }
+}
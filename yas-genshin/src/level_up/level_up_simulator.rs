@@ -0,0 +1,111 @@
+use yas::rating::WeightProfile;
+
+use crate::artifact::{ArtifactStat, ArtifactStatName, GenshinArtifact};
+use crate::level_up::ScoreDistribution;
+
+/// The stats which can be drawn as sub stats
+const SUB_STAT_NAMES: [ArtifactStatName; 10] = [
+    ArtifactStatName::Hp,
+    ArtifactStatName::Atk,
+    ArtifactStatName::Def,
+    ArtifactStatName::HpPercentage,
+    ArtifactStatName::AtkPercentage,
+    ArtifactStatName::DefPercentage,
+    ArtifactStatName::Recharge,
+    ArtifactStatName::ElementalMastery,
+    ArtifactStatName::Critical,
+    ArtifactStatName::CriticalDamage,
+];
+
+/// Computes exactly how the score of an artifact is distributed once it is leveled to the max
+///
+/// every 4 levels, an artifact with less than 4 sub stats draws a new one, weighted by
+/// `ArtifactStatName::sub_stat_draw_weight` among those it does not have, otherwise one of its 4 sub stats
+/// is rolled with equal chances, and every roll is one of the 4 tiers with equal chances
+pub struct LevelUpSimulator<'a> {
+    profile: &'a WeightProfile,
+    star: i32,
+    main_stat: ArtifactStatName,
+}
+
+impl<'a> LevelUpSimulator<'a> {
+    /// The score a roll of `name` of each tier adds, in the highest rolls of 5 star artifacts like `Rateable`
+    fn roll_scores(&self, name: &ArtifactStatName) -> [f64; 4] {
+        let weight = self.profile.weight(name.to_good());
+        match (name.sub_stat_tiers(self.star), name.sub_stat_tiers(5)) {
+            (Some(tiers), Some(highest)) => tiers.map(|v| weight * v / highest[3]),
+            _ => [0.0; 4],
+        }
+    }
+
+    fn stat_score(&self, stat: &ArtifactStat) -> f64 {
+        match stat.name.sub_stat_tiers(5) {
+            Some(highest) => self.profile.weight(stat.name.to_good()) * stat.value / highest[3],
+            None => 0.0,
+        }
+    }
+
+    fn level_up(&self, distribution: ScoreDistribution, sub_stats: &[ArtifactStatName], upgrades: i32) -> ScoreDistribution {
+        if upgrades <= 0 {
+            return distribution;
+        }
+
+        if sub_stats.len() < 4 {
+            let candidates = SUB_STAT_NAMES.iter()
+                .filter(|name| **name != self.main_stat && !sub_stats.contains(name))
+                .filter_map(|name| Some((name.clone(), name.sub_stat_draw_weight()? as f64)))
+                .collect::<Vec<_>>();
+            let total_weight = candidates.iter().map(|(_, w)| w).sum::<f64>();
+
+            let parts = candidates.into_iter().map(|(name, w)| {
+                let steps = self.roll_scores(&name).map(|score| (score, 0.25));
+                let mut drawn = sub_stats.to_vec();
+                drawn.push(name);
+                (w / total_weight, self.level_up(distribution.add(&steps), &drawn, upgrades - 1))
+            }).collect::<Vec<_>>();
+            return ScoreDistribution::mix(&parts);
+        }
+
+        let steps = sub_stats.iter()
+            .flat_map(|name| self.roll_scores(name))
+            .map(|score| (score, 1.0 / 16.0))
+            .collect::<Vec<_>>();
+        let mut distribution = distribution;
+        for _ in 0..upgrades {
+            distribution = distribution.add(&steps);
+        }
+        distribution
+    }
+
+    /// The distribution of the score against `profile` at the max level,
+    /// None if the artifact is at the max level already or its rolls are not known
+    pub fn simulate(artifact: &GenshinArtifact, profile: &WeightProfile) -> Option<ScoreDistribution> {
+        let star = artifact.star;
+        // the rolls are known for 4 and 5 star artifacts only
+        ArtifactStatName::Hp.sub_stat_tiers(star)?;
+        let mut upgrades = star - artifact.level.clamp(0, star * 4) / 4;
+        if upgrades <= 0 {
+            return None;
+        }
+
+        let simulator = LevelUpSimulator {
+            profile,
+            star,
+            main_stat: artifact.main_stat.name.clone(),
+        };
+        let mut score = profile.score(artifact);
+        let mut sub_stats = Vec::new();
+        for stat in [&artifact.sub_stat_1, &artifact.sub_stat_2, &artifact.sub_stat_3, &artifact.sub_stat_4].into_iter().flatten() {
+            if stat.activated {
+                sub_stats.push(stat.name.clone());
+            } else {
+                // the sub stat shown as not activated is the one the next upgrade adds, with the value shown
+                score += simulator.stat_score(stat);
+                sub_stats.push(stat.name.clone());
+                upgrades -= 1;
+            }
+        }
+
+        Some(simulator.level_up(ScoreDistribution::certain(score), &sub_stats, upgrades))
+    }
+}
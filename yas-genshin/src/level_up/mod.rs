@@ -0,0 +1,9 @@
+pub use level_up_config::LevelUpConfig;
+pub use level_up_report::{ArtifactLevelUp, LevelUpAdvice, LevelUpReport};
+pub use level_up_simulator::LevelUpSimulator;
+pub use score_distribution::ScoreDistribution;
+
+mod level_up_config;
+mod level_up_report;
+mod level_up_simulator;
+mod score_distribution;
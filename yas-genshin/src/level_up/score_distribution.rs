@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+/// Scores closer than this are merged into one outcome
+const SCORE_RESOLUTION: f64 = 1e-6;
+
+/// The probability of every score an artifact can end up with
+#[derive(Debug, Clone)]
+pub struct ScoreDistribution {
+    /// pairs of score and probability, from the lowest score to the highest
+    pub outcomes: Vec<(f64, f64)>,
+}
+
+impl ScoreDistribution {
+    pub fn certain(score: f64) -> ScoreDistribution {
+        ScoreDistribution {
+            outcomes: vec![(score, 1.0)],
+        }
+    }
+
+    fn from_outcomes(outcomes: impl Iterator<Item = (f64, f64)>) -> ScoreDistribution {
+        let mut merged: HashMap<i64, (f64, f64)> = HashMap::new();
+        for (score, probability) in outcomes {
+            let entry = merged.entry((score / SCORE_RESOLUTION).round() as i64).or_insert((score, 0.0));
+            entry.1 += probability;
+        }
+
+        let mut outcomes = merged.into_values().collect::<Vec<_>>();
+        outcomes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ScoreDistribution {
+            outcomes,
+        }
+    }
+
+    /// The distribution after one more step, which adds one of `steps` (score and probability)
+    pub fn add(&self, steps: &[(f64, f64)]) -> ScoreDistribution {
+        Self::from_outcomes(self.outcomes.iter().flat_map(|&(score, probability)| {
+            steps.iter().map(move |&(step, p)| (score + step, probability * p))
+        }))
+    }
+
+    /// The distribution which is each of `parts` with its probability
+    pub fn mix(parts: &[(f64, ScoreDistribution)]) -> ScoreDistribution {
+        Self::from_outcomes(parts.iter().flat_map(|(p, part)| {
+            part.outcomes.iter().map(move |&(score, probability)| (score, probability * p))
+        }))
+    }
+
+    pub fn expected(&self) -> f64 {
+        self.outcomes.iter().map(|(score, probability)| score * probability).sum()
+    }
+
+    pub fn probability_at_least(&self, threshold: f64) -> f64 {
+        self.outcomes.iter()
+            .filter(|(score, _)| *score >= threshold - SCORE_RESOLUTION)
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+}
@@ -6,5 +6,6 @@ pub mod scanner_controller;
 pub mod export;
 pub mod scanner;
 pub mod artifact;
+pub mod level_up;
 pub mod character;
 pub mod application;
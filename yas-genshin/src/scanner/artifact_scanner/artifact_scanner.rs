@@ -1,4 +1,4 @@
-use std::{cell::RefCell, ops::{Coroutine, CoroutineState}, pin::Pin, rc::Rc, sync::mpsc::{self, Sender}, time::SystemTime};
+use std::{cell::RefCell, ops::{Coroutine, CoroutineState}, pin::Pin, rc::Rc, sync::mpsc, time::SystemTime};
 
 use anyhow::Result;
 use clap::FromArgMatches;
@@ -7,10 +7,16 @@ use log::{error, info};
 
 use yas::capture::{Capturer, GenericCapturer};
 use yas::game_info::GameInfo;
-use yas::ocr::{ImageToText, yas_ocr_model};
+use yas::game_language::GameLanguage;
+use yas::ocr::{ImageToText, LowConfidenceReport, OCRConfig, OCRModelRegistry, yas_ocr_model};
 use yas::positioning::Pos;
+use yas::screen_state::{ScreenProbe, ScreenState, ScreenStateClassifier};
+use yas::sort_order::{SortOrder, SortOrderSwitcher};
+use yas::utils;
+use yas::vision::ColorClassifier;
 use yas::window_info::FromWindowInfoRepository;
 use yas::window_info::WindowInfoRepository;
+use yas::worker_pool::{PageRetry, PoolSender};
 
 use crate::{scanner::artifact_scanner::artifact_scanner_worker::ArtifactScannerWorker};
 use crate::scanner::artifact_scanner::message_items::SendItem;
@@ -19,16 +25,16 @@ use crate::scanner_controller::repository_layout::{
     GenshinRepositoryScanController,
     GenshinRepositoryScannerLogicConfig,
     ReturnResult as GenshinRepositoryControllerReturnResult,
+    ScanEvent,
 };
 
 use super::artifact_scanner_config::GenshinArtifactScannerConfig;
 use super::ArtifactScannerWindowInfo;
 
-fn color_distance(c1: &image::Rgb<u8>, c2: &image::Rgb<u8>) -> usize {
-    let x = c1.0[0] as i32 - c2.0[0] as i32;
-    let y = c1.0[1] as i32 - c2.0[1] as i32;
-    let z = c1.0[2] as i32 - c2.0[2] as i32;
-    (x * x + y * y + z * z) as usize
+fn load_yas_model(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    Ok(Box::new(
+        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json", config.backend(), config.intra_threads)?
+    ))
 }
 
 pub struct GenshinArtifactScanner {
@@ -38,19 +44,31 @@ pub struct GenshinArtifactScanner {
     image_to_text: Box<dyn ImageToText<RgbImage> + Send>,
     controller: Rc<RefCell<GenshinRepositoryScanController>>,
     capturer: Rc<dyn Capturer<RgbImage>>,
+    low_confidence_report: LowConfidenceReport,
+    item_positions: Vec<usize>,
 }
 
 impl GenshinArtifactScanner {
     pub const MAX_COUNT: usize = 2100;
+
+    /// The title of the inventory, followed by the item count, e.g. "圣遗物 1234/2100"
+    fn inventory_title(language: GameLanguage) -> &'static str {
+        match language {
+            GameLanguage::ZhCn => "圣遗物",
+            GameLanguage::En => "Artifacts",
+        }
+    }
 }
 
 // constructor
 impl GenshinArtifactScanner {
-    fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-        let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-            yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-        );
-        Ok(model)
+    /// The models which can be chosen by `--ocr-model`, `yas` is the one trained for the game
+    pub fn get_model_registry() -> OCRModelRegistry {
+        OCRModelRegistry::new().with_model("yas", load_yas_model)
+    }
+
+    pub fn get_image_to_text(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+        Self::get_model_registry().load(&config.model, config)
     }
 
     fn get_capturer() -> Result<Rc<dyn Capturer<RgbImage>>> {
@@ -59,11 +77,15 @@ impl GenshinArtifactScanner {
 
     pub fn new(
         window_info_repo: &WindowInfoRepository,
-        config: GenshinArtifactScannerConfig,
+        mut config: GenshinArtifactScannerConfig,
         controller_config: GenshinRepositoryScannerLogicConfig,
         game_info: GameInfo,
     ) -> Result<Self> {
+        config.adapt_ocr_to_language();
+        let image_to_text = Self::get_image_to_text(&config.ocr)?;
         Ok(Self {
+            low_confidence_report: LowConfidenceReport::new(config.low_confidence),
+            item_positions: Vec::new(),
             scanner_config: config,
             window_info: ArtifactScannerWindowInfo::from_window_info_repository(
                 game_info.window.to_rect_usize().size(),
@@ -75,7 +97,7 @@ impl GenshinArtifactScanner {
                 GenshinRepositoryScanController::new(window_info_repo, controller_config, game_info.clone(), true)?
             )),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             // item count will be set later, once the scan starts
             capturer: Self::get_capturer()?,
         })
@@ -92,14 +114,19 @@ impl GenshinArtifactScanner {
             game_info.platform,
             window_info_repo,
         )?;
+        let mut scanner_config = GenshinArtifactScannerConfig::from_arg_matches(arg_matches)?;
+        scanner_config.adapt_ocr_to_language();
+        let image_to_text = Self::get_image_to_text(&scanner_config.ocr)?;
         Ok(GenshinArtifactScanner {
-            scanner_config: GenshinArtifactScannerConfig::from_arg_matches(arg_matches)?,
+            low_confidence_report: LowConfidenceReport::new(scanner_config.low_confidence),
+            item_positions: Vec::new(),
+            scanner_config,
             window_info,
             controller: Rc::new(RefCell::new(
                 GenshinRepositoryScanController::from_arg_matches(window_info_repo, arg_matches, game_info.clone(), true)?
             )),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             capturer: Self::get_capturer()?,
         })
     }
@@ -128,22 +155,60 @@ impl GenshinArtifactScanner {
             image::Rgb([188, 105, 50]),
         ];
 
-        let mut min_dis: usize = 0xdeadbeef;
-        let mut ret: usize = 1;
-        for (i, match_color) in match_colors.iter().enumerate() {
-            let dis2 = color_distance(match_color, &color);
-            if dis2 < min_dis {
-                min_dis = dis2;
-                ret = i + 1;
-            }
-        }
+        let ret = ColorClassifier::from_colors(&match_colors).classify(&color).label + 1;
 
         anyhow::Ok(ret)
     }
 
+    pub fn get_screen_state_classifier(&self) -> ScreenStateClassifier {
+        let language = self.scanner_config.game_language;
+        let (dialog_texts, character_texts): (&[&str], &[&str]) = match language {
+            GameLanguage::ZhCn => (&["确认", "取消"], &["属性"]),
+            GameLanguage::En => (&["Confirm", "Cancel"], &["Attributes"]),
+        };
+
+        let mut classifier = ScreenStateClassifier::new();
+        classifier.add_loading_rule();
+        if let Some(rect) = self.window_info.dialog_button_rect {
+            classifier.add_rule(ScreenState::Dialog, vec![ScreenProbe::text(rect, dialog_texts)]);
+        }
+        classifier.add_rule(
+            ScreenState::ArtifactInventory,
+            vec![ScreenProbe::text(self.window_info.item_count_rect, &[Self::inventory_title(language)])]
+        );
+        if let Some(rect) = self.window_info.character_anchor_rect {
+            classifier.add_rule(ScreenState::Character, vec![ScreenProbe::text(rect, character_texts)]);
+        }
+        classifier
+    }
+
+    /// Make sure the game is showing the artifact inventory before any click happens
+    pub fn check_screen_state(&self) -> Result<()> {
+        if self.scanner_config.skip_screen_check {
+            return Ok(());
+        }
+
+        let frame = self.capturer.capture_rect(self.game_info.window)?;
+        self.get_screen_state_classifier().ensure(
+            &frame,
+            self.image_to_text.as_ref(),
+            ScreenState::ArtifactInventory
+        )
+    }
+
+    /// Switch to the configured sort order if any, and return the order the scan runs in
+    pub fn prepare_sort_order(&self) -> Result<Option<SortOrder>> {
+        SortOrderSwitcher::new(
+            self.window_info.sort_order_layout(),
+            self.game_info.window,
+            self.scanner_config.game_language,
+            self.capturer.as_ref(),
+            self.image_to_text.as_ref(),
+        ).prepare(self.scanner_config.sort)
+    }
+
     pub fn get_item_count(&self) -> Result<i32> {
         let count = self.scanner_config.number;
-        let item_name = "圣遗物";
 
         let max_count = Self::MAX_COUNT as i32;
         if count > 0 {
@@ -159,49 +224,79 @@ impl GenshinArtifactScanner {
 
         info!("物品信息: {}", s);
 
-        if s.starts_with(item_name) {
-            let chars = s.chars().collect::<Vec<char>>();
-            let count_str = chars[4..chars.len() - 5].iter().collect::<String>();
-            Ok(match count_str.parse::<usize>() {
-                Ok(v) => (v as i32).min(max_count),
-                Err(_) => max_count,
-            })
-        } else {
-            Ok(max_count)
+        Ok(Self::parse_item_count(&s, self.scanner_config.game_language))
+    }
+
+    /// The count in the inventory title, e.g. "圣遗物 1234/2100", "Artifacts 1234/2100", the max count if it cannot be read
+    fn parse_item_count(s: &str, language: GameLanguage) -> i32 {
+        let max_count = Self::MAX_COUNT as i32;
+        match s.strip_prefix(Self::inventory_title(language)) {
+            Some(rest) => {
+                // e.g. " 1234/2100"
+                let count_str = rest.split('/').next().unwrap_or("").trim();
+                match count_str.parse::<usize>() {
+                    Ok(v) => (v as i32).min(max_count),
+                    Err(_) => max_count,
+                }
+            },
+            None => max_count,
         }
     }
 
+    /// The language of the game client, which the scan results are in
+    pub fn game_language(&self) -> GameLanguage {
+        self.scanner_config.game_language
+    }
+
+    /// Recognized fields of the last scan that the user should double check
+    pub fn low_confidence_report(&self) -> &LowConfidenceReport {
+        &self.low_confidence_report
+    }
+
+    /// The position in the repository of each item returned by the last `scan`, starting from 0
+    pub fn item_positions(&self) -> &[usize] {
+        &self.item_positions
+    }
+
     pub fn scan(&mut self) -> Result<Vec<GenshinArtifactScanResult>> {
         info!("开始扫描，使用鼠标右键中断扫描");
 
+        self.check_screen_state()?;
+        let sort_order = self.prepare_sort_order()?;
+
         let now = SystemTime::now();
-        let (tx, rx) = mpsc::channel::<Option<SendItem>>();
         // let token = self.cancellation_token.clone();
         let count = self.get_item_count()?;
-        let worker = ArtifactScannerWorker::new(
-            self.window_info.clone(),
-            self.scanner_config.clone(),
-        )?;
-
-        let join_handle = worker.run(rx);
-        info!("Worker created");
+        let worker_count = self.scanner_config.ocr.workers.max(1);
+        let mut workers = Vec::with_capacity(worker_count);
+        for _ in 0..worker_count {
+            workers.push(ArtifactScannerWorker::new(
+                self.window_info.clone(),
+                self.scanner_config.clone(),
+                sort_order,
+            )?);
+        }
 
-        self.send(&tx, count);
+        let (feedback_tx, feedback_rx) = mpsc::channel();
+        let (mut tx, join_handle) = ArtifactScannerWorker::run(workers, self.scanner_config.ocr.queue_size, feedback_tx);
+        info!("创建 {} 个识别线程", worker_count);
 
-        match tx.send(None) {
-            Ok(_) => info!("扫描结束，等待识别线程结束，请勿关闭程序"),
-            Err(_) => info!("扫描结束，识别已完成"),
-        }
+        self.send(&mut tx, &mut PageRetry::new(feedback_rx), count, sort_order);
+        drop(tx);
+        info!("扫描结束，等待识别线程结束，请勿关闭程序");
 
         match join_handle.join() {
-            Ok(v) => {
+            Ok((v, low_confidence_report)) => {
                 info!("识别耗时: {:?}", now.elapsed()?);
+                low_confidence_report.log();
+                self.low_confidence_report = low_confidence_report;
 
                 // filter min level
                 let min_level = self.scanner_config.min_level;
-                let v = v.iter().filter(|a| {
+                let (positions, v): (Vec<usize>, Vec<_>) = v.into_iter().filter(|(_, a)| {
                     a.level >= min_level
-                }).cloned().collect();
+                }).unzip();
+                self.item_positions = positions;
 
                 Ok(v)
             }
@@ -235,14 +330,52 @@ impl GenshinArtifactScanner {
         }
     }
 
-    fn send(&mut self, tx: &Sender<Option<SendItem>>, count: i32) {
+    /// Capture again the items of the current page which fail to parse, returns false if the scan should stop
+    fn retry_page(&mut self, tx: &mut PoolSender<SendItem>, retry: &mut PageRetry) -> Result<bool> {
+        while let Some(item) = retry.next_retry() {
+            if utils::is_rmb_down() {
+                info!("用户中断");
+                return Ok(false);
+            }
+
+            info!("重新截图识别第 {} 个物品，第 {} 次重试", item.index + 1, item.attempt);
+            self.controller.borrow_mut().reselect(item.row, item.col, self.scanner_config.retry_wait * item.attempt as u32)?;
+
+            let send_item = SendItem {
+                index: item.index,
+                attempt: item.attempt,
+                panel_image: self.capture_panel()?,
+                star: self.get_star()?,
+                list_image: None,
+            };
+            if tx.send(send_item).is_err() {
+                return Ok(false);
+            }
+            retry.sent(item.index, item.row, item.col);
+        }
+
+        Ok(true)
+    }
+
+    fn send(&mut self, tx: &mut PoolSender<SendItem>, retry: &mut PageRetry, count: i32, sort_order: Option<SortOrder>) {
         let mut generator = GenshinRepositoryScanController::get_generator(self.controller.clone(), count as usize);
         let mut artifact_index: i32 = 0;
 
         loop {
             let pinned_generator = Pin::new(&mut generator);
             match pinned_generator.resume(()) {
-                CoroutineState::Yielded(_) => {
+                CoroutineState::Yielded(ScanEvent::PageEnd) => {
+                    match self.retry_page(tx, retry) {
+                        Ok(true) => (),
+                        Ok(false) => break,
+                        Err(e) => {
+                            error!("重新截图发生错误：{}", e);
+                            break;
+                        }
+                    }
+                    retry.end_page();
+                }
+                CoroutineState::Yielded(ScanEvent::Item { index, row, col }) => {
                     let image = self.capture_panel().unwrap();
                     let star = self.get_star().unwrap();
 
@@ -284,7 +417,8 @@ impl GenshinArtifactScanner {
                     artifact_index = artifact_index + 1;
 
                     // todo normalize types
-                    if (star as i32) < self.scanner_config.min_star {
+                    // low star items are still sent when not sorted by rarity, the worker filters them
+                    if (star as i32) < self.scanner_config.min_star && SortOrder::can_stop_at_star(sort_order) {
                         info!(
                             "找到满足最低星级要求 {} 的物品，准备退出……",
                             self.scanner_config.min_star
@@ -293,15 +427,19 @@ impl GenshinArtifactScanner {
                     }
 
                     if tx
-                        .send(Some(SendItem {
+                        .send(SendItem {
+                            index,
+                            attempt: 0,
                             panel_image: image,
                             star,
                             list_image,
-                        }))
+                        })
                         .is_err()
                     {
                         break;
                     }
+                    retry.sent(index, row, col);
+                    retry.poll();
 
                     // scanned_count += 1;
                 }
@@ -321,4 +459,45 @@ impl GenshinArtifactScanner {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_item_count() {
+        assert_eq!(GenshinArtifactScanner::parse_item_count("圣遗物 1234/2100", GameLanguage::ZhCn), 1234);
+        assert_eq!(GenshinArtifactScanner::parse_item_count("Artifacts 1234/2100", GameLanguage::En), 1234);
+        assert_eq!(GenshinArtifactScanner::parse_item_count("Artifacts 1234/2100", GameLanguage::ZhCn), 2100);
+        assert_eq!(GenshinArtifactScanner::parse_item_count("Artifacts ?/2100", GameLanguage::En), 2100);
+    }
+
+    #[cfg(all(feature = "ort", feature = "tract_onnx"))]
+    mod backend_parity {
+        use yas::ocr::{load_ocr_samples, ocr_samples_dir, OCRBackend, OCRResult};
+
+        use super::super::*;
+
+        fn recognize_samples(backend: OCRBackend, samples: &[(String, RgbImage)]) -> Vec<OCRResult> {
+            let config = OCRConfig { backend: Some(backend), intra_threads: 1, ..OCRConfig::default() };
+            let model = load_yas_model(&config).unwrap();
+            samples.iter()
+                .map(|(_, image)| model.image_to_text_with_confidence(image, false).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn test_backend_parity() {
+            let samples = load_ocr_samples(ocr_samples_dir()).unwrap();
+            assert!(!samples.is_empty());
+            let ort = recognize_samples(OCRBackend::Ort, &samples);
+            let tract = recognize_samples(OCRBackend::Tract, &samples);
+
+            for (((name, _), a), b) in samples.iter().zip(ort.iter()).zip(tract.iter()) {
+                assert_eq!(a.text, b.text, "{}", name);
+                assert!((a.confidence - b.confidence).abs() < 1e-3, "{}: {} {}", name, a.confidence, b.confidence);
+            }
+        }
+    }
+}
@@ -1,3 +1,8 @@
+use log::warn;
+use yas::game_language::GameLanguage;
+use yas::ocr::OCRConfig;
+use yas::sort_order::SortOrder;
+
 #[derive(Clone, clap::Args)]
 pub struct GenshinArtifactScannerConfig {
     /// Items with stars less than this will be ignored
@@ -19,4 +24,53 @@ pub struct GenshinArtifactScannerConfig {
     /// the exact amount to scan
     #[arg(id = "number", long, help = "指定圣遗物数量", value_name = "NUMBER", default_value_t = -1)]
     pub number: i32,
+
+    /// Do not check whether the game is showing the artifact inventory before scanning
+    #[arg(id = "skip-screen-check", long = "skip-screen-check", help = "跳过界面检查")]
+    pub skip_screen_check: bool,
+
+    /// Switch the inventory to this sort order before scanning
+    #[arg(id = "sort", long = "sort", help = "扫描前切换排序方式", value_name = "SORT", value_enum)]
+    pub sort: Option<SortOrder>,
+
+    /// OCR results less confident than this are listed for double checking
+    #[arg(id = "low-confidence", long = "low-confidence", help = "置信度低于该值的识别结果将被列出以供核对", value_name = "CONFIDENCE", default_value_t = 0.9)]
+    pub low_confidence: f32,
+
+    /// Decode OCR results greedily, instead of constraining them to known names and number formats
+    #[arg(id = "greedy-decoding", long = "greedy-decoding", help = "不使用词表与数值格式约束识别结果")]
+    pub greedy_decoding: bool,
+
+    /// Items whose text cannot be parsed are selected and captured again, up to this many times
+    #[arg(id = "retry-attempts", long = "retry-attempts", help = "识别结果无法解析时重新截图识别的最大次数，为 0 时不重试", value_name = "ATTEMPTS", default_value_t = 2)]
+    pub retry_attempts: usize,
+
+    /// The time to wait before capturing an item again, in addition to the usual, multiplied by the attempt
+    #[arg(id = "retry-wait", long = "retry-wait", help = "重新截图前额外等待的时间（ms），随重试次数递增", value_name = "MS", default_value_t = 200)]
+    pub retry_wait: u32,
+
+    /// The language of the game client, the built-in yas model only reads Simplified Chinese
+    #[arg(id = "game-language", long = "game-language", help = "游戏语言，非简体中文时默认使用 ppocr 模型", value_name = "LANGUAGE", value_enum, default_value_t = GameLanguage::ZhCn)]
+    pub game_language: GameLanguage,
+
+    #[command(flatten)]
+    pub ocr: OCRConfig,
+}
+
+impl GenshinArtifactScannerConfig {
+    /// Replace the yas model, which cannot read the game language, with ppocr
+    pub fn adapt_ocr_to_language(&mut self) {
+        if self.game_language.is_supported_by_yas_model() {
+            return;
+        }
+
+        if self.ocr.model == "yas" {
+            warn!("yas 模型仅支持简体中文，改用 ppocr 模型");
+            self.ocr.model = String::from("ppocr");
+        }
+        if self.ocr.fallback_model.as_deref() == Some("yas") {
+            warn!("yas 模型仅支持简体中文，不使用备用模型");
+            self.ocr.fallback_model = None;
+        }
+    }
 }
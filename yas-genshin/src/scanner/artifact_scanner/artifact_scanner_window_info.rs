@@ -1,4 +1,6 @@
+use yas::game_language::GameLanguage;
 use yas::positioning::{Pos, Rect, Size};
+use yas::sort_order::SortOrderLayout;
 
 #[derive(Clone, yas_derive::YasWindowInfo, Debug)]
 pub struct ArtifactScannerWindowInfo {
@@ -32,6 +34,18 @@ pub struct ArtifactScannerWindowInfo {
     #[window_info(rename = "genshin_artifact_item_equip_rect")]
     pub item_equip_rect: Rect<f64>,
 
+    /// the title of the English client, which is longer, optional
+    #[window_info(rename = "genshin_artifact_title_en_rect")]
+    pub title_en_rect: Option<Rect<f64>>,
+
+    /// the second line of a wrapped English title, where the slot is shown if the title fits in one line, optional
+    #[window_info(rename = "genshin_artifact_title_wrap_en_rect")]
+    pub title_wrap_en_rect: Option<Rect<f64>>,
+
+    /// the equip status of the English client, "Equipped: X" is longer, optional
+    #[window_info(rename = "genshin_artifact_item_equip_en_rect")]
+    pub item_equip_en_rect: Option<Rect<f64>>,
+
     /// the count of artifacts relative to window
     #[window_info(rename = "genshin_artifact_item_count_rect")]
     pub item_count_rect: Rect<f64>,
@@ -70,4 +84,57 @@ pub struct ArtifactScannerWindowInfo {
 
     #[window_info(rename = "genshin_repository_lock_pos")]
     pub lock_pos: Pos<f64>,
+
+    /// a text region only shown in the character screen, optional
+    #[window_info(rename = "genshin_screen_character_anchor_rect")]
+    pub character_anchor_rect: Option<Rect<f64>>,
+
+    /// the button region of a popup dialog, optional
+    #[window_info(rename = "genshin_screen_dialog_button_rect")]
+    pub dialog_button_rect: Option<Rect<f64>>,
+
+    /// the sort dropdown of the inventory, optional
+    #[window_info(rename = "genshin_repository_sort_rect")]
+    pub sort_rect: Option<Rect<f64>>,
+
+    /// the options of the opened sort dropdown, optional
+    #[window_info(rename = "genshin_repository_sort_rarity_pos")]
+    pub sort_rarity_pos: Option<Pos<f64>>,
+    #[window_info(rename = "genshin_repository_sort_level_pos")]
+    pub sort_level_pos: Option<Pos<f64>>,
+    #[window_info(rename = "genshin_repository_sort_time_pos")]
+    pub sort_time_pos: Option<Pos<f64>>,
+}
+
+impl ArtifactScannerWindowInfo {
+    pub fn title_rect_of(&self, language: GameLanguage) -> Rect<f64> {
+        match language {
+            GameLanguage::ZhCn => self.title_rect,
+            GameLanguage::En => self.title_en_rect.unwrap_or(self.title_rect),
+        }
+    }
+
+    /// Only English titles wrap
+    pub fn title_wrap_rect_of(&self, language: GameLanguage) -> Option<Rect<f64>> {
+        match language {
+            GameLanguage::ZhCn => None,
+            GameLanguage::En => self.title_wrap_en_rect,
+        }
+    }
+
+    pub fn item_equip_rect_of(&self, language: GameLanguage) -> Rect<f64> {
+        match language {
+            GameLanguage::ZhCn => self.item_equip_rect,
+            GameLanguage::En => self.item_equip_en_rect.unwrap_or(self.item_equip_rect),
+        }
+    }
+
+    pub fn sort_order_layout(&self) -> SortOrderLayout {
+        SortOrderLayout {
+            sort_rect: self.sort_rect,
+            rarity_pos: self.sort_rarity_pos,
+            level_pos: self.sort_level_pos,
+            time_pos: self.sort_time_pos,
+        }
+    }
 }
@@ -1,5 +1,5 @@
-use std::collections::HashSet;
-use std::sync::mpsc::Receiver;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::Sender;
 use std::thread::JoinHandle;
 
 use anyhow::Result;
@@ -7,15 +7,25 @@ use image::Rgb;
 use image::{GenericImageView, RgbImage};
 use log::{error, info, warn};
 
-use yas::ocr::ImageToText;
-use yas::ocr::yas_ocr_model;
+use yas::ocr::{LowConfidenceReport, OCRModelSet, RegionRequirement, ScanConfidence};
 use yas::positioning::{Pos, Rect};
-use yas::utils::color_distance;
+use yas::sort_order::SortOrder;
+use yas::vision::find_color;
+use yas::worker_pool::{ordered_worker_pool, PoolSender, ScanFeedback};
 
+use crate::artifact::join_title_lines;
 use crate::scanner::artifact_scanner::artifact_scanner_window_info::ArtifactScannerWindowInfo;
 use crate::scanner::artifact_scanner::GenshinArtifactScannerConfig;
 use crate::scanner::artifact_scanner::message_items::SendItem;
 use crate::scanner::artifact_scanner::scan_result::GenshinArtifactScanResult;
+use crate::scanner::artifact_scanner::GenshinArtifactScanner;
+use crate::scanner::artifact_scanner::region_requirements::get_region_requirements;
+
+/// The recognized items with their indices in the repository, and the fields of low confidence
+type ScanResults = (Vec<(usize, GenshinArtifactScanResult)>, LowConfidenceReport);
+
+/// The text of a sub stat is dark, a sub stat which is not activated yet is greyed out and much lighter
+const GREYED_TEXT_MIN_LUMA: u8 = 120;
 
 fn parse_level(s: &str) -> Result<i32> {
     let pos = s.find('+');
@@ -29,63 +39,138 @@ fn parse_level(s: &str) -> Result<i32> {
     anyhow::Ok(level)
 }
 
-fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-    let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-    );
-    Ok(model)
-}
-
 /// run in a separate thread, accept captured image and get an artifact
 pub struct ArtifactScannerWorker {
-    model: Box<dyn ImageToText<RgbImage> + Send>,
+    models: OCRModelSet,
     window_info: ArtifactScannerWindowInfo,
     config: GenshinArtifactScannerConfig,
+    /// the sort order of the inventory, None if it is not recognized
+    sort_order: Option<SortOrder>,
+    /// the text of the OCR regions which can be parsed, by field name
+    region_requirements: HashMap<&'static str, RegionRequirement>,
 }
 
 impl ArtifactScannerWorker {
     pub fn new(
         window_info: ArtifactScannerWindowInfo,
         config: GenshinArtifactScannerConfig,
+        sort_order: Option<SortOrder>,
     ) -> Result<Self> {
         Ok(ArtifactScannerWorker {
-            model: get_image_to_text()?,
+            models: GenshinArtifactScanner::get_model_registry().load_set(&config.ocr)?,
             window_info,
+            sort_order,
+            region_requirements: get_region_requirements(config.game_language),
             config,
         })
     }
 
-    /// the captured_img is a panel of the artifact, the rect is a region of the panel
-    fn model_inference(&self, rect: Rect<f64>, captured_img: &RgbImage) -> Result<String> {
-        let relative_rect = rect.translate(Pos {
+    /// the captured_img is a panel of the artifact, the rects are regions of the panel
+    /// all regions are recognized in batches, the texts are in the same order as `fields`
+    /// unless it is the last attempt, an error is returned if any text cannot be parsed, so that the item is captured again
+    fn model_inference(
+        &self,
+        fields: &[(&str, Rect<f64>)],
+        captured_img: &RgbImage,
+        attempt: usize,
+        confidence: &mut ScanConfidence
+    ) -> Result<Vec<String>> {
+        let relative_rects = fields.iter().map(|(_, rect)| rect.translate(Pos {
             x: -self.window_info.panel_rect.left,
             y: -self.window_info.panel_rect.top,
-        });
+        })).collect::<Vec<_>>();
+
+        let raw_images = relative_rects.iter().map(|rect| captured_img.view(
+            rect.left as u32, rect.top as u32, rect.width as u32, rect.height as u32,
+        ).to_image()).collect::<Vec<_>>();
+
+        let regions = fields.iter()
+            .zip(raw_images.iter())
+            .map(|(&(field, _), im)| (field, im, self.region_requirements.get(field)))
+            .collect::<Vec<_>>();
+
+        let batch_size = self.config.ocr.batch_size;
+        let constrained = !self.config.greedy_decoding;
+        // the model has failed the item once, so the fallback model gets the first try
+        let results = if attempt > 0 {
+            self.models.recognize_fallback_first(&regions, batch_size, constrained)?
+        } else {
+            self.models.recognize(&regions, batch_size, constrained)?
+        };
+
+        if attempt < self.config.retry_attempts {
+            for (&(field, _, requirement), result) in regions.iter().zip(results.iter()) {
+                if requirement.map_or(false, |r| !r.is_parseable(&result.text)) {
+                    return Err(anyhow::anyhow!("{} 的识别结果 \"{}\" 无法解析", field, result.text));
+                }
+            }
+        }
 
-        let raw_img = captured_img.view(
-            relative_rect.left as u32, relative_rect.top as u32, relative_rect.width as u32, relative_rect.height as u32,
-        ).to_image();
+        let mut texts = Vec::with_capacity(results.len());
+        for ((&(field, _), rect), result) in fields.iter().zip(relative_rects).zip(results) {
+            confidence.push(field, rect, &result);
+            texts.push(result.text);
+        }
 
-        let inference_result = self.model.image_to_text(&raw_img, false);
+        Ok(texts)
+    }
 
-        inference_result
+    /// Whether the text in `rect` of the panel is greyed out, i.e. even the darkest pixel is light
+    fn is_greyed_out(&self, panel_image: &RgbImage, rect: Rect<f64>) -> bool {
+        let rect = rect.translate(Pos {
+            x: -self.window_info.panel_rect.left,
+            y: -self.window_info.panel_rect.top,
+        });
+        let view = panel_image.view(rect.left as u32, rect.top as u32, rect.width as u32, rect.height as u32);
+
+        let darkest = view.pixels()
+            .map(|(_, _, Rgb([r, g, b]))| ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8)
+            .min();
+        darkest.map_or(false, |luma| luma >= GREYED_TEXT_MIN_LUMA)
     }
 
     /// Parse the captured result (of type SendItem) to a scanned artifact
-    fn scan_item_image(&self, item: SendItem, lock: bool) -> Result<GenshinArtifactScanResult> {
+    /// the lock state is left unset, it is parsed from the list images in the order of the items
+    fn scan_item_image(&self, item: &SendItem) -> Result<GenshinArtifactScanResult> {
         let image = &item.panel_image;
+        let mut confidence = ScanConfidence::default();
+
+        let language = self.config.game_language;
+        let title_wrap_rect = self.window_info.title_wrap_rect_of(language);
+        // a wrapped title is read line by line without the constraint of the titles, and corrected after joined
+        let title_field = if title_wrap_rect.is_some() { "title_line" } else { "title" };
+        let mut fields = vec![
+            (title_field, self.window_info.title_rect_of(language)),
+            ("main_stat_name", self.window_info.main_stat_name_rect),
+            ("main_stat_value", self.window_info.main_stat_value_rect),
+            ("sub_stat_1", self.window_info.sub_stat_1),
+            ("sub_stat_2", self.window_info.sub_stat_2),
+            ("sub_stat_3", self.window_info.sub_stat_3),
+            ("sub_stat_4", self.window_info.sub_stat_4),
+            ("level", self.window_info.level_rect),
+            ("equip", self.window_info.item_equip_rect_of(language)),
+        ];
+        if let Some(rect) = title_wrap_rect {
+            fields.push(("title_wrap", rect));
+        }
 
-        let str_title = self.model_inference(self.window_info.title_rect, image)?;
-        let str_main_stat_name = self.model_inference(self.window_info.main_stat_name_rect, image)?;
-        let str_main_stat_value = self.model_inference(self.window_info.main_stat_value_rect, image)?;
-
-        let str_sub_stat0 = self.model_inference(self.window_info.sub_stat_1, image)?;
-        let str_sub_stat1 = self.model_inference(self.window_info.sub_stat_2, image)?;
-        let str_sub_stat2 = self.model_inference(self.window_info.sub_stat_3, image)?;
-        let str_sub_stat3 = self.model_inference(self.window_info.sub_stat_4, image)?;
-
-        let str_level = self.model_inference(self.window_info.level_rect, image)?;
-        let str_equip = self.model_inference(self.window_info.item_equip_rect, image)?;
+        let mut texts = self.model_inference(&fields, image, item.attempt, &mut confidence)?;
+        let title_wrap = title_wrap_rect.and_then(|_| texts.pop());
+        let [
+            str_title,
+            str_main_stat_name,
+            str_main_stat_value,
+            str_sub_stat0,
+            str_sub_stat1,
+            str_sub_stat2,
+            str_sub_stat3,
+            str_level,
+            str_equip,
+        ]: [String; 9] = texts.try_into().unwrap();
+        let str_title = match title_wrap {
+            Some(line) => join_title_lines(&str_title, &line),
+            None => str_title,
+        };
 
         anyhow::Ok(GenshinArtifactScanResult {
             name: str_title,
@@ -97,22 +182,24 @@ impl ArtifactScannerWorker {
                 str_sub_stat2,
                 str_sub_stat3,
             ],
+            sub_stat_4_activated: !self.is_greyed_out(image, self.window_info.sub_stat_4),
             level: parse_level(&str_level)?,
             equip: str_equip,
             star: item.star as i32,
-            lock,
+            lock: false,
+            confidence,
         })
     }
 
     /// Get all lock state from a list image
-    fn get_page_locks(&self, list_image: &RgbImage) -> Vec<bool> {
+    fn get_page_locks(window_info: &ArtifactScannerWindowInfo, list_image: &RgbImage) -> Vec<bool> {
         let mut result = Vec::new();
 
-        let row = self.window_info.row;
-        let col = self.window_info.col;
-        let gap = self.window_info.item_gap_size;
-        let size = self.window_info.item_size;
-        let lock_pos = self.window_info.lock_pos;
+        let row = window_info.row;
+        let col = window_info.col;
+        let gap = window_info.item_gap_size;
+        let size = window_info.item_size;
+        let lock_pos = window_info.lock_pos;
 
         for r in 0..row {
             if ((gap.height + size.height) * (r as f64)) as u32 > list_image.height() {
@@ -122,66 +209,78 @@ impl ArtifactScannerWorker {
                 let pos_x = (gap.width + size.width) * (c as f64) + lock_pos.x;
                 let pos_y = (gap.height + size.height) * (r as f64) + lock_pos.y;
 
-                let mut locked = false;
-                'sq: for dx in -1..1 {
-                    for dy in -10..10 {
-                        if pos_y as i32 + dy < 0 || (pos_y as i32 + dy) as u32 >= list_image.height() {
-                            continue;
-                        }
-
-                        let color = list_image
-                            .get_pixel((pos_x as i32 + dx) as u32, (pos_y as i32 + dy) as u32);
-
-                        if color_distance(color, &Rgb([255, 138, 117])) < 30 {
-                            locked = true;
-                            break 'sq;
-                        }
-                    }
-                }
+                let region = Rect {
+                    left: pos_x as i32 - 1,
+                    top: pos_y as i32 - 10,
+                    width: 2,
+                    height: 20,
+                };
+                let locked = find_color(list_image, region, &Rgb([255, 138, 117]), 30).is_some();
                 result.push(locked);
             }
         }
         result
     }
 
-    pub fn run(self, rx: Receiver<Option<SendItem>>) -> JoinHandle<Vec<GenshinArtifactScanResult>> {
-        std::thread::spawn(move || {
+    /// Recognize the items with a pool of workers, and collect the results in the order of the items
+    /// every recognized item is reported through `feedback`, the results are returned once the sender is dropped,
+    /// each with its position in the repository
+    pub fn run(
+        workers: Vec<ArtifactScannerWorker>,
+        queue_size: usize,
+        feedback: Sender<ScanFeedback>,
+    ) -> (PoolSender<SendItem>, JoinHandle<ScanResults>) {
+        let config = workers[0].config.clone();
+        let info = workers[0].window_info.clone();
+        let sort_order = workers[0].sort_order;
+
+        let (tx, rx) = ordered_worker_pool(workers, queue_size, |worker: &mut ArtifactScannerWorker, item: SendItem| {
+            let result = worker.scan_item_image(&item);
+            (item, result)
+        });
+
+        let join_handle = std::thread::spawn(move || {
             let mut results = Vec::new();
+            let mut low_confidence = LowConfidenceReport::new(config.low_confidence);
             let mut hash: HashSet<GenshinArtifactScanResult> = HashSet::new();
             // if too many artifacts are same in consecutive, then an error has occurred
             let mut consecutive_dup_count = 0;
 
-            let is_verbose = self.config.verbose;
-            let min_level = self.config.min_level;
-            let info = self.window_info.clone();
+            let is_verbose = config.verbose;
+            let min_star = config.min_star;
+            let min_level = config.min_level;
             // todo remove dump mode to another scanner
             // let dump_mode = false;
             // let model = self.model.clone();
             // let panel_origin = Pos { x: self.window_info.panel_rect.left, y: self.window_info.panel_rect.top };
 
             let mut locks = Vec::new();
-            let mut artifact_index: i32 = 0;
-
-            for item in rx.into_iter() {
-                // receiving None, which means the worker should end
-                let item = match item {
-                    Some(v) => v,
-                    None => break,
-                };
 
+            for (item, result) in rx {
                 // if there is a list image, then parse the lock state
-                match item.list_image.as_ref() {
-                    Some(v) => {
-                        locks = vec![locks, self.get_page_locks(v)].concat()
-                    }
-                    None => {}
-                };
+                if let Some(v) = item.list_image.as_ref() {
+                    locks.extend(Self::get_page_locks(&info, v));
+                }
 
-                artifact_index += 1;
-                let result = match self.scan_item_image(item, locks[artifact_index as usize - 1]) {
-                    Ok(v) => v,
+                let retry = result.is_err() && item.attempt < config.retry_attempts;
+                // the capturing thread may have finished, then there is no one to retry
+                let _ = feedback.send(ScanFeedback {
+                    index: item.index,
+                    attempt: item.attempt,
+                    retry,
+                });
+
+                let result = match result {
+                    Ok(mut v) => {
+                        v.lock = locks[item.index];
+                        v
+                    },
                     Err(e) => {
-                        error!("识别错误: {}", e);
+                        if retry {
+                            warn!("第 {} 个物品识别失败: {}", item.index + 1, e);
+                        } else {
+                            error!("识别错误: {}", e);
+                        }
                         continue;
                     }
                 };
@@ -190,7 +289,15 @@ impl ArtifactScannerWorker {
                     info!("{:?}", result);
                 }
 
+                // the scanner only stops at low star items when sorted by rarity
+                if result.star < min_star {
+                    continue;
+                }
+
                 if result.level < min_level {
+                    if !SortOrder::can_stop_at_level(sort_order) {
+                        continue;
+                    }
                     info!(
                         "找到满足最低等级要求 {} 的物品({})，准备退出……",
                         min_level, result.level
@@ -198,16 +305,22 @@ impl ArtifactScannerWorker {
                     break;
                 }
 
+                // the items captured again are out of the scan order, they do not count as consecutive
                 if hash.contains(&result) {
-                    consecutive_dup_count += 1;
+                    if item.attempt == 0 {
+                        consecutive_dup_count += 1;
+                    }
                     warn!("识别到重复物品: {:#?}", result);
                 } else {
-                    consecutive_dup_count = 0;
+                    if item.attempt == 0 {
+                        consecutive_dup_count = 0;
+                    }
+                    low_confidence.add(item.index, &result.confidence, &item.panel_image);
                     hash.insert(result.clone());
-                    results.push(result);
+                    results.push((item.index, result));
                 }
 
-                if consecutive_dup_count >= info.col && !self.config.ignore_dup {
+                if consecutive_dup_count >= info.col && !config.ignore_dup {
                     error!("识别到连续多个重复物品，可能为翻页错误，或者为非背包顶部开始扫描");
                     // token.cancel();
                     break;
@@ -221,10 +334,14 @@ impl ArtifactScannerWorker {
 
             info!("识别结束，非重复物品数量: {}", hash.len());
 
+            results.sort_by_key(|&(index, _)| index);
+
             // progress_bar.finish();
             // MULTI_PROGRESS.remove(&progress_bar);
 
-            results
-        })
+            (results, low_confidence)
+        });
+
+        (tx, join_handle)
     }
 }
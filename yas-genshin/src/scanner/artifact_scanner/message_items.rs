@@ -2,7 +2,12 @@ use image::RgbImage;
 
 /// this is constructed by the capturing thread, and sent to the worker thread
 pub struct SendItem {
+    /// the position of the item in scan order, starting from 0
+    pub index: usize,
+    /// 0 for the first capture, and the number of retries for the captures again
+    pub attempt: usize,
     pub panel_image: RgbImage,
     pub star: usize,
     pub list_image: Option<RgbImage>,
 }
+
@@ -9,3 +9,4 @@ mod scan_result;
 mod artifact_scanner_worker;
 pub mod artifact_scanner_window_info;
 mod message_items;
+mod region_requirements;
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use yas::game_language::GameLanguage;
+use yas::ocr::{RegionRequirement, TextConstraint};
+
+use crate::artifact::{artifact_names, character_names, equip_label, stat_names, sub_stat_names, unactivated_marks};
+
+/// e.g. `4,780` or `46.6%`
+fn stat_value() -> TextConstraint {
+    TextConstraint::Any(vec![
+        TextConstraint::Number { thousands_separator: true, max_decimals: 0 },
+        TextConstraint::Sequence(vec![
+            TextConstraint::Number { thousands_separator: false, max_decimals: 1 },
+            TextConstraint::Literal("%"),
+        ]),
+    ])
+}
+
+/// The text of the OCR regions which can be parsed, by field name
+/// artifacts of low rarity have less than four sub stats, and most artifacts are not equipped
+/// the fourth sub stat may be followed by the mark of not being activated
+pub fn get_region_requirements(language: GameLanguage) -> HashMap<&'static str, RegionRequirement> {
+    let sub_stat = TextConstraint::Sequence(vec![
+        TextConstraint::lexicon(sub_stat_names(language)),
+        TextConstraint::Literal("+"),
+        stat_value(),
+    ]);
+    let sub_stat_4 = TextConstraint::Any(vec![
+        sub_stat.clone(),
+        TextConstraint::Sequence(vec![
+            sub_stat.clone(),
+            TextConstraint::Any(unactivated_marks(language).iter().map(|&mark| TextConstraint::Literal(mark)).collect()),
+        ]),
+    ]);
+    let level = TextConstraint::Number { thousands_separator: false, max_decimals: 0 };
+    // e.g. "芙宁娜已装备", "Equipped: Furina", the empty part of the label matches nothing
+    let (equip_prefix, equip_suffix) = equip_label(language);
+
+    HashMap::from([
+        ("title", RegionRequirement::required(TextConstraint::lexicon(artifact_names(language)))),
+        ("main_stat_name", RegionRequirement::required(TextConstraint::lexicon(stat_names(language)))),
+        ("main_stat_value", RegionRequirement::required(stat_value())),
+        ("sub_stat_1", RegionRequirement::optional(sub_stat.clone())),
+        ("sub_stat_2", RegionRequirement::optional(sub_stat.clone())),
+        ("sub_stat_3", RegionRequirement::optional(sub_stat.clone())),
+        ("sub_stat_4", RegionRequirement::optional(sub_stat_4)),
+        ("level", RegionRequirement::required(TextConstraint::Any(vec![
+            TextConstraint::Sequence(vec![TextConstraint::Literal("+"), level.clone()]),
+            level,
+        ]))),
+        ("equip", RegionRequirement::optional(TextConstraint::Sequence(vec![
+            TextConstraint::Literal(equip_prefix),
+            TextConstraint::lexicon(character_names(language)),
+            TextConstraint::Literal(equip_suffix),
+        ]))),
+    ])
+}
@@ -1,11 +1,16 @@
+use yas::ocr::ScanConfidence;
+
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub struct GenshinArtifactScanResult {
     pub name: String,
     pub main_stat_name: String,
     pub main_stat_value: String,
     pub sub_stat: [String; 4],
+    /// false if the fourth sub stat is greyed out, i.e. not activated yet
+    pub sub_stat_4_activated: bool,
     pub equip: String,
     pub level: i32,
     pub star: i32,
     pub lock: bool,
+    pub confidence: ScanConfidence,
 }
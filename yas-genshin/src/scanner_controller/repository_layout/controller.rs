@@ -13,15 +13,19 @@ use yas::game_info::GameInfo;
 use yas::positioning::{Pos, Size};
 use yas::system_control::SystemControl;
 use yas::utils;
+use yas::vision::ColorFlag;
 use yas::window_info::{FromWindowInfoRepository, WindowInfoRepository};
 
-use crate::scanner_controller::repository_layout::{GenshinRepositoryScanControllerWindowInfo, GenshinRepositoryScannerLogicConfig, ScrollResult};
+use crate::scanner_controller::repository_layout::{GenshinRepositoryScanControllerWindowInfo, GenshinRepositoryScannerLogicConfig, ScanEvent, ScrollResult};
+
+/// The squared color distance within which the flag pixel is unchanged
+const FLAG_MAX_DISTANCE: usize = 11;
 
 pub struct GenshinRepositoryScanController {
     // to detect whether an item changes
     pool: f64,
 
-    initial_color: image::Rgb<u8>,
+    initial_flag: ColorFlag,
 
     // for scrolls
     scrolled_rows: u32,
@@ -59,13 +63,6 @@ fn get_capturer() -> Result<Rc<dyn Capturer<RgbImage>>> {
     Ok(Rc::new(GenericCapturer::new()?))
 }
 
-fn color_distance(c1: &image::Rgb<u8>, c2: &image::Rgb<u8>) -> usize {
-    let x = c1.0[0] as i32 - c2.0[0] as i32;
-    let y = c1.0[1] as i32 - c2.0[1] as i32;
-    let z = c1.0[2] as i32 - c2.0[2] as i32;
-    (x * x + y * y + z * z) as usize
-}
-
 // constructor
 impl GenshinRepositoryScanController {
     pub fn new(
@@ -94,7 +91,7 @@ impl GenshinRepositoryScanController {
 
             pool: 0.0,
 
-            initial_color: image::Rgb([0, 0, 0]),
+            initial_flag: ColorFlag::from_pixel(image::Rgb([0, 0, 0]), FLAG_MAX_DISTANCE),
 
             scrolled_rows: 0,
             avg_scroll_one_row: 0.0,
@@ -132,7 +129,7 @@ pub enum ReturnResult {
 }
 
 impl GenshinRepositoryScanController {
-    pub fn get_generator(object: Rc<RefCell<GenshinRepositoryScanController>>, item_count: usize) -> impl Coroutine<Yield=(), Return=Result<ReturnResult>> {
+    pub fn get_generator(object: Rc<RefCell<GenshinRepositoryScanController>>, item_count: usize) -> impl Coroutine<Yield=ScanEvent, Return=Result<ReturnResult>> {
         let generator = #[coroutine] move || {
             let mut scanned_row = 0;
             let mut scanned_count = 0;
@@ -189,7 +186,7 @@ impl GenshinRepositoryScanController {
                         let _ = object.borrow_mut().wait_until_switched();
 
                         // have to make sure at this point no mut ref exists
-                        yield;
+                        yield ScanEvent::Item { index: scanned_count, row, col };
 
                         scanned_count += 1;
                         object.borrow_mut().scanned_count = scanned_count;
@@ -200,10 +197,13 @@ impl GenshinRepositoryScanController {
                     // todo this is dangerous, use uniform integer type instead
                     if scanned_row >= object.borrow().config.max_row as usize {
                         info!("到达最大行数，准备退出……");
+                        yield ScanEvent::PageEnd;
                         break 'outer;
                     }
                 } // end '_row
 
+                yield ScanEvent::PageEnd;
+
                 let remain = item_count - scanned_count;
                 let remain_row = (remain + object.borrow().col - 1) / object.borrow().col;
                 let scroll_row = remain_row.min(object.borrow().row);
@@ -229,6 +229,17 @@ impl GenshinRepositoryScanController {
         generator
     }
 
+    /// Select the item at `row` and `col` of the current page again, and wait `extra_wait` ms longer than usual
+    pub fn reselect(&mut self, row: usize, col: usize, extra_wait: u32) -> Result<()> {
+        self.move_to(row, col);
+        self.system_control.mouse_click()?;
+
+        // the wait fails if the item is the one selected already
+        let _ = self.wait_until_switched();
+        utils::sleep(extra_wait);
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn get_flag_color(&self) -> Result<image::Rgb<u8>> {
         let mut pos_f64 = Pos {
@@ -248,7 +259,7 @@ impl GenshinRepositoryScanController {
 
     #[inline(always)]
     pub fn sample_initial_color(&mut self) -> Result<()> {
-        self.initial_color = self.get_flag_color()?;
+        self.initial_flag = ColorFlag::from_pixel(self.get_flag_color()?, FLAG_MAX_DISTANCE);
         anyhow::Ok(())
     }
 
@@ -259,7 +270,7 @@ impl GenshinRepositoryScanController {
                 Err(_) => return,
             };
 
-            if color_distance(&self.initial_color, &color) > 10 {
+            if !self.initial_flag.matches_pixel(&color) {
                 self.mouse_scroll(1, false);
                 utils::sleep(self.config.scroll_delay.try_into().unwrap());
             } else {
@@ -310,9 +321,9 @@ impl GenshinRepositoryScanController {
                 Err(_) => return ScrollResult::Failed,
             };
 
-            if state == 0 && color_distance(&self.initial_color, &color) > 10 {
+            if state == 0 && !self.initial_flag.matches_pixel(&color) {
                 state = 1;
-            } else if state == 1 && color_distance(&self.initial_color, &color) <= 10 {
+            } else if state == 1 && self.initial_flag.matches_pixel(&color) {
                 self.update_avg_row(count);
                 return ScrollResult::Success;
             }
@@ -1,11 +1,13 @@
 pub use config::GenshinRepositoryScannerLogicConfig;
 pub use controller::GenshinRepositoryScanController;
 pub use controller::ReturnResult;
+pub use scan_event::ScanEvent;
 pub use scroll_result::ScrollResult;
 pub use window_info::GenshinRepositoryScanControllerWindowInfo;
 
 mod config;
 mod controller;
 
+mod scan_event;
 mod scroll_result;
 mod window_info;
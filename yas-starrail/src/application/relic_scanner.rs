@@ -1,13 +1,17 @@
-use clap::{command, ArgMatches, Args};
+use clap::{command, ArgMatches, Args, FromArgMatches};
+use yas::auto_layout::{LandmarkCrop, LandmarkDefinition};
+use yas::correction::CorrectionConfig;
 use yas::game_info::{GameInfo, GameInfoBuilder};
-use yas::window_info::{load_window_info_repo, WindowInfoRepository};
+use yas::rating::{RatingConfig, RatingReport};
+use yas::validation::ValidationConfig;
+use yas::window_info::{load_window_info_repo, WindowInfoConfig, WindowInfoRepository};
 use crate::export::{ExportRelicConfig, StarRailRelicExporter};
 use crate::scanner::relic_scanner::{StarRailRelicScanner, StarRailRelicScannerConfig};
 use crate::scanner_controller::repository_layout::StarRailRepositoryScannerLogicConfig;
 use anyhow::{anyhow, Result};
 use log::info;
-use yas::export::{AssetEmitter, ExportAssets};
-use crate::relic::StarRailRelic;
+use yas::export::{AssetEmitter, ExportAssets, RejectedItems};
+use crate::relic::{StarRailRelic, StarRailRelicCorrector, StarRailRelicValidator};
 
 pub struct RelicScannerApplication {
     arg_matches: ArgMatches,
@@ -25,6 +29,10 @@ impl RelicScannerApplication {
         cmd = <StarRailRelicScannerConfig as Args>::augment_args_for_update(cmd);
         cmd = <StarRailRepositoryScannerLogicConfig as Args>::augment_args_for_update(cmd);
         cmd = <ExportRelicConfig as Args>::augment_args_for_update(cmd);
+        cmd = <CorrectionConfig as Args>::augment_args_for_update(cmd);
+        cmd = <ValidationConfig as Args>::augment_args_for_update(cmd);
+        cmd = <RatingConfig as Args>::augment_args_for_update(cmd);
+        cmd = <WindowInfoConfig as Args>::augment_args_for_update(cmd);
         cmd
     }
 
@@ -34,12 +42,65 @@ impl RelicScannerApplication {
         )
     }
 
+    /// The built-in window info, overridden by `--window-info` and `--auto-layout` if given
+    pub fn load_window_info_repository(arg_matches: &ArgMatches, game_info: &GameInfo) -> Result<WindowInfoRepository> {
+        let config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        let mut repo = config.load_repository(Self::get_window_info_repository())?;
+        config.apply_auto_layout(&mut repo, game_info)?;
+        Ok(repo)
+    }
+
     // fn init() {
     //     env_logger::Builder::new()
     //         .filter_level(log::LevelFilter::Info)
     //         .init();
     // }
 
+    /// Landmarks for `--auto-layout`, the grid and the panel move independently when the aspect ratio changes
+    pub fn get_landmark_definitions() -> Vec<LandmarkDefinition> {
+        vec![
+            LandmarkDefinition {
+                name: "count_label",
+                crop: LandmarkCrop::Rect("starrail_relic_item_count_rect"),
+                keys: &["starrail_relic_item_count_rect"],
+            },
+            LandmarkDefinition {
+                name: "grid_first_cell",
+                crop: LandmarkCrop::Around("starrail_repository_scan_margin_pos", 40.0),
+                keys: &["starrail_repository_scan_margin_pos", "starrail_repository_flag_rect"],
+            },
+            LandmarkDefinition {
+                name: "panel",
+                crop: LandmarkCrop::Rect("starrail_repository_panel_rect"),
+                keys: &[
+                    "starrail_repository_panel_rect",
+                    "starrail_repository_pool_rect",
+                    "starrail_relic_title_rect",
+                    "starrail_relic_level_rect",
+                    "starrail_relic_star_pos",
+                    "starrail_relic_main_stat_name_rect",
+                    "starrail_relic_main_stat_value_rect",
+                    "starrail_relic_sub_stat0_name_rect",
+                    "starrail_relic_sub_stat0_value_rect",
+                    "starrail_relic_sub_stat1_name_rect",
+                    "starrail_relic_sub_stat1_value_rect",
+                    "starrail_relic_sub_stat2_name_rect",
+                    "starrail_relic_sub_stat2_value_rect",
+                    "starrail_relic_sub_stat3_name_rect",
+                    "starrail_relic_sub_stat3_value_rect",
+                    "starrail_relic_equip_rect",
+                    "starrail_relic_equipper_pos",
+                    "starrail_relic_equipper_rect",
+                ],
+            },
+            LandmarkDefinition {
+                name: "lock_icon",
+                crop: LandmarkCrop::Around("starrail_relic_lock_pos", 16.0),
+                keys: &["starrail_relic_lock_pos", "starrail_relic_discard_pos"],
+            },
+        ]
+    }
+
     pub fn get_game_info() -> Result<GameInfo> {
         let game_info = GameInfoBuilder::new()
             .add_local_window_name("崩坏：星穹铁道")
@@ -54,13 +115,23 @@ impl RelicScannerApplication {
     pub fn run(&self) -> Result<()> {
         // Self::init();
         let arg_matches = &self.arg_matches;
-        let window_info_repository = Self::get_window_info_repository();
         let game_info = Self::get_game_info()?;
+        let window_info_repository = Self::load_window_info_repository(arg_matches, &game_info)?;
 
         info!("window: {:?}", game_info.window);
         info!("ui: {:?}", game_info.ui);
         info!("cloud: {}", game_info.is_cloud);
         info!("resolution family: {:?}", game_info.resolution_family);
+        window_info_repository.log_resolution_summary(
+            game_info.window.to_rect_usize().size(),
+            game_info.ui,
+            game_info.platform,
+        );
+
+        let window_info_config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        if window_info_config.dump_if_required(&window_info_repository, &game_info)? {
+            return Ok(());
+        }
 
         #[cfg(target_os = "windows")]
         {
@@ -76,15 +147,46 @@ impl RelicScannerApplication {
             game_info.clone()
         )?;
 
-        let results = scanner.scan()?;
-        let starrail_relics = results.iter()
-            .map(|x| StarRailRelic::try_from(x))
-            .filter(|x| x.is_ok())
-            .map(|x| x.unwrap())
-            .collect::<Vec<_>>();
-        let exporter = StarRailRelicExporter::new(&arg_matches, &starrail_relics)?;
+        let mut results = scanner.scan()?;
+
+        let correction_config = CorrectionConfig::from_arg_matches(arg_matches)?;
+        let mut corrector = StarRailRelicCorrector::new(correction_config.max_distance);
+        for (index, item) in results.iter_mut().enumerate() {
+            corrector.correct(index, item);
+        }
+        corrector.report().log();
+
+        let validation_config = ValidationConfig::from_arg_matches(arg_matches)?;
+        let mut validator = StarRailRelicValidator::new(!validation_config.no_autocorrect);
+        let mut starrail_relics = Vec::new();
+        let mut scan_indices = Vec::new();
+        let mut rejected = RejectedItems::new();
+        for (index, item) in results.iter().enumerate() {
+            match StarRailRelic::try_from(item) {
+                Ok(mut v) => {
+                    if !validation_config.no_validation {
+                        validator.validate(index, &mut v);
+                    }
+                    starrail_relics.push(v);
+                    scan_indices.push(index);
+                },
+                Err(e) => rejected.push(index, e),
+            }
+        }
+        validator.report().log();
+        let exporter = StarRailRelicExporter::new(&arg_matches, &starrail_relics)?
+            .with_corrections(corrector.report(), &scan_indices);
         let mut export_assets = ExportAssets::new();
         exporter.emit(&mut export_assets);
+        scanner.low_confidence_report().emit_to(&exporter.output_dir, &mut export_assets);
+        corrector.report().emit_to(&exporter.output_dir, &mut export_assets);
+        validator.report().emit_to(&exporter.output_dir, &mut export_assets);
+        if exporter.output_rejected {
+            rejected.emit_to(&exporter.output_dir, &mut export_assets);
+        }
+        let rating_config = RatingConfig::from_arg_matches(arg_matches)?;
+        let rating_report = RatingReport::new(&rating_config.load_profiles::<StarRailRelic>()?, &starrail_relics, rating_config.top);
+        rating_report.emit_to(&exporter.output_dir, &mut export_assets);
 
         let stats = export_assets.save();
         info!("保存结果：");
@@ -94,7 +196,34 @@ impl RelicScannerApplication {
             info!("{}", line);
         }
         info!("Yas 识别结束，共识别到 {} 件圣遗物。", results.len());
+        rejected.log();
+        rating_report.log();
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use yas::game_info::{Platform, UI};
+    use yas::positioning::Size;
+    use yas::window_info::FromWindowInfoRepository;
+
+    use crate::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
+    use crate::scanner_controller::repository_layout::StarRailRepositoryScanControllerWindowInfo;
+
+    use super::*;
+
+    #[test]
+    fn test_anchored_window_sizes() {
+        let repo = RelicScannerApplication::get_window_info_repository();
+        // the same aspect ratio is scaled, the others are derived by the anchors
+        for (width, height) in [(2560, 1440), (2560, 1080), (1920, 1200), (3440, 1440)] {
+            let size = Size::new(width, height);
+            let info = RelicScannerWindowInfo::from_window_info_repository(size, UI::Desktop, Platform::Windows, &repo);
+            assert!(info.is_ok(), "{}x{}", width, height);
+            let info = StarRailRepositoryScanControllerWindowInfo::from_window_info_repository(size, UI::Desktop, Platform::Windows, &repo);
+            assert!(info.is_ok(), "{}x{}", width, height);
+        }
+    }
+}
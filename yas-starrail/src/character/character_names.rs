@@ -0,0 +1,7 @@
+use strum_macros::Display;
+
+yas_derive::yas_game_data! {
+    #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, Display)]
+    #[game_data(keys(march7th))]
+    pub enum StarRailCharacter = "data/characters.json";
+}
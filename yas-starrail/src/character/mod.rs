@@ -0,0 +1,3 @@
+pub use character_names::StarRailCharacter;
+
+mod character_names;
@@ -8,4 +8,7 @@ pub struct ExportRelicConfig {
 
     #[arg(id = "output-dir", long = "output-dir", short, default_value_t = String::from("."), help = "输出目录")]
     pub output_dir: String,
+
+    #[arg(id = "output-rejected", long = "output-rejected", help = "将无法转换的物品及原因输出至 rejected.json")]
+    pub output_rejected: bool,
 }
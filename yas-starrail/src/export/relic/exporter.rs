@@ -6,6 +6,7 @@ use crate::relic::StarRailRelic;
 
 use crate::export::relic::{ExportRelicConfig, StarRailRelicExportFormat};
 use anyhow::Result;
+use yas::correction::{Correction, CorrectionReport};
 use yas::export::{AssetEmitter, ExportAssets};
 use crate::export::relic::hsr::StarRailHSRFormat;
 
@@ -15,6 +16,10 @@ pub struct StarRailRelicExporter<'a> {
     pub format: StarRailRelicExportFormat,
     pub results: Option<&'a [StarRailRelic]>,
     pub output_dir: PathBuf,
+    /// whether the items which cannot be converted are written to `rejected.json`
+    pub output_rejected: bool,
+    /// the corrections of the recognized names of every relic, attached to the relics in the exports
+    pub corrections: Vec<Vec<Correction>>,
 }
 
 impl<'a> StarRailRelicExporter<'a> {
@@ -23,9 +28,17 @@ impl<'a> StarRailRelicExporter<'a> {
         Ok(Self {
             format: config.format,
             results: Some(results),
-            output_dir: PathBuf::from(&config.output_dir)
+            output_dir: PathBuf::from(&config.output_dir),
+            output_rejected: config.output_rejected,
+            corrections: Vec::new(),
         })
     }
+
+    /// Attach the corrections to the relics, `scan_indices` holds the index in the scan results of every relic
+    pub fn with_corrections(mut self, report: &CorrectionReport, scan_indices: &[usize]) -> Self {
+        self.corrections = report.by_exported_item(scan_indices);
+        self
+    }
 }
 
 impl<'a> AssetEmitter for StarRailRelicExporter<'a> {
@@ -39,7 +52,7 @@ impl<'a> AssetEmitter for StarRailRelicExporter<'a> {
         match self.format {
             StarRailRelicExportFormat::March7th => {
                 let path = self.output_dir.join("march7th.json");
-                let format = March7thFormat::new(results);
+                let format = March7thFormat::new(results, &self.corrections);
                 let contents = serde_json::to_string(&format).unwrap();
 
                 asset_bundle.add_asset(
@@ -51,7 +64,7 @@ impl<'a> AssetEmitter for StarRailRelicExporter<'a> {
             },
             StarRailRelicExportFormat::HSR => {
                 let path = self.output_dir.join("hsr.json");
-                let format = StarRailHSRFormat::new_version3(results);
+                let format = StarRailHSRFormat::new_version3(results, &self.corrections);
                 let contents = serde_json::to_string(&format).unwrap();
 
                 asset_bundle.add_asset(
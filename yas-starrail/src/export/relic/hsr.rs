@@ -3,9 +3,11 @@ use std::ops::Deref;
 use nanoid::nanoid;
 use serde::{Serialize, Serializer};
 use serde::ser::SerializeMap;
-use crate::relic::{RelicSetName, RelicSlot, RelicStat, RelicStatName, StarRailRelic};
+use yas::correction::{corrections_of, Correction};
+use crate::relic::{RelicSetName, RelicSlot, RelicStat, RelicStatName, RelicStepAnalysis, StarRailRelic, SubStatSteps};
 
-struct HSRRelic<'a>(&'a StarRailRelic);
+/// a relic along with the corrections of its recognized names, written as `yasCorrections` if any
+struct HSRRelic<'a>(&'a StarRailRelic, &'a [Correction]);
 
 impl<'a> Deref for HSRRelic<'a> {
     type Target = StarRailRelic;
@@ -22,10 +24,10 @@ pub struct StarRailHSRFormat<'a> {
 }
 
 impl<'a> StarRailHSRFormat<'a> {
-    pub fn new_version3(results: &'a [StarRailRelic]) -> Self {
+    pub fn new_version3(results: &'a [StarRailRelic], corrections: &'a [Vec<Correction>]) -> Self {
         let mut r = Vec::new();
-        for item in results.iter() {
-            r.push(HSRRelic(item));
+        for (index, item) in results.iter().enumerate() {
+            r.push(HSRRelic(item, corrections_of(corrections, index)));
         }
         Self {
             results: r,
@@ -36,74 +38,13 @@ impl<'a> StarRailHSRFormat<'a> {
 
 impl RelicStatName {
     pub fn to_hsr_stat_name(&self) -> &'static str {
-        match *self {
-            RelicStatName::SPD => "SPD",
-            RelicStatName::HP => "HP",
-            RelicStatName::ATK => "ATK",
-            RelicStatName::DEF => "DEF",
-            RelicStatName::BreakEffect => "Break Effect",
-            RelicStatName::EffectHitRate => "Effect Hit Rate",
-            RelicStatName::EnergyRegenerationRate => "Energy Regeneration Rate",
-            RelicStatName::OutgoingHealingBoost => "Outgoing Healing Boost",
-            RelicStatName::PhysicalDMGBoost => "Physical DMG Boost",
-            RelicStatName::FireDMGBoost => "Fire DMG Boost",
-            RelicStatName::IceDMGBoost => "Ice DMG Boost",
-            RelicStatName::WindDMGBoost => "Wind DMG Boost",
-            RelicStatName::LightningDMGBoost => "Lightning DMG Boost",
-            RelicStatName::QuantumDMGBoost => "Quantum DMG Boost",
-            RelicStatName::ImaginaryDMGBoost => "Imaginary DMG Boost",
-            RelicStatName::CRITRate => "CRIT Rate",
-            RelicStatName::CRITDMG => "CRIT DMG",
-            RelicStatName::EffectRES => "Effect RES",
-            RelicStatName::HPPercentage => "HP",
-            RelicStatName::ATKPercentage => "ATK",
-            RelicStatName::DEFPercentage => "DEF",
-        }
+        self.hsr_key()
     }
 }
 
 impl RelicSetName {
     pub fn to_hsr_set_name(&self) -> &'static str {
-        match *self {
-            RelicSetName::PasserbyofWanderingCloud => "Passerby of Wandering Cloud",
-            RelicSetName::MusketeerofWildWheat => "Musketeer of Wild Wheat",
-            RelicSetName::KnightofPurityPalace => "Knight of Purity Palace",
-            RelicSetName::HunterofGlacialForest => "Hunter of Glacial Forest",
-            RelicSetName::ChampionofStreetwiseBoxing => "Champion of Streetwise Boxing",
-            RelicSetName::GuardofWutheringSnow => "Guard of Wuthering Snow",
-            RelicSetName::FiresmithofLavaForging => "Firesmith of Lava-Forging",
-            RelicSetName::GeniusofBrilliantStars => "Genius of Brilliant Stars",
-            RelicSetName::BandofSizzlingThunder => "Band of Sizzling Thunder",
-            RelicSetName::EagleofTwilightLine => "Eagle of Twilight Line",
-            RelicSetName::ThiefofShootingMeteor => "Thief of Shooting Meteor",
-            RelicSetName::WastelanderofBanditryDesert => "Wastelander of Banditry Desert",
-            RelicSetName::LongevousDisciple => "Longevous Disciple",
-            RelicSetName::MessengerTraversingHackerspace => "Messenger Traversing Hackerspace",
-            RelicSetName::TheAshblazingGrandDuke => "The Ashblazing Grand Duke",
-            RelicSetName::PrisonerinDeepConfinement => "Prisoner in Deep Confinement",
-            RelicSetName::PioneerDiverofDeadWaters => "Pioneer Diver of Dead Waters",
-            RelicSetName::WatchmakerMasterofDreamMachinations => "Watchmaker, Master of Dream Machinations",
-            RelicSetName::IronCavalryAgainsttheScourge => "Iron Cavalry Against the Scourge",
-            RelicSetName::TheWindSoaringValorous => "The Wind-Soaring Valorous",
-            RelicSetName::SpaceSealingStation => "Space Sealing Station",
-            RelicSetName::FleetoftheAgeless => "Fleet of the Ageless",
-            RelicSetName::PanCosmicCommercialEnterprise => "Pan-Cosmic Commercial Enterprise",
-            RelicSetName::BelobogoftheArchitects => "Belobog's Fortress of Preservation",
-            RelicSetName::CelestialDifferentiator => "Celestial Differentiator",
-            RelicSetName::InertSalsotto => "Inert Salsotto",
-            RelicSetName::TaliaKingdomofBanditry => "Talia: Kingdom of Banditry",
-            RelicSetName::SprightlyVonwacq => "Sprightly Vonwacq",
-            RelicSetName::RutilantArena => "Rutilant Arena",
-            RelicSetName::BrokenKeel => "Broken Keel",
-            RelicSetName::FirmamentFrontlineGlamoth => "Firmament Frontline: Glamoth",
-            RelicSetName::PenaconyLandoftheDreams => "Penacony, Land of the Dreams",
-            RelicSetName::SigoniatheUnclaimedDesolation => "Sigonia, the Unclaimed Desolation",
-            RelicSetName::IzumoGenseiandTakamaDivineRealm => "Izumo Gensei and Takama Divine Realm",
-            RelicSetName::DuranDynastyofRunningWolves => "Duran, Dynasty of Running Wolves",
-            RelicSetName::ForgeoftheKalpagniLantern => "Forge of the Kalpagni Lantern",
-            RelicSetName::LushakatheSunkenSeas => "Lushaka, the Sunken Seas",
-            RelicSetName::TheWondrousBananAmusementPark => "The Wondrous BananAmusement Park",
-        }
+        self.hsr_key()
     }
 }
 
@@ -120,11 +61,12 @@ impl RelicSlot {
     }
 }
 
-struct HSRStat(RelicStat);
+/// A sub stat along with its rolls and steps, if they are known
+struct HSRStat(RelicStat, Option<SubStatSteps>);
 
 impl Serialize for HSRStat {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        let mut root = serializer.serialize_map(Some(2))?;
+        let mut root = serializer.serialize_map(None)?;
 
         let mut name = String::from(self.0.name.to_hsr_stat_name());
         let is_percentage = self.0.name.is_percentage();
@@ -138,6 +80,10 @@ impl Serialize for HSRStat {
             self.0.value
         };
         root.serialize_entry("value", &value)?;
+        if let Some(steps) = &self.1 {
+            root.serialize_entry("count", &steps.rolls)?;
+            root.serialize_entry("step", &steps.steps)?;
+        }
 
         root.end()
     }
@@ -145,7 +91,7 @@ impl Serialize for HSRStat {
 
 impl<'a> Serialize for HSRRelic<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        let mut root = serializer.serialize_map(Some(10))?;
+        let mut root = serializer.serialize_map(None)?;
 
         root.serialize_entry("set", self.set_name.to_hsr_set_name())?;
         root.serialize_entry("slot", self.slot.to_hsr_slot_name())?;
@@ -153,24 +99,21 @@ impl<'a> Serialize for HSRRelic<'a> {
         root.serialize_entry("level", &self.level)?;
         root.serialize_entry("mainstat", self.main_stat.name.to_hsr_stat_name())?;
 
+        let analysis = RelicStepAnalysis::analyze(self);
         let mut sub_stats: Vec<HSRStat> = Vec::new();
-        if let Some(s) = &self.sub_stat_1 {
-            sub_stats.push(HSRStat(s.clone()));
-        }
-        if let Some(s) = &self.sub_stat_2 {
-            sub_stats.push(HSRStat(s.clone()));
-        }
-        if let Some(s) = &self.sub_stat_3 {
-            sub_stats.push(HSRStat(s.clone()));
-        }
-        if let Some(s) = &self.sub_stat_4 {
-            sub_stats.push(HSRStat(s.clone()));
+        for (i, sub_stat) in [&self.sub_stat_1, &self.sub_stat_2, &self.sub_stat_3, &self.sub_stat_4].into_iter().enumerate() {
+            if let Some(s) = sub_stat {
+                sub_stats.push(HSRStat(s.clone(), analysis.sub_stats[i].clone()));
+            }
         }
         root.serialize_entry("substats", &sub_stats)?;
-        root.serialize_entry("location", "")?;
+        root.serialize_entry("location", self.equip.as_deref().unwrap_or(""))?;
         root.serialize_entry("lock", &self.lock)?;
         root.serialize_entry("discard", &self.discard)?;
         root.serialize_entry("_id", &nanoid!())?;
+        if !self.1.is_empty() {
+            root.serialize_entry("yasCorrections", self.1)?;
+        }
 
         root.end()
     }
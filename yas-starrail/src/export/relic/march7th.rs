@@ -1,85 +1,27 @@
 use serde::ser::{Serialize, SerializeMap, Serializer};
 use std::convert::From;
 
+use yas::correction::{corrections_of, Correction};
+
 use crate::relic::{
-    RelicSetName, RelicSlot, RelicStat, RelicStatName, StarRailRelic,
+    RelicSetName, RelicSlot, RelicStat, RelicStatName, RelicStepAnalysis, StarRailRelic, SubStatSteps,
 };
 
-type March7thRelic = StarRailRelic;
+struct March7thRelic<'a> {
+    relic: &'a StarRailRelic,
+    /// written as `yasCorrections` if any
+    corrections: &'a [Correction],
+}
 
 impl RelicStatName {
     pub fn to_march7th(&self) -> String {
-        let temp = match self {
-            RelicStatName::HP => "hp",
-            RelicStatName::HPPercentage => "hp_",
-            RelicStatName::ATK => "atk",
-            RelicStatName::ATKPercentage => "atk_",
-            RelicStatName::DEFPercentage => "def_",
-            RelicStatName::SPD => "spd",
-            RelicStatName::CRITRate => "critRate",
-            RelicStatName::CRITDMG => "critDMG",
-            RelicStatName::BreakEffect => "break",
-            RelicStatName::OutgoingHealingBoost => "heal",
-            RelicStatName::EnergyRegenerationRate => "enerRegen",
-            RelicStatName::EffectHitRate => "eff",
-            RelicStatName::PhysicalDMGBoost => "physicalDmg",
-            RelicStatName::FireDMGBoost => "fireDmg",
-            RelicStatName::IceDMGBoost => "iceDmg",
-            RelicStatName::LightningDMGBoost => "lightningDmg",
-            RelicStatName::WindDMGBoost => "windDmg",
-            RelicStatName::QuantumDMGBoost => "quantumDmg",
-            RelicStatName::ImaginaryDMGBoost => "imaginaryDmg",
-            RelicStatName::DEF => "def",
-            RelicStatName::EffectRES => "effRes",
-        };
-        String::from(temp)
+        String::from(self.march7th_key())
     }
 }
 
 impl RelicSetName {
     pub fn to_march7th(&self) -> String {
-        let temp = match self {
-            RelicSetName::PasserbyofWanderingCloud => "PasserbyofWanderingCloud",
-            RelicSetName::MusketeerofWildWheat => "MusketeerofWildWheat",
-            RelicSetName::KnightofPurityPalace => "KnightofPurityPalace",
-            RelicSetName::HunterofGlacialForest => "HunterofGlacialForest",
-            RelicSetName::ChampionofStreetwiseBoxing => "ChampionofStreetwiseBoxing",
-            RelicSetName::GuardofWutheringSnow => "GuardofWutheringSnow",
-            RelicSetName::FiresmithofLavaForging => "FiresmithofLavaForging",
-            RelicSetName::GeniusofBrilliantStars => "GeniusofBrilliantStars",
-            RelicSetName::BandofSizzlingThunder => "BandofSizzlingThunder",
-            RelicSetName::EagleofTwilightLine => "EagleofTwilightLine",
-            RelicSetName::ThiefofShootingMeteor => "ThiefofShootingMeteor",
-            RelicSetName::WastelanderofBanditryDesert => "WastelanderofBanditryDesert",
-            RelicSetName::LongevousDisciple => "LongevousDisciple",
-            RelicSetName::MessengerTraversingHackerspace => "MessengerTraversingHackerspace",
-            RelicSetName::TheAshblazingGrandDuke => "TheAshblazingGrandDuke",
-            RelicSetName::PrisonerinDeepConfinement => "PrisonerinDeepConfinement",
-            RelicSetName::PioneerDiverofDeadWaters => "PioneerDiverofDeadWaters",
-            RelicSetName::WatchmakerMasterofDreamMachinations => "WatchmakerMasterofDreamMachinations",
-            RelicSetName::IronCavalryAgainsttheScourge => "IronCavalryAgainsttheScourge",
-            RelicSetName::TheWindSoaringValorous => "TheWindSoaringValorous",
-
-            RelicSetName::SpaceSealingStation => "SpaceSealingStation",
-            RelicSetName::FleetoftheAgeless => "FleetoftheAgeless",
-            RelicSetName::PanCosmicCommercialEnterprise => "PanCosmicCommercialEnterprise",
-            RelicSetName::BelobogoftheArchitects => "BelobogoftheArchitects",
-            RelicSetName::CelestialDifferentiator => "CelestialDifferentiator",
-            RelicSetName::InertSalsotto => "InertSalsotto",
-            RelicSetName::TaliaKingdomofBanditry => "TaliaKingdomofBanditry",
-            RelicSetName::SprightlyVonwacq => "SprightlyVonwacq",
-            RelicSetName::RutilantArena => "RutilantArena",
-            RelicSetName::BrokenKeel => "BrokenKeel",
-            RelicSetName::FirmamentFrontlineGlamoth => "FirmamentFrontlineGlamoth",
-            RelicSetName::PenaconyLandoftheDreams => "PenaconyLandoftheDreams",
-            RelicSetName::SigoniatheUnclaimedDesolation => "SigoniatheUnclaimedDesolation",
-            RelicSetName::IzumoGenseiandTakamaDivineRealm => "IzumoGenseiandTakamaDivineRealm",
-            RelicSetName::DuranDynastyofRunningWolves => "DuranDynastyofRunningWolves",
-            RelicSetName::ForgeoftheKalpagniLantern => "ForgeoftheKalpagniLantern",
-            RelicSetName::LushakatheSunkenSeas => "LushakatheSunkenSeas",
-            RelicSetName::TheWondrousBananAmusementPark => "TheWondrousBananAmusementPark",
-        };
-        String::from(temp)
+        String::from(self.march7th_key())
     }
 }
 
@@ -109,37 +51,54 @@ impl Serialize for RelicStat {
     }
 }
 
-impl Serialize for March7thRelic {
+/// A sub stat along with its rolls and steps, if they are known
+struct March7thSubStat<'a>(&'a RelicStat, Option<&'a SubStatSteps>);
+
+impl<'a> Serialize for March7thSubStat<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut root = serializer.serialize_map(Some(7))?;
-
-        root.serialize_entry("setName", &self.set_name.to_march7th())?;
-        root.serialize_entry("position", &self.slot.to_march7th())?;
-        root.serialize_entry("mainTag", &self.main_stat)?;
-
-        let mut sub_stats: Vec<&RelicStat> = vec![];
-        if let Some(ref s) = self.sub_stat_1 {
-            sub_stats.push(s);
-        }
-        if let Some(ref s) = self.sub_stat_2 {
-            sub_stats.push(s);
+        let mut root = serializer.serialize_map(None)?;
+        root.serialize_entry("name", &self.0.name.to_march7th())?;
+        root.serialize_entry("value", &self.0.value)?;
+        if let Some(steps) = self.1 {
+            root.serialize_entry("count", &steps.rolls)?;
+            root.serialize_entry("step", &steps.steps)?;
         }
-        if let Some(ref s) = self.sub_stat_3 {
-            sub_stats.push(s);
-        }
-        if let Some(ref s) = self.sub_stat_4 {
-            sub_stats.push(s);
+        root.end()
+    }
+}
+
+impl<'a> Serialize for March7thRelic<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let relic = self.relic;
+        let mut root = serializer.serialize_map(None)?;
+
+        root.serialize_entry("setName", &relic.set_name.to_march7th())?;
+        root.serialize_entry("position", &relic.slot.to_march7th())?;
+        root.serialize_entry("mainTag", &relic.main_stat)?;
+
+        let analysis = RelicStepAnalysis::analyze(relic);
+        let mut sub_stats: Vec<March7thSubStat> = vec![];
+        for (i, sub_stat) in [&relic.sub_stat_1, &relic.sub_stat_2, &relic.sub_stat_3, &relic.sub_stat_4].into_iter().enumerate() {
+            if let Some(s) = sub_stat {
+                sub_stats.push(March7thSubStat(s, analysis.sub_stats[i].as_ref()));
+            }
         }
 
         root.serialize_entry("normalTags", &sub_stats)?;
-        root.serialize_entry("level", &self.level)?;
-        root.serialize_entry("star", &self.star)?;
-        root.serialize_entry("equip", &self.equip)?;
-        root.serialize_entry("lock", &self.lock)?;
-        root.serialize_entry("discard", &self.discard)?;
+        root.serialize_entry("level", &relic.level)?;
+        root.serialize_entry("star", &relic.star)?;
+        root.serialize_entry("equip", &relic.equip)?;
+        root.serialize_entry("lock", &relic.lock)?;
+        root.serialize_entry("discard", &relic.discard)?;
+        if !self.corrections.is_empty() {
+            root.serialize_entry("yasCorrections", self.corrections)?;
+        }
 
         root.end()
     }
@@ -147,12 +106,12 @@ impl Serialize for March7thRelic {
 
 pub struct March7thFormat<'a> {
     version: String,
-    head: Vec<&'a March7thRelic>,
-    hands: Vec<&'a March7thRelic>,
-    body: Vec<&'a March7thRelic>,
-    feet: Vec<&'a March7thRelic>,
-    sphere: Vec<&'a March7thRelic>,
-    rope: Vec<&'a March7thRelic>,
+    head: Vec<March7thRelic<'a>>,
+    hands: Vec<March7thRelic<'a>>,
+    body: Vec<March7thRelic<'a>>,
+    feet: Vec<March7thRelic<'a>>,
+    sphere: Vec<March7thRelic<'a>>,
+    rope: Vec<March7thRelic<'a>>,
 }
 
 impl<'a> Serialize for March7thFormat<'a> {
@@ -173,22 +132,23 @@ impl<'a> Serialize for March7thFormat<'a> {
 }
 
 impl<'a> March7thFormat<'a> {
-    pub fn new(results: &'a [StarRailRelic]) -> March7thFormat {
-        let mut head: Vec<&March7thRelic> = Vec::new();
-        let mut hands: Vec<&March7thRelic> = Vec::new();
-        let mut body: Vec<&March7thRelic> = Vec::new();
-        let mut feet: Vec<&March7thRelic> = Vec::new();
-        let mut sphere: Vec<&March7thRelic> = Vec::new();
-        let mut rope: Vec<&March7thRelic> = Vec::new();
-
-        for relic in results.iter() {
+    pub fn new(results: &'a [StarRailRelic], corrections: &'a [Vec<Correction>]) -> March7thFormat<'a> {
+        let mut head: Vec<March7thRelic> = Vec::new();
+        let mut hands: Vec<March7thRelic> = Vec::new();
+        let mut body: Vec<March7thRelic> = Vec::new();
+        let mut feet: Vec<March7thRelic> = Vec::new();
+        let mut sphere: Vec<March7thRelic> = Vec::new();
+        let mut rope: Vec<March7thRelic> = Vec::new();
+
+        for (index, relic) in results.iter().enumerate() {
+            let item = March7thRelic { relic, corrections: corrections_of(corrections, index) };
             match relic.slot {
-                RelicSlot::Head => head.push(relic),
-                RelicSlot::Hands => hands.push(relic),
-                RelicSlot::Body => body.push(relic),
-                RelicSlot::Feet => feet.push(relic),
-                RelicSlot::PlanarSphere => sphere.push(relic),
-                RelicSlot::LinkRope => rope.push(relic),
+                RelicSlot::Head => head.push(item),
+                RelicSlot::Hands => hands.push(item),
+                RelicSlot::Body => body.push(item),
+                RelicSlot::Feet => feet.push(item),
+                RelicSlot::PlanarSphere => sphere.push(item),
+                RelicSlot::LinkRope => rope.push(item),
             }
         }
 
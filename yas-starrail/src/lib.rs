@@ -6,4 +6,5 @@ pub mod scanner_controller;
 pub mod export;
 pub mod scanner;
 pub mod relic;
+pub mod character;
 pub mod application;
@@ -0,0 +1,34 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Why a scanned relic cannot be converted, with the recognized text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RelicConversionError {
+    UnknownSetTitle {
+        title: String,
+    },
+    BadMainStat {
+        name: String,
+        value: String,
+    },
+    MalformedSubStat {
+        /// starting from 1
+        index: usize,
+        name: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for RelicConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelicConversionError::UnknownSetTitle { title } => write!(f, "未知的遗器名称 \"{}\"", title),
+            RelicConversionError::BadMainStat { name, value } => write!(f, "无法解析主词条 \"{}\" \"{}\"", name, value),
+            RelicConversionError::MalformedSubStat { index, name, value } => write!(f, "无法解析第 {} 个副词条 \"{}\" \"{}\"", index, name, value),
+        }
+    }
+}
+
+impl std::error::Error for RelicConversionError {}
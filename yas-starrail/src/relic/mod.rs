@@ -3,5 +3,17 @@ pub use relic::RelicSetName;
 pub use relic::RelicStat;
 pub use relic::RelicStatName;
 pub use relic::StarRailRelic;
+pub use relic_corrector::StarRailRelicCorrector;
+pub use relic_validator::StarRailRelicValidator;
+pub use step_analysis::{RelicStepAnalysis, SubStatSteps};
+pub use conversion_error::RelicConversionError;
+pub use zh_cn::{RELIC_NAMES_ZH_CN, STAT_NAMES_ZH_CN, SUB_STAT_NAMES_ZH_CN};
 
 mod relic;
+mod relic_corrector;
+mod relic_validator;
+mod relic_rating;
+mod conversion_error;
+mod stat_table;
+mod step_analysis;
+mod zh_cn;
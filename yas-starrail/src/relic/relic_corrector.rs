@@ -0,0 +1,39 @@
+use yas::correction::{CorrectionReport, Dictionary, TextCorrector};
+
+use crate::relic::{RELIC_NAMES_ZH_CN, STAT_NAMES_ZH_CN, SUB_STAT_NAMES_ZH_CN};
+use crate::scanner::relic_scanner::StarRailRelicScanResult;
+
+/// Snaps the names in relic scan results to the known ones, before they are converted to relics
+pub struct StarRailRelicCorrector {
+    corrector: TextCorrector,
+    titles: Dictionary,
+    stat_names: Dictionary,
+    sub_stat_names: Dictionary,
+}
+
+impl StarRailRelicCorrector {
+    pub fn new(max_distance: usize) -> StarRailRelicCorrector {
+        StarRailRelicCorrector {
+            corrector: TextCorrector::new(max_distance),
+            titles: Dictionary::new(RELIC_NAMES_ZH_CN),
+            stat_names: Dictionary::new(STAT_NAMES_ZH_CN),
+            sub_stat_names: Dictionary::new(SUB_STAT_NAMES_ZH_CN),
+        }
+    }
+
+    /// `index` is the position of the result in the scan results
+    pub fn correct(&mut self, index: usize, result: &mut StarRailRelicScanResult) {
+        let corrector = &mut self.corrector;
+
+        result.name = corrector.correct(index, "title", &result.name, &self.titles);
+        result.main_stat_name = corrector.correct(index, "main_stat_name", &result.main_stat_name, &self.stat_names);
+        for (i, name) in result.sub_stat_name.iter_mut().enumerate() {
+            let field = format!("sub_stat_name_{}", i + 1);
+            *name = corrector.correct(index, &field, name, &self.sub_stat_names);
+        }
+    }
+
+    pub fn report(&self) -> &CorrectionReport {
+        &self.corrector.report
+    }
+}
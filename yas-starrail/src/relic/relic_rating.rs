@@ -0,0 +1,47 @@
+use yas::rating::Rateable;
+
+use crate::relic::{RelicStat, RelicStatName, StarRailRelic};
+
+impl StarRailRelic {
+    fn sub_stats(&self) -> impl Iterator<Item = &RelicStat> {
+        [&self.sub_stat_1, &self.sub_stat_2, &self.sub_stat_3, &self.sub_stat_4]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// The keys in weight profiles are those of the March7th format, e.g. `critRate`, `spd`
+/// the rolls are counted in the highest rolls of 5 star relics, so that relics of any rarity compare
+impl Rateable for StarRailRelic {
+    fn rated_stats(&self) -> Vec<(String, f64)> {
+        self.sub_stats()
+            .filter_map(|stat| {
+                let (base, step) = stat.name.sub_stat_base_step(5)?;
+                Some((stat.name.to_march7th(), stat.value / (base + step * 2.0)))
+            })
+            .collect()
+    }
+
+    fn stat_keys() -> Vec<String> {
+        RelicStatName::ALL.iter().map(|name| name.to_march7th()).collect()
+    }
+
+    fn crit_value(&self) -> f64 {
+        let value = self.sub_stats()
+            .map(|stat| match stat.name {
+                RelicStatName::CRITRate => stat.value * 2.0,
+                RelicStatName::CRITDMG => stat.value,
+                _ => 0.0,
+            })
+            .sum::<f64>();
+        value * 100.0
+    }
+
+    fn describe(&self) -> String {
+        let mut s = format!("{} {:?} {}星 +{}", self.set_name, self.slot, self.star, self.level);
+        if let Some(equip) = &self.equip {
+            s = s + " " + equip;
+        }
+        s
+    }
+}
@@ -0,0 +1,69 @@
+use yas::validation::{StatValidator, StatValues, ValidationReport};
+
+use crate::relic::{RelicStepAnalysis, StarRailRelic};
+
+/// Checks the stat values of relics against the main stat growth and the sub stat steps,
+/// and whether the rolls of the sub stats add up to the level
+pub struct StarRailRelicValidator {
+    validator: StatValidator,
+}
+
+impl StarRailRelicValidator {
+    pub fn new(autocorrect: bool) -> StarRailRelicValidator {
+        StarRailRelicValidator {
+            validator: StatValidator::new(autocorrect),
+        }
+    }
+
+    /// `index` is the position of the relic in the scan results
+    pub fn validate(&mut self, index: usize, relic: &mut StarRailRelic) {
+        let star = relic.star;
+        let level = relic.level;
+
+        let main_stat = &mut relic.main_stat;
+        if let Some(expected) = main_stat.name.main_stat_value(star, level) {
+            // the displayed values are truncated
+            let tolerance = (expected * 0.01).max(main_stat.name.display_unit());
+            main_stat.value = self.validator.validate(
+                index,
+                "main_stat",
+                &format!("{:?}", main_stat.name),
+                main_stat.value,
+                &StatValues::Discrete(vec![expected]),
+                tolerance,
+            );
+        }
+
+        let sub_stats = [
+            &mut relic.sub_stat_1,
+            &mut relic.sub_stat_2,
+            &mut relic.sub_stat_3,
+            &mut relic.sub_stat_4,
+        ];
+        for (i, sub_stat) in sub_stats.into_iter().enumerate() {
+            let stat = match sub_stat {
+                Some(v) => v,
+                None => continue,
+            };
+            if let Some(values) = stat.name.sub_stat_values(star, level) {
+                stat.value = self.validator.validate(
+                    index,
+                    &format!("sub_stat_{}", i + 1),
+                    &format!("{:?}", stat.name),
+                    stat.value,
+                    &values,
+                    stat.name.display_unit(),
+                );
+            }
+        }
+
+        // the sub stats may each be attainable, but not at this level altogether
+        if !RelicStepAnalysis::analyze(relic).consistent {
+            self.validator.flag(index, "level", "SubStatRolls", level as f64);
+        }
+    }
+
+    pub fn report(&self) -> &ValidationReport {
+        &self.validator.report
+    }
+}
@@ -0,0 +1,100 @@
+use yas::validation::StatValues;
+
+use crate::relic::RelicStatName;
+
+impl RelicStatName {
+    /// HP, ATK, DEF and SPD are displayed as they are, the others in %
+    fn is_flat(&self) -> bool {
+        matches!(self, RelicStatName::HP | RelicStatName::ATK | RelicStatName::DEF | RelicStatName::SPD)
+    }
+
+    /// The difference between two adjacent displayed values, in the unit of `RelicStat::value`
+    pub fn display_unit(&self) -> f64 {
+        if self.is_flat() { 1.0 } else { 0.001 }
+    }
+
+    fn value_of_displayed(&self, displayed: f64) -> f64 {
+        if self.is_flat() { displayed } else { displayed / 100.0 }
+    }
+
+    /// The main stat is `base + step * level`, percentages are in %
+    #[rustfmt::skip]
+    fn main_stat_growth(&self, star: i32) -> Option<(f64, f64)> {
+        use RelicStatName::*;
+        let growth = match (star, self) {
+            (5, HP) => (112.896, 39.5136),
+            (5, ATK) => (56.448, 19.7568),
+            (5, HPPercentage | ATKPercentage | EffectHitRate) => (6.912, 2.4192),
+            (5, DEFPercentage) => (8.64, 3.024),
+            (5, CRITRate) => (5.184, 1.8144),
+            (5, CRITDMG | BreakEffect) => (10.368, 3.6288),
+            (5, OutgoingHealingBoost) => (5.5296, 1.9354),
+            (5, SPD) => (4.032, 1.4),
+            (5, PhysicalDMGBoost | FireDMGBoost | IceDMGBoost | LightningDMGBoost) => (6.2208, 2.1773),
+            (5, WindDMGBoost | QuantumDMGBoost | ImaginaryDMGBoost) => (6.2208, 2.1773),
+            (5, EnergyRegenerationRate) => (3.1104, 1.0886),
+            (4, HP) => (90.3168, 31.6108),
+            (4, ATK) => (45.1584, 15.8054),
+            (4, HPPercentage | ATKPercentage | EffectHitRate) => (5.5296, 1.9354),
+            (4, DEFPercentage) => (6.912, 2.4192),
+            (4, CRITRate) => (4.1472, 1.4515),
+            (4, CRITDMG | BreakEffect) => (8.2944, 2.903),
+            (4, OutgoingHealingBoost) => (4.4237, 1.5483),
+            (4, SPD) => (3.2256, 1.1),
+            (4, PhysicalDMGBoost | FireDMGBoost | IceDMGBoost | LightningDMGBoost) => (4.9766, 1.7418),
+            (4, WindDMGBoost | QuantumDMGBoost | ImaginaryDMGBoost) => (4.9766, 1.7418),
+            (4, EnergyRegenerationRate) => (2.4883, 0.8709),
+            _ => return None,
+        };
+        Some(growth)
+    }
+
+    /// The main stat value of a relic of `star` at `level`, None if it is not known
+    pub fn main_stat_value(&self, star: i32, level: i32) -> Option<f64> {
+        if level < 0 || level > star * 3 {
+            return None;
+        }
+
+        let (base, step) = self.main_stat_growth(star)?;
+        Some(self.value_of_displayed(base + step * level as f64))
+    }
+
+    /// A roll of this sub stat adds `base + step * k` where k is 0, 1 or 2, in the unit of `RelicStat::value`
+    #[rustfmt::skip]
+    pub fn sub_stat_base_step(&self, star: i32) -> Option<(f64, f64)> {
+        use RelicStatName::*;
+        let (base, step) = match self {
+            HP => (33.870065, 4.233757),
+            ATK | DEF => (16.935034, 2.116877),
+            HPPercentage | ATKPercentage | EffectHitRate | EffectRES => (3.456, 0.432),
+            DEFPercentage => (4.32, 0.54),
+            SPD => (2.0, 0.3),
+            CRITRate => (2.592, 0.324),
+            CRITDMG | BreakEffect => (5.184, 0.648),
+            _ => return None,
+        };
+        // the rolls of 4 star relics are 80% of those of 5 star ones
+        let scale = match star {
+            5 => 1.0,
+            4 => 0.8,
+            _ => return None,
+        };
+
+        Some((self.value_of_displayed(base * scale), self.value_of_displayed(step * scale)))
+    }
+
+    /// The values this sub stat can have on a relic of `star` at `level`
+    /// every 3 levels one of the sub stats is rolled once more
+    pub fn sub_stat_values(&self, star: i32, level: i32) -> Option<StatValues> {
+        let (base, step) = self.sub_stat_base_step(star)?;
+        let max_rolls = 1 + level.clamp(0, star * 3) / 3;
+
+        let mut values = Vec::new();
+        for rolls in 1..=max_rolls {
+            for steps in 0..=rolls * 2 {
+                values.push(base * rolls as f64 + step * steps as f64);
+            }
+        }
+        Some(StatValues::Discrete(values))
+    }
+}
@@ -0,0 +1,149 @@
+use yas::validation::choose_roll_counts;
+
+use crate::relic::{RelicStat, StarRailRelic};
+
+/// How a sub stat value is made up of rolls, each roll adds `base + step * k` where k is 0, 1 or 2
+/// the low, mid and high rolls cannot be told apart by the value, only their total steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubStatSteps {
+    /// the number of rolls, including the one when the sub stat is added
+    pub rolls: usize,
+    /// the sum of k of every roll, from 0 to `2 * rolls`
+    pub steps: usize,
+}
+
+/// The steps of the sub stats of a relic, and how many sub stats it started with
+#[derive(Debug, Clone, Default)]
+pub struct RelicStepAnalysis {
+    /// in the order of the sub stats, None if the sub stat is absent or its value cannot be decomposed
+    pub sub_stats: [Option<SubStatSteps>; 4],
+    /// the number of sub stats at level 0, None if it cannot be told
+    pub initial_sub_stats: Option<usize>,
+    /// false if every sub stat can be decomposed, but their rolls do not add up to the level,
+    /// then one of the values or the level is likely misread
+    pub consistent: bool,
+}
+
+/// For every roll count, the steps which add up closest to the value, and how far they are
+/// the displayed values are truncated, so the value is allowed to be a display unit off
+fn decompose(stat: &RelicStat, star: i32, max_rolls: usize) -> Vec<(SubStatSteps, f64)> {
+    let (base, step) = match stat.name.sub_stat_base_step(star) {
+        Some(v) => v,
+        None => return Vec::new(),
+    };
+    let tolerance = stat.name.display_unit();
+
+    let mut result = Vec::new();
+    for rolls in 1..=max_rolls {
+        let steps = ((stat.value - base * rolls as f64) / step).round();
+        if steps < 0.0 || steps > (rolls * 2) as f64 {
+            continue;
+        }
+        let error = (base * rolls as f64 + step * steps - stat.value).abs();
+        if error <= tolerance {
+            result.push((SubStatSteps { rolls, steps: steps as usize }, error));
+        }
+    }
+    result
+}
+
+impl RelicStepAnalysis {
+    /// Decompose the sub stats into steps so that the total number of rolls agrees with the level
+    /// a relic of `star` starts with `star - 2` or `star - 1` sub stats, and gets a roll every 3 levels,
+    /// which adds a sub stat until there are 4
+    pub fn analyze(relic: &StarRailRelic) -> RelicStepAnalysis {
+        let star = relic.star;
+        if star < 2 {
+            return RelicStepAnalysis::default();
+        }
+
+        let upgrades = (relic.level.clamp(0, star * 3) / 3) as usize;
+        let sub_stats = [&relic.sub_stat_1, &relic.sub_stat_2, &relic.sub_stat_3, &relic.sub_stat_4];
+        let candidates = sub_stats.iter()
+            .map(|s| s.as_ref().map(|stat| decompose(stat, star, 1 + upgrades)))
+            .collect::<Vec<_>>();
+        let decomposable = candidates.iter().flatten().all(|c| !c.is_empty());
+
+        let roll_counts = candidates.iter()
+            .map(|c| c.as_ref().map(|c| c.iter().map(|(s, e)| (s.rolls, *e)).collect()))
+            .collect::<Vec<_>>();
+        let initial_counts = [star as usize - 2, star as usize - 1];
+        let choice = match choose_roll_counts(&roll_counts, &initial_counts, upgrades) {
+            Some(v) => v,
+            None => return RelicStepAnalysis {
+                consistent: !decomposable,
+                ..RelicStepAnalysis::default()
+            },
+        };
+
+        let mut result = RelicStepAnalysis {
+            sub_stats: Default::default(),
+            initial_sub_stats: choice.initial_sub_stats,
+            consistent: true,
+        };
+        for (i, (c, index)) in candidates.into_iter().zip(choice.chosen).enumerate() {
+            if let (Some(c), Some(index)) = (c, index) {
+                result.sub_stats[i] = Some(c[index].0.clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relic::{RelicSetName, RelicSlot, RelicStatName};
+
+    fn stat(name: RelicStatName, value: f64) -> Option<RelicStat> {
+        Some(RelicStat { name, value })
+    }
+
+    fn relic(level: i32, sub_stats: [Option<RelicStat>; 4]) -> StarRailRelic {
+        let [sub_stat_1, sub_stat_2, sub_stat_3, sub_stat_4] = sub_stats;
+        StarRailRelic {
+            set_name: RelicSetName::PasserbyofWanderingCloud,
+            slot: RelicSlot::Head,
+            star: 5,
+            level,
+            main_stat: RelicStat { name: RelicStatName::HP, value: 112.0 },
+            sub_stat_1,
+            sub_stat_2,
+            sub_stat_3,
+            sub_stat_4,
+            equip: None,
+            lock: false,
+            discard: false,
+        }
+    }
+
+    #[test]
+    fn test_upgraded() {
+        // two high rolls of crit rate, the second one at level 3
+        let analysis = RelicStepAnalysis::analyze(&relic(3, [
+            stat(RelicStatName::CRITRate, 0.0648),
+            stat(RelicStatName::CRITDMG, 0.05184),
+            stat(RelicStatName::ATKPercentage, 0.03456),
+            stat(RelicStatName::SPD, 2.0),
+        ]));
+
+        assert_eq!(analysis.sub_stats[0], Some(SubStatSteps { rolls: 2, steps: 4 }));
+        assert_eq!(analysis.sub_stats[1], Some(SubStatSteps { rolls: 1, steps: 0 }));
+        assert_eq!(analysis.initial_sub_stats, Some(4));
+        assert!(analysis.consistent);
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        // a 5 star relic starts with at least 3 sub stats
+        let analysis = RelicStepAnalysis::analyze(&relic(0, [
+            stat(RelicStatName::CRITRate, 0.02592),
+            stat(RelicStatName::CRITDMG, 0.05184),
+            None,
+            None,
+        ]));
+
+        assert_eq!(analysis.sub_stats, [None, None, None, None]);
+        assert!(!analysis.consistent);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::relic::{RelicSetName, RelicStatName};
+
+/// Names of all relics that `RelicSetName::from_zh_cn` accepts
+pub const RELIC_NAMES_ZH_CN: &[&str] = RelicSetName::PIECE_NAMES_ZH_CN;
+
+/// Names of all stats that `RelicStatName::from_zh_cn` accepts
+pub const STAT_NAMES_ZH_CN: &[&str] = RelicStatName::NAMES_ZH_CN;
+
+#[rustfmt::skip]
+pub const SUB_STAT_NAMES_ZH_CN: &[&str] = &[
+    "生命值", "攻击力", "防御力", "速度",
+    "暴击率", "暴击伤害", "击破特攻", "效果命中",
+    "效果抵抗",
+];
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+use include_dir::{include_dir, Dir};
+use log::{info, warn};
+
+use yas::positioning::{Pos, Rect};
+use yas::utils::crop_rect;
+use yas::vision::TemplateClassifier;
+
+use crate::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
+use crate::scanner::relic_scanner::StarRailRelicScannerConfig;
+
+/// The equipper of a relic whose avatar is not recognized, as listed for double checking
+pub const UNKNOWN_EQUIPPER: &str = "Unknown";
+
+/// The avatar templates of the characters at 1920x1080
+static BUNDLED_AVATAR_TEMPLATES: Dir = include_dir!("$CARGO_MANIFEST_DIR/avatar_templates");
+
+#[derive(Debug, Clone)]
+pub struct EquipperRecognition {
+    /// None if the avatar is not recognized
+    pub name: Option<String>,
+    /// in [0, 1]
+    pub confidence: f32,
+    /// where the avatar is recognized, relative to the window
+    pub rect: Rect<f64>,
+}
+
+impl EquipperRecognition {
+    pub fn name_or_unknown(&self) -> String {
+        self.name.clone().unwrap_or_else(|| String::from(UNKNOWN_EQUIPPER))
+    }
+}
+
+/// Recognizes the avatar of the equipper in the relic panel
+#[derive(Clone)]
+pub enum EquipperRecognizer {
+    /// match avatar templates over the whole avatar region
+    Templates {
+        classifier: TemplateClassifier<String>,
+        rect: Rect<f64>,
+    },
+    /// there is no template, every equipper is unknown
+    Unavailable {
+        rect: Rect<f64>,
+    },
+}
+
+impl EquipperRecognizer {
+    /// Match the avatar templates of `--avatar-templates`, or the bundled ones by default,
+    /// the equipper is reported as unknown when there is no template
+    pub fn new(window_info: &RelicScannerWindowInfo, config: &StarRailRelicScannerConfig) -> Result<Self> {
+        let classifier = match config.avatar_templates.as_ref() {
+            Some(dir) => {
+                let classifier = TemplateClassifier::load_dir(dir, config.avatar_min_score)?;
+                if classifier.is_empty() {
+                    return Err(anyhow!("头像模板目录 {} 中没有模板", dir.display()));
+                }
+                classifier
+            },
+            None => TemplateClassifier::from_png_files(
+                BUNDLED_AVATAR_TEMPLATES.files().map(|f| (f.path(), f.contents())),
+                config.avatar_min_score,
+            )?,
+        };
+
+        let rect = match (classifier.is_empty(), window_info.equipper_rect) {
+            (false, Some(rect)) => rect,
+            (false, None) if config.avatar_templates.is_some() => {
+                return Err(anyhow!("当前分辨率没有角色头像区域 starrail_relic_equipper_rect，无法使用头像模板"));
+            },
+            _ => {
+                warn!("没有可用的角色头像模板，装备角色将记为 {}，可通过 --avatar-templates 指定头像模板", UNKNOWN_EQUIPPER);
+                let pos = window_info.equipper_pos;
+                return Ok(EquipperRecognizer::Unavailable {
+                    rect: window_info.equipper_rect.unwrap_or(Rect {
+                        left: pos.x - 1.0,
+                        top: pos.y - 1.0,
+                        width: 3.0,
+                        height: 3.0,
+                    }),
+                });
+            },
+        };
+        info!("加载 {} 个角色头像模板", classifier.len());
+
+        Ok(EquipperRecognizer::Templates {
+            classifier,
+            rect,
+        })
+    }
+
+    /// `panel_image` is captured from `panel_rect`, which contains the avatar
+    pub fn recognize(&self, panel_image: &RgbImage, panel_rect: Rect<f64>) -> EquipperRecognition {
+        let origin = Pos {
+            x: -panel_rect.left,
+            y: -panel_rect.top,
+        };
+
+        match self {
+            EquipperRecognizer::Templates { classifier, rect } => {
+                let classification = crop_rect(panel_image, &rect.translate(origin))
+                    .map(|im| classifier.classify(&im));
+                EquipperRecognition {
+                    name: classification.as_ref().and_then(|c| c.label.clone()),
+                    confidence: classification.map_or(0.0, |c| c.confidence as f32),
+                    rect: *rect,
+                }
+            },
+            EquipperRecognizer::Unavailable { rect } => EquipperRecognition {
+                name: None,
+                confidence: 0.0,
+                rect: *rect,
+            },
+        }
+    }
+}
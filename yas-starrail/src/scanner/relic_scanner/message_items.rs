@@ -1,9 +1,12 @@
 use image::RgbImage;
 
 pub struct SendItem {
+    /// the position of the item in scan order, starting from 0
+    pub index: usize,
+    /// 0 for the first capture, and the number of retries for the captures again
+    pub attempt: usize,
     pub panel_image: RgbImage,
-    pub equip: String,
     pub star: usize,
     pub lock: bool,
     pub discard: bool,
-}
\ No newline at end of file
+}
@@ -1,8 +1,10 @@
+pub use equipper_recognizer::UNKNOWN_EQUIPPER;
 pub use relic_scanner::StarRailRelicScanner;
 pub use relic_scanner_config::StarRailRelicScannerConfig;
 pub use scan_result::StarRailRelicScanResult;
 // pub use relic_scanner_window_info::RelicScannerWindowInfo;
 
+mod equipper_recognizer;
 mod match_colors;
 mod message_items;
 mod relic_scanner;
@@ -10,3 +12,4 @@ mod relic_scanner_config;
 pub mod relic_scanner_window_info;
 mod relic_scanner_worker;
 mod scan_result;
+mod region_requirements;
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use yas::ocr::{RegionRequirement, TextConstraint};
+
+use crate::relic::{RELIC_NAMES_ZH_CN, STAT_NAMES_ZH_CN, SUB_STAT_NAMES_ZH_CN};
+
+/// e.g. `705`, `2.3` or `10.5%`
+fn stat_value() -> TextConstraint {
+    TextConstraint::Any(vec![
+        TextConstraint::Number { thousands_separator: true, max_decimals: 2 },
+        TextConstraint::Sequence(vec![
+            TextConstraint::Number { thousands_separator: false, max_decimals: 2 },
+            TextConstraint::Literal("%"),
+        ]),
+    ])
+}
+
+/// The text of the OCR regions which can be parsed, by field name
+/// relics of low rarity have less than four sub stats
+pub fn get_region_requirements() -> HashMap<&'static str, RegionRequirement> {
+    let sub_stat_name = TextConstraint::lexicon(SUB_STAT_NAMES_ZH_CN);
+    let level = TextConstraint::Number { thousands_separator: false, max_decimals: 0 };
+
+    let mut result = HashMap::from([
+        ("title", RegionRequirement::required(TextConstraint::lexicon(RELIC_NAMES_ZH_CN))),
+        ("main_stat_name", RegionRequirement::required(TextConstraint::lexicon(STAT_NAMES_ZH_CN))),
+        ("main_stat_value", RegionRequirement::required(stat_value())),
+        ("level", RegionRequirement::required(TextConstraint::Any(vec![
+            TextConstraint::Sequence(vec![TextConstraint::Literal("+"), level.clone()]),
+            level,
+        ]))),
+    ]);
+    for (name, value) in [
+        ("sub_stat_name_1", "sub_stat_value_1"),
+        ("sub_stat_name_2", "sub_stat_value_2"),
+        ("sub_stat_name_3", "sub_stat_value_3"),
+        ("sub_stat_name_4", "sub_stat_value_4"),
+    ] {
+        result.insert(name, RegionRequirement::optional(sub_stat_name.clone()));
+        result.insert(value, RegionRequirement::optional(stat_value()));
+    }
+
+    result
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, ops::{Coroutine, CoroutineState}, pin::Pin, rc::Rc, sync::mpsc::{self, Sender}, time::SystemTime};
+use std::{cell::RefCell, ops::{Coroutine, CoroutineState}, pin::Pin, rc::Rc, sync::mpsc, time::SystemTime};
 
 use anyhow::Result;
 use clap::FromArgMatches;
@@ -7,20 +7,32 @@ use log::{error, info};
 
 use yas::capture::{Capturer, GenericCapturer};
 use yas::game_info::GameInfo;
-use yas::ocr::{ImageToText, yas_ocr_model};
+use yas::game_language::GameLanguage;
+use yas::ocr::{ImageToText, LowConfidenceReport, OCRConfig, OCRModelRegistry, yas_ocr_model};
 use yas::positioning::Pos;
-use yas::utils::color_distance;
+use yas::screen_state::{ScreenProbe, ScreenState, ScreenStateClassifier};
+use yas::sort_order::{SortOrder, SortOrderSwitcher};
+use yas::vision::ColorClassifier;
 use yas::window_info::{FromWindowInfoRepository, WindowInfoRepository};
+use yas::utils;
+use yas::worker_pool::{PageRetry, PoolSender};
 
+use crate::scanner::relic_scanner::equipper_recognizer::EquipperRecognizer;
 use crate::scanner::relic_scanner::match_colors::{MATCH_COLORS, MatchColors};
 use crate::scanner::relic_scanner::message_items::SendItem;
 use crate::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
 use crate::scanner::relic_scanner::relic_scanner_worker::RelicScannerWorker;
 use crate::scanner::relic_scanner::scan_result::StarRailRelicScanResult;
-use crate::scanner_controller::repository_layout::{ReturnResult, StarRailRepositoryScanController, StarRailRepositoryScannerLogicConfig};
+use crate::scanner_controller::repository_layout::{ReturnResult, ScanEvent, StarRailRepositoryScanController, StarRailRepositoryScannerLogicConfig};
 
 use super::relic_scanner_config::StarRailRelicScannerConfig;
 
+fn load_yas_model(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    Ok(Box::new(
+        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json", config.backend(), config.intra_threads)?
+    ))
+}
+
 pub struct StarRailRelicScanner {
     scanner_config: StarRailRelicScannerConfig,
     window_info: RelicScannerWindowInfo,
@@ -28,18 +40,20 @@ pub struct StarRailRelicScanner {
     image_to_text: Box<dyn ImageToText<RgbImage> + Send>,
     controller: Rc<RefCell<StarRailRepositoryScanController>>,
     capturer: Rc<dyn Capturer<RgbImage>>,
+    low_confidence_report: LowConfidenceReport,
 
     match_colors: MatchColors,
 }
 
 // constructor
 impl StarRailRelicScanner {
-    fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-        let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-            yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-        );
-        // let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(yas::ocr::PPOCRChV4RecInfer::new()?);
-        Ok(model)
+    /// The models which can be chosen by `--ocr-model`, `yas` is the one trained for the game
+    pub fn get_model_registry() -> OCRModelRegistry {
+        OCRModelRegistry::new().with_model("yas", load_yas_model)
+    }
+
+    pub fn get_image_to_text(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+        Self::get_model_registry().load(&config.model, config)
     }
 
     fn get_capturer() -> Result<Rc<dyn Capturer<RgbImage>>> {
@@ -52,7 +66,9 @@ impl StarRailRelicScanner {
         controller_config: StarRailRepositoryScannerLogicConfig,
         game_info: GameInfo
     ) -> Result<Self> {
+        let image_to_text = Self::get_image_to_text(&config.ocr)?;
         Ok(StarRailRelicScanner {
+            low_confidence_report: LowConfidenceReport::new(config.low_confidence),
             scanner_config: config,
             window_info: RelicScannerWindowInfo::from_window_info_repository(
                 game_info.window.to_rect_usize().size(),
@@ -66,7 +82,7 @@ impl StarRailRelicScanner {
                 game_info.clone()
             )?)),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             capturer: Self::get_capturer()?,
 
             match_colors: MATCH_COLORS,
@@ -84,14 +100,17 @@ impl StarRailRelicScanner {
             game_info.platform,
             window_info_repo
         )?;
+        let scanner_config = StarRailRelicScannerConfig::from_arg_matches(arg_matches)?;
+        let image_to_text = Self::get_image_to_text(&scanner_config.ocr)?;
         Ok(StarRailRelicScanner {
-            scanner_config: StarRailRelicScannerConfig::from_arg_matches(arg_matches)?,
+            low_confidence_report: LowConfidenceReport::new(scanner_config.low_confidence),
+            scanner_config,
             window_info,
             controller: Rc::new(RefCell::new(
                 StarRailRepositoryScanController::from_arg_matches(window_info_repo, arg_matches, game_info.clone())?
             )),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             capturer: Self::get_capturer()?,
             match_colors: MATCH_COLORS,
         })
@@ -113,11 +132,9 @@ impl StarRailRelicScanner {
         };
         let color = self.capturer.capture_color(pos)?;
 
-        let (index, _) = self.match_colors.match_colors_star
-            .iter()
-            .enumerate()
-            .min_by_key(|&(_, match_color)| color_distance(match_color, &color))
-            .unwrap();
+        let index = ColorClassifier::from_colors(&self.match_colors.match_colors_star)
+            .classify(&color)
+            .label;
 
         Ok(index + 1)
     }
@@ -129,11 +146,9 @@ impl StarRailRelicScanner {
         };
         let color = self.capturer.capture_color(pos)?;
 
-        let (index, _) = self.match_colors.match_colors_lock
-            .iter()
-            .enumerate()
-            .min_by_key(|&(_, match_color)| color_distance(match_color, &color))
-            .unwrap();
+        let index = ColorClassifier::from_colors(&self.match_colors.match_colors_lock)
+            .classify(&color)
+            .label;
 
         Ok(index == 0)
     }
@@ -145,28 +160,52 @@ impl StarRailRelicScanner {
         };
         let color = self.capturer.capture_color(pos)?;
 
-        let (index, _) = self.match_colors.match_colors_discard
-            .iter()
-            .enumerate()
-            .min_by_key(|&(_, match_color)| color_distance(match_color, &color))
-            .unwrap();
+        let index = ColorClassifier::from_colors(&self.match_colors.match_colors_discard)
+            .classify(&color)
+            .label;
 
         Ok(index == 0)
     }
 
-    pub fn get_equipper(&self) -> Result<String> {
-        let pos: Pos<i32> = Pos {
-            x: self.game_info.window.left + self.window_info.equipper_pos.x as i32,
-            y: self.game_info.window.top + self.window_info.equipper_pos.y as i32,
-        };
-        let color = self.capturer.capture_color(pos)?;
+    pub fn get_screen_state_classifier(&self) -> ScreenStateClassifier {
+        let mut classifier = ScreenStateClassifier::new();
+        classifier.add_loading_rule();
+        if let Some(rect) = self.window_info.dialog_button_rect {
+            classifier.add_rule(ScreenState::Dialog, vec![ScreenProbe::text(rect, &["确认", "取消"])]);
+        }
+        classifier.add_rule(
+            ScreenState::RelicInventory,
+            vec![ScreenProbe::text(self.window_info.item_count_rect, &["遗器数量"])]
+        );
+        if let Some(rect) = self.window_info.character_anchor_rect {
+            classifier.add_rule(ScreenState::Character, vec![ScreenProbe::text(rect, &["详情"])]);
+        }
+        classifier
+    }
+
+    /// Make sure the game is showing the relic inventory before any click happens
+    pub fn check_screen_state(&self) -> Result<()> {
+        if self.scanner_config.skip_screen_check {
+            return Ok(());
+        }
 
-        let (name, _) = self.match_colors.match_colors_equipper
-            .iter()
-            .min_by_key(|&(_, match_color)| color_distance(match_color, &color))
-            .unwrap();
+        let frame = self.capturer.capture_rect(self.game_info.window)?;
+        self.get_screen_state_classifier().ensure(
+            &frame,
+            self.image_to_text.as_ref(),
+            ScreenState::RelicInventory
+        )
+    }
 
-        Ok(name.to_string())
+    /// Switch to the configured sort order if any, and return the order the scan runs in
+    pub fn prepare_sort_order(&self) -> Result<Option<SortOrder>> {
+        SortOrderSwitcher::new(
+            self.window_info.sort_order_layout(),
+            self.game_info.window,
+            GameLanguage::ZhCn,
+            self.capturer.as_ref(),
+            self.image_to_text.as_ref(),
+        ).prepare(self.scanner_config.sort)
     }
 
     pub fn get_item_count(&self) -> Result<i32> {
@@ -198,27 +237,39 @@ impl StarRailRelicScanner {
         }
     }
 
+    /// Recognized fields of the last scan that the user should double check
+    pub fn low_confidence_report(&self) -> &LowConfidenceReport {
+        &self.low_confidence_report
+    }
+
     pub fn scan(&mut self) -> Result<Vec<StarRailRelicScanResult>> {
         info!("开始扫描，使用鼠标右键中断扫描");
 
+        self.check_screen_state()?;
+        let sort_order = self.prepare_sort_order()?;
+
         let now = SystemTime::now();
-        let (tx, rx) = mpsc::channel::<Option<SendItem>>();
         // let token = self.cancellation_token.clone();
         let count = self.get_item_count()?;
-        let worker = RelicScannerWorker::new(
-            self.window_info.clone(),
-            self.scanner_config.clone()
-        )?;
-
-        let join_handle = worker.run(rx);
-        info!("Worker created");
+        let worker_count = self.scanner_config.ocr.workers.max(1);
+        let equipper = EquipperRecognizer::new(&self.window_info, &self.scanner_config)?;
+        let mut workers = Vec::with_capacity(worker_count);
+        for _ in 0..worker_count {
+            workers.push(RelicScannerWorker::new(
+                self.window_info.clone(),
+                self.scanner_config.clone(),
+                sort_order,
+                equipper.clone(),
+            )?);
+        }
 
-        self.send(&tx, count);
+        let (feedback_tx, feedback_rx) = mpsc::channel();
+        let (mut tx, join_handle) = RelicScannerWorker::run(workers, self.scanner_config.ocr.queue_size, feedback_tx);
+        info!("创建 {} 个识别线程", worker_count);
 
-        match tx.send(None) {
-            Ok(_) => info!("扫描结束，等待识别线程结束，请勿关闭程序"),
-            Err(_) => info!("扫描结束，识别已完成"),
-        }
+        self.send(&mut tx, &mut PageRetry::new(feedback_rx), count, sort_order);
+        drop(tx);
+        info!("扫描结束，等待识别线程结束，请勿关闭程序");
 
         let average_inference_time = self.image_to_text.get_average_inference_time();
         if let Some(t) = average_inference_time {
@@ -227,15 +278,45 @@ impl StarRailRelicScanner {
         }
 
         match join_handle.join() {
-            Ok(v) => {
+            Ok((v, low_confidence_report)) => {
                 info!("识别耗时: {:?}", now.elapsed()?);
+                low_confidence_report.log();
+                self.low_confidence_report = low_confidence_report;
                 Ok(v)
             },
             Err(_) => Err(anyhow::anyhow!("识别线程出现错误")),
         }
     }
 
-    fn send(&mut self, tx: &Sender<Option<SendItem>>, count: i32) {
+    /// Capture again the items of the current page which fail to parse, returns false if the scan should stop
+    fn retry_page(&mut self, tx: &mut PoolSender<SendItem>, retry: &mut PageRetry) -> Result<bool> {
+        while let Some(item) = retry.next_retry() {
+            if utils::is_rmb_down() {
+                info!("用户中断");
+                return Ok(false);
+            }
+
+            info!("重新截图识别第 {} 个物品，第 {} 次重试", item.index + 1, item.attempt);
+            self.controller.borrow_mut().reselect(item.row, item.col, self.scanner_config.retry_wait * item.attempt as u32)?;
+
+            let send_item = SendItem {
+                index: item.index,
+                attempt: item.attempt,
+                panel_image: self.capture_panel()?,
+                star: self.get_star()?,
+                lock: self.get_lock()?,
+                discard: self.get_discard()?,
+            };
+            if tx.send(send_item).is_err() {
+                return Ok(false);
+            }
+            retry.sent(item.index, item.row, item.col);
+        }
+
+        Ok(true)
+    }
+
+    fn send(&mut self, tx: &mut PoolSender<SendItem>, retry: &mut PageRetry, count: i32, sort_order: Option<SortOrder>) {
         let mut generator = StarRailRepositoryScanController::get_generator(
             self.controller.clone(),
             count as usize
@@ -244,16 +325,27 @@ impl StarRailRelicScanner {
         loop {
             let pinned_generator = Pin::new(&mut generator);
             match pinned_generator.resume(()) {
-                CoroutineState::Yielded(_) => {
+                CoroutineState::Yielded(ScanEvent::PageEnd) => {
+                    match self.retry_page(tx, retry) {
+                        Ok(true) => (),
+                        Ok(false) => break,
+                        Err(e) => {
+                            error!("重新截图发生错误：{}", e);
+                            break;
+                        }
+                    }
+                    retry.end_page();
+                },
+                CoroutineState::Yielded(ScanEvent::Item { index, row, col }) => {
                     // let image = self.capture_panel().unwrap();
                     let panel_image = self.capture_panel().unwrap();
-                    let equip = self.get_equipper().unwrap();
                     let star = self.get_star().unwrap();
                     let lock = self.get_lock().unwrap();
                     let discard = self.get_discard().unwrap();
 
                     // todo normalize types
-                    if (star as i32) < self.scanner_config.min_star {
+                    // low star items are still sent when not sorted by rarity, the worker filters them
+                    if (star as i32) < self.scanner_config.min_star && SortOrder::can_stop_at_star(sort_order) {
                         info!(
                             "找到满足最低星级要求 {} 的物品，准备退出……",
                             self.scanner_config.min_star
@@ -261,9 +353,11 @@ impl StarRailRelicScanner {
                         break;
                     }
 
-                    if tx.send(Some(SendItem { panel_image, equip, star, lock, discard })).is_err() {
+                    if tx.send(SendItem { index, attempt: 0, panel_image, star, lock, discard }).is_err() {
                         break;
                     }
+                    retry.sent(index, row, col);
+                    retry.poll();
 
                     // scanned_count += 1;
                 },
@@ -1,3 +1,8 @@
+use std::path::PathBuf;
+
+use yas::ocr::OCRConfig;
+use yas::sort_order::SortOrder;
+
 #[derive(Clone, clap::Args)]
 pub struct StarRailRelicScannerConfig {
     /// Items with stars less than this will be ignored
@@ -17,4 +22,40 @@ pub struct StarRailRelicScannerConfig {
 
     #[arg(id = "number", long, help = "指定遗器数量", value_name = "NUMBER", default_value_t = -1)]
     pub number: i32,
+
+    /// Do not check whether the game is showing the relic inventory before scanning
+    #[arg(id = "skip-screen-check", long = "skip-screen-check", help = "跳过界面检查")]
+    pub skip_screen_check: bool,
+
+    /// Switch the inventory to this sort order before scanning
+    #[arg(id = "sort", long = "sort", help = "扫描前切换排序方式", value_name = "SORT", value_enum)]
+    pub sort: Option<SortOrder>,
+
+    /// OCR results less confident than this are listed for double checking
+    #[arg(id = "low-confidence", long = "low-confidence", help = "置信度低于该值的识别结果将被列出以供核对", value_name = "CONFIDENCE", default_value_t = 0.9)]
+    pub low_confidence: f32,
+
+    /// Decode OCR results greedily, instead of constraining them to known names and number formats
+    #[arg(id = "greedy-decoding", long = "greedy-decoding", help = "不使用词表与数值格式约束识别结果")]
+    pub greedy_decoding: bool,
+
+    /// Items whose text cannot be parsed are selected and captured again, up to this many times
+    #[arg(id = "retry-attempts", long = "retry-attempts", help = "识别结果无法解析时重新截图识别的最大次数，为 0 时不重试", value_name = "ATTEMPTS", default_value_t = 2)]
+    pub retry_attempts: usize,
+
+    /// The time to wait before capturing an item again, in addition to the usual, multiplied by the attempt
+    #[arg(id = "retry-wait", long = "retry-wait", help = "重新截图前额外等待的时间（ms），随重试次数递增", value_name = "MS", default_value_t = 200)]
+    pub retry_wait: u32,
+
+    /// A directory of avatar templates named after the characters, e.g. `Acheron.png`, cropped from `starrail_relic_equipper_rect`
+    /// without it the bundled templates are used, and the equipper is unknown when there is none
+    #[arg(id = "avatar-templates", long = "avatar-templates", help = "角色头像模板目录，文件名为角色名，用于识别遗器的装备角色，默认使用内置模板，没有模板时装备角色记为未知", value_name = "DIR")]
+    pub avatar_templates: Option<PathBuf>,
+
+    /// Avatars matching the best template less than this are recognized as unknown
+    #[arg(id = "avatar-min-score", long = "avatar-min-score", help = "头像与模板的匹配度低于该值时视为未知角色", value_name = "SCORE", default_value_t = 0.7)]
+    pub avatar_min_score: f64,
+
+    #[command(flatten)]
+    pub ocr: OCRConfig,
 }
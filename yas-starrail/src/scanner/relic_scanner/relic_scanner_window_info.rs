@@ -1,4 +1,5 @@
 use yas::positioning::{Pos, Rect};
+use yas::sort_order::SortOrderLayout;
 
 #[derive(Clone, yas_derive::YasWindowInfo, Debug)]
 pub struct RelicScannerWindowInfo {
@@ -40,6 +41,10 @@ pub struct RelicScannerWindowInfo {
     #[window_info(rename = "starrail_relic_equipper_pos")]
     pub equipper_pos: Pos<f64>,
 
+    /// the avatar of the equipper, where the avatar templates are matched, optional
+    #[window_info(rename = "starrail_relic_equipper_rect")]
+    pub equipper_rect: Option<Rect<f64>>,
+
     #[window_info(rename = "starrail_relic_item_count_rect")]
     pub item_count_rect: Rect<f64>,
 
@@ -60,4 +65,35 @@ pub struct RelicScannerWindowInfo {
 
     #[window_info(rename = "starrail_repository_item_row")]
     pub row: i32,
+
+    /// a text region only shown in the character screen, optional
+    #[window_info(rename = "starrail_screen_character_anchor_rect")]
+    pub character_anchor_rect: Option<Rect<f64>>,
+
+    /// the button region of a popup dialog, optional
+    #[window_info(rename = "starrail_screen_dialog_button_rect")]
+    pub dialog_button_rect: Option<Rect<f64>>,
+
+    /// the sort dropdown of the inventory, optional
+    #[window_info(rename = "starrail_repository_sort_rect")]
+    pub sort_rect: Option<Rect<f64>>,
+
+    /// the options of the opened sort dropdown, optional
+    #[window_info(rename = "starrail_repository_sort_rarity_pos")]
+    pub sort_rarity_pos: Option<Pos<f64>>,
+    #[window_info(rename = "starrail_repository_sort_level_pos")]
+    pub sort_level_pos: Option<Pos<f64>>,
+    #[window_info(rename = "starrail_repository_sort_time_pos")]
+    pub sort_time_pos: Option<Pos<f64>>,
+}
+
+impl RelicScannerWindowInfo {
+    pub fn sort_order_layout(&self) -> SortOrderLayout {
+        SortOrderLayout {
+            sort_rect: self.sort_rect,
+            rarity_pos: self.sort_rarity_pos,
+            level_pos: self.sort_level_pos,
+            time_pos: self.sort_time_pos,
+        }
+    }
 }
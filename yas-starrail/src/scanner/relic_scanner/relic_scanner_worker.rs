@@ -1,23 +1,33 @@
-use std::collections::HashSet;
-use std::sync::mpsc::Receiver;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::Sender;
 use std::thread::JoinHandle;
 
 use anyhow::Result;
 use image::{GenericImageView, RgbImage};
 use log::{error, info, warn};
 
-use yas::ocr::{yas_ocr_model, ImageToText};
+use yas::ocr::{FieldConfidence, LowConfidenceReport, OCRModelSet, RegionRequirement, ScanConfidence};
 use yas::positioning::{Pos, Rect};
+use yas::sort_order::SortOrder;
+use yas::worker_pool::{ordered_worker_pool, PoolSender, ScanFeedback};
 
+use crate::scanner::relic_scanner::equipper_recognizer::EquipperRecognizer;
 use crate::scanner::relic_scanner::message_items::SendItem;
 use crate::scanner::relic_scanner::relic_scanner_window_info::RelicScannerWindowInfo;
 use crate::scanner::relic_scanner::scan_result::StarRailRelicScanResult;
 use crate::scanner::relic_scanner::StarRailRelicScannerConfig;
+use crate::scanner::relic_scanner::StarRailRelicScanner;
+use crate::scanner::relic_scanner::region_requirements::get_region_requirements;
 
 pub struct RelicScannerWorker {
-    model: Box<dyn ImageToText<RgbImage> + Send>,
+    models: OCRModelSet,
     window_info: RelicScannerWindowInfo,
     config: StarRailRelicScannerConfig,
+    /// the sort order of the inventory, None if it is not recognized
+    sort_order: Option<SortOrder>,
+    /// the text of the OCR regions which can be parsed, by field name
+    region_requirements: HashMap<&'static str, RegionRequirement>,
+    equipper: EquipperRecognizer,
 }
 
 fn parse_level(s: &str) -> Result<i32> {
@@ -32,59 +42,121 @@ fn parse_level(s: &str) -> Result<i32> {
     return Ok(level);
 }
 
-fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-    let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-    );
-    // let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(PPOCRChV4RecInfer::new()?);
-    Ok(model)
-}
-
 impl RelicScannerWorker {
     pub fn new(
         window_info: RelicScannerWindowInfo,
         config: StarRailRelicScannerConfig,
+        sort_order: Option<SortOrder>,
+        equipper: EquipperRecognizer,
     ) -> Result<Self> {
         Ok(RelicScannerWorker {
-            model: get_image_to_text()?,
+            models: StarRailRelicScanner::get_model_registry().load_set(&config.ocr)?,
             window_info,
             config,
+            sort_order,
+            region_requirements: get_region_requirements(),
+            equipper,
         })
     }
 
-    fn model_inference(&self, rect: Rect<f64>, captured_img: &RgbImage) -> Result<String> {
-        let relative_rect = rect.translate(Pos {
+    /// the rects are regions of the relic panel, which are recognized in batches
+    /// the texts are in the same order as `fields`
+    /// unless it is the last attempt, an error is returned if any text cannot be parsed, so that the item is captured again
+    fn model_inference(
+        &self,
+        fields: &[(&str, Rect<f64>)],
+        captured_img: &RgbImage,
+        attempt: usize,
+        confidence: &mut ScanConfidence
+    ) -> Result<Vec<String>> {
+        let relative_rects = fields.iter().map(|(_, rect)| rect.translate(Pos {
             x: -self.window_info.panel_rect.left,
             y: -self.window_info.panel_rect.top,
-        });
+        })).collect::<Vec<_>>();
+
+        let raw_images = relative_rects.iter().map(|rect| captured_img.view(
+            rect.left as u32, rect.top as u32, rect.width as u32, rect.height as u32
+        ).to_image()).collect::<Vec<_>>();
+
+        let regions = fields.iter()
+            .zip(raw_images.iter())
+            .map(|(&(field, _), im)| (field, im, self.region_requirements.get(field)))
+            .collect::<Vec<_>>();
 
-        let raw_img = captured_img.view(
-            relative_rect.left as u32, relative_rect.top as u32, relative_rect.width as u32, relative_rect.height as u32
-        ).to_image();
+        let batch_size = self.config.ocr.batch_size;
+        let constrained = !self.config.greedy_decoding;
+        // the model has failed the item once, so the fallback model gets the first try
+        let results = if attempt > 0 {
+            self.models.recognize_fallback_first(&regions, batch_size, constrained)?
+        } else {
+            self.models.recognize(&regions, batch_size, constrained)?
+        };
 
-        let inference_result = self.model.image_to_text(&raw_img, false);
+        if attempt < self.config.retry_attempts {
+            for (&(field, _, requirement), result) in regions.iter().zip(results.iter()) {
+                if requirement.map_or(false, |r| !r.is_parseable(&result.text)) {
+                    return Err(anyhow::anyhow!("{} 的识别结果 \"{}\" 无法解析", field, result.text));
+                }
+            }
+        }
+
+        let mut texts = Vec::with_capacity(results.len());
+        for ((&(field, _), rect), result) in fields.iter().zip(relative_rects).zip(results) {
+            confidence.push(field, rect, &result);
+            texts.push(result.text);
+        }
 
-        inference_result
+        Ok(texts)
     }
 
-    fn scan_item_image(&self, item: SendItem) -> Result<StarRailRelicScanResult> {
+    fn scan_item_image(&self, item: &SendItem) -> Result<StarRailRelicScanResult> {
         let image = &item.panel_image;
+        let mut confidence = ScanConfidence::default();
 
-        let str_title = self.model_inference(self.window_info.title_rect, &image)?;
-        let str_main_stat_name = self.model_inference(self.window_info.main_stat_name_rect, &image)?;
-        let str_main_stat_value = self.model_inference(self.window_info.main_stat_value_rect, &image)?;
+        let fields = [
+            ("title", self.window_info.title_rect),
+            ("main_stat_name", self.window_info.main_stat_name_rect),
+            ("main_stat_value", self.window_info.main_stat_value_rect),
+            ("sub_stat_name_1", self.window_info.sub_stat_name_1),
+            ("sub_stat_name_2", self.window_info.sub_stat_name_2),
+            ("sub_stat_name_3", self.window_info.sub_stat_name_3),
+            ("sub_stat_name_4", self.window_info.sub_stat_name_4),
+            ("sub_stat_value_1", self.window_info.sub_stat_value_1),
+            ("sub_stat_value_2", self.window_info.sub_stat_value_2),
+            ("sub_stat_value_3", self.window_info.sub_stat_value_3),
+            ("sub_stat_value_4", self.window_info.sub_stat_value_4),
+            ("level", self.window_info.level_rect),
+            ("equip", self.window_info.equip_rect),
+        ];
+        let [
+            str_title,
+            str_main_stat_name,
+            str_main_stat_value,
+            str_sub_stat0_name,
+            str_sub_stat1_name,
+            str_sub_stat2_name,
+            str_sub_stat3_name,
+            str_sub_stat0_value,
+            str_sub_stat1_value,
+            str_sub_stat2_value,
+            str_sub_stat3_value,
+            str_level,
+            str_equip,
+        ]: [String; 13] = self.model_inference(&fields, image, item.attempt, &mut confidence)?.try_into().unwrap();
 
-        let str_sub_stat0_name = self.model_inference(self.window_info.sub_stat_name_1, &image)?;
-        let str_sub_stat1_name = self.model_inference(self.window_info.sub_stat_name_2, &image)?;
-        let str_sub_stat2_name = self.model_inference(self.window_info.sub_stat_name_3, &image)?;
-        let str_sub_stat3_name = self.model_inference(self.window_info.sub_stat_name_4, &image)?;
-        let str_sub_stat0_value = self.model_inference(self.window_info.sub_stat_value_1, &image)?;
-        let str_sub_stat1_value = self.model_inference(self.window_info.sub_stat_value_2, &image)?;
-        let str_sub_stat2_value = self.model_inference(self.window_info.sub_stat_value_3, &image)?;
-        let str_sub_stat3_value = self.model_inference(self.window_info.sub_stat_value_4, &image)?;
-
-        let str_level = self.model_inference(self.window_info.level_rect, &image)?;
-        let str_equip = self.model_inference(self.window_info.equip_rect, &image)?;
+        let equipper = self.equipper.recognize(image, self.window_info.panel_rect);
+        // the avatar region is empty when the relic is not equipped
+        if str_equip.ends_with("装备中") {
+            confidence.fields.push(FieldConfidence {
+                field: String::from("equipper"),
+                rect: equipper.rect.translate(Pos {
+                    x: -self.window_info.panel_rect.left,
+                    y: -self.window_info.panel_rect.top,
+                }),
+                text: equipper.name_or_unknown(),
+                confidence: equipper.confidence,
+            });
+        }
 
         Ok(StarRailRelicScanResult {
             name: str_title,
@@ -103,33 +175,58 @@ impl RelicScannerWorker {
                 str_sub_stat3_value,
             ],
             level: parse_level(&str_level)?,
-            equip: item.equip + &str_equip,
+            equip: str_equip,
+            equipper: equipper.name,
             star: item.star as i32,
             lock: item.lock,
             discard: item.discard,
+            confidence,
         })
     }
 
-    pub fn run(self, rx: Receiver<Option<SendItem>>) -> JoinHandle<Vec<StarRailRelicScanResult>> {
-        std::thread::spawn(move || {
+    /// Recognize the items with a pool of workers, and collect the results in the order of the items
+    /// every recognized item is reported through `feedback`, the results are returned once the sender is dropped
+    pub fn run(
+        workers: Vec<RelicScannerWorker>,
+        queue_size: usize,
+        feedback: Sender<ScanFeedback>,
+    ) -> (PoolSender<SendItem>, JoinHandle<(Vec<StarRailRelicScanResult>, LowConfidenceReport)>) {
+        let config = workers[0].config.clone();
+        let info = workers[0].window_info.clone();
+        let sort_order = workers[0].sort_order;
+
+        let (tx, rx) = ordered_worker_pool(workers, queue_size, |worker: &mut RelicScannerWorker, item: SendItem| {
+            let result = worker.scan_item_image(&item);
+            (item, result)
+        });
+
+        let join_handle = std::thread::spawn(move || {
             let mut results = Vec::new();
+            let mut low_confidence = LowConfidenceReport::new(config.low_confidence);
             let mut hash = HashSet::new();
             let mut consecutive_dup_count = 0;
 
-            let is_verbose = self.config.verbose;
-            let min_level = self.config.min_level;
-            let info = self.window_info.clone();
+            let is_verbose = config.verbose;
+            let min_star = config.min_star;
+            let min_level = config.min_level;
 
-            for (_cnt, item) in rx.into_iter().enumerate() {
-                let item = match item {
-                    Some(v) => v,
-                    None => break,
-                };
+            for (item, result) in rx {
+                let retry = result.is_err() && item.attempt < config.retry_attempts;
+                // the capturing thread may have finished, then there is no one to retry
+                let _ = feedback.send(ScanFeedback {
+                    index: item.index,
+                    attempt: item.attempt,
+                    retry,
+                });
 
-                let result = match self.scan_item_image(item) {
+                let result = match result {
                     Ok(v) => v,
                     Err(e) => {
-                        error!("识别错误: {}", e);
+                        if retry {
+                            warn!("第 {} 个物品识别失败: {}", item.index + 1, e);
+                        } else {
+                            error!("识别错误: {}", e);
+                        }
                         continue;
                     },
                 };
@@ -138,7 +235,15 @@ impl RelicScannerWorker {
                     info!("{:?}", result);
                 }
 
+                // the scanner only stops at low star items when sorted by rarity
+                if result.star < min_star {
+                    continue;
+                }
+
                 if result.level < min_level {
+                    if !SortOrder::can_stop_at_level(sort_order) {
+                        continue;
+                    }
                     info!(
                         "找到满足最低等级要求 {} 的物品({})，准备退出……",
                         min_level, result.level
@@ -147,16 +252,22 @@ impl RelicScannerWorker {
                     break;
                 }
 
+                // the items captured again are out of the scan order, they do not count as consecutive
                 if hash.contains(&result) {
-                    consecutive_dup_count += 1;
+                    if item.attempt == 0 {
+                        consecutive_dup_count += 1;
+                    }
                     warn!("识别到重复物品: {:#?}", result);
                 } else {
-                    consecutive_dup_count = 0;
+                    if item.attempt == 0 {
+                        consecutive_dup_count = 0;
+                    }
+                    low_confidence.add(item.index, &result.confidence, &item.panel_image);
                     hash.insert(result.clone());
-                    results.push(result);
+                    results.push((item.index, result));
                 }
 
-                if consecutive_dup_count >= info.col && !self.config.ignore_dup {
+                if consecutive_dup_count >= info.col && !config.ignore_dup {
                     error!("识别到连续多个重复物品，可能为翻页错误，或者为非背包顶部开始扫描");
                     break;
                 }
@@ -164,7 +275,12 @@ impl RelicScannerWorker {
 
             info!("识别结束，非重复物品数量: {}", hash.len());
 
-            results
-        })
+            results.sort_by_key(|&(index, _)| index);
+            let results = results.into_iter().map(|(_, result)| result).collect();
+
+            (results, low_confidence)
+        });
+
+        (tx, join_handle)
     }
 }
\ No newline at end of file
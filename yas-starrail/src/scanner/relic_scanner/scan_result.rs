@@ -1,3 +1,5 @@
+use yas::ocr::ScanConfidence;
+
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub struct StarRailRelicScanResult {
     pub name: String,
@@ -5,9 +7,13 @@ pub struct StarRailRelicScanResult {
     pub main_stat_value: String,
     pub sub_stat_name: [String; 4],
     pub sub_stat_value: [String; 4],
+    /// the text of the equip region, e.g. "装备中"
     pub equip: String,
+    /// the character whose avatar is shown, None if it is not recognized
+    pub equipper: Option<String>,
     pub level: i32,
     pub star: i32,
     pub lock: bool,
     pub discard: bool,
+    pub confidence: ScanConfidence,
 }
@@ -1,10 +1,12 @@
 pub use scan_logic::StarRailRepositoryScanController;
 pub use scan_logic::ReturnResult;
+pub use scan_event::ScanEvent;
 pub use config::StarRailRepositoryScannerLogicConfig;
 pub use window_info::StarRailRepositoryScanControllerWindowInfo;
 // pub use scroll_result::ScrollResult;
 
 mod config;
 mod scan_logic;
+mod scan_event;
 mod window_info;
 pub mod scroll_result;
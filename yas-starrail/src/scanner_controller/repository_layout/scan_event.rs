@@ -0,0 +1,12 @@
+/// What the scan controller yields to the scanner
+#[derive(Debug, Clone, Copy)]
+pub enum ScanEvent {
+    /// an item is selected and shown in the panel, `index` counts from 0 in scan order
+    Item {
+        index: usize,
+        row: usize,
+        col: usize,
+    },
+    /// every item of the current page is selected, the page is about to be scrolled or the scan to finish
+    PageEnd,
+}
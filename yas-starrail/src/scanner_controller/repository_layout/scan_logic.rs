@@ -5,23 +5,32 @@ use image::{Rgb, RgbImage};
 use yas::game_info::GameInfo;
 use crate::scanner_controller::repository_layout::config::StarRailRepositoryScannerLogicConfig;
 use yas::utils;
-use log::{info, error};
+use log::{info, error, warn};
 use std::time::SystemTime;
 use yas::capture::{Capturer, GenericCapturer};
 use yas::system_control::SystemControl;
 use crate::scanner_controller::repository_layout::window_info::StarRailRepositoryScanControllerWindowInfo;
 use anyhow::{anyhow, Result};
 use clap::{ArgMatches, FromArgMatches};
-use yas::utils::color_distance;
+use yas::vision::{ColorFlag, ColorHistogram};
 use yas::window_info::{FromWindowInfoRepository, WindowInfoRepository};
 use crate::scanner_controller::repository_layout::scroll_result::ScrollResult;
+use crate::scanner_controller::repository_layout::ScanEvent;
+
+/// The squared color distance within which a flag pixel is unchanged
+const FLAG_MAX_DISTANCE: usize = 10;
+
+/// The histogram bins per channel of the panel region, to detect whether an item changes
+const POOL_BINS_PER_CHANNEL: usize = 8;
 
 pub struct StarRailRepositoryScanController {
     // to detect whether an item changes
-    pool: f64,
+    pool: Option<ColorHistogram>,
+    // the row and col of the selected item in the current page
+    selected: Option<(usize, usize)>,
 
     // Stores initial gap colors for line gap detection
-    initial_flag: [Rgb<u8>; 50],
+    initial_flag: ColorFlag,
 
     // for scrolls
     scrolled_rows: u32,
@@ -42,16 +51,6 @@ pub struct StarRailRepositoryScanController {
     capturer: Rc<dyn Capturer<RgbImage>>,
 }
 
-fn calc_pool(row: &Vec<u8>) -> f32 {
-    let len = row.len() / 3;
-    let mut pool: f32 = 0.0;
-
-    for i in 0..len {
-        pool += row[i * 3] as f32;
-    }
-    pool
-}
-
 fn get_capturer() -> Result<Rc<dyn Capturer<RgbImage>>> {
     Ok(Rc::new(GenericCapturer::new()?))
 }
@@ -82,9 +81,10 @@ impl StarRailRepositoryScanController {
             window_info,
             config,
 
-            pool: 0.0,
+            pool: None,
+            selected: None,
 
-            initial_flag: [Rgb([0, 0, 0]); 50],
+            initial_flag: ColorFlag::new(Vec::new(), FLAG_MAX_DISTANCE),
 
             scrolled_rows: 0,
             avg_scroll_one_row: 0.0,
@@ -120,7 +120,7 @@ impl StarRailRepositoryScanController {
     pub fn get_generator(
         object: Rc<RefCell<StarRailRepositoryScanController>>,
         item_count: usize,
-    ) -> impl Coroutine<Yield = (), Return = Result<ReturnResult>> {
+    ) -> impl Coroutine<Yield = ScanEvent, Return = Result<ReturnResult>> {
         let generator = #[coroutine] move || {
             let mut scanned_row = 0;
             let mut scanned_count = 0;
@@ -170,6 +170,7 @@ impl StarRailRepositoryScanController {
 
                         object.borrow_mut().move_to(row, col);
                         object.borrow_mut().system_control.mouse_click().unwrap();
+                        object.borrow_mut().selected = Some((row, col));
 
                         #[cfg(target_os = "macos")]
                         utils::sleep(20);
@@ -177,7 +178,7 @@ impl StarRailRepositoryScanController {
                         let _ = object.borrow_mut().wait_until_switched();
 
                         // have to make sure at this point no mut ref exists
-                        yield;
+                        yield ScanEvent::Item { index: scanned_count, row, col };
 
                         scanned_count += 1;
                         object.borrow_mut().scanned_count = scanned_count;
@@ -188,10 +189,13 @@ impl StarRailRepositoryScanController {
                     // todo this is dangerous, use uniform integer type instead
                     if scanned_row >= object.borrow().config.max_row as usize {
                         info!("到达最大行数，准备退出……");
+                        yield ScanEvent::PageEnd;
                         break 'outer;
                     }
                 } // end '_row
 
+                yield ScanEvent::PageEnd;
+
                 let remain = item_count - scanned_count;
                 let remain_row = (remain + object.borrow().col - 1) / object.borrow().col;
                 let scroll_row = remain_row.min(object.borrow().row);
@@ -216,9 +220,25 @@ impl StarRailRepositoryScanController {
         generator
     }
 
+    /// Select the item at `row` and `col` of the current page again, and wait `extra_wait` ms longer than usual
+    pub fn reselect(&mut self, row: usize, col: usize, extra_wait: u32) -> Result<()> {
+        self.move_to(row, col);
+        self.system_control.mouse_click()?;
+
+        // the panel does not change if the item is the one selected already
+        if self.selected != Some((row, col)) {
+            self.selected = Some((row, col));
+            if let Err(e) = self.wait_until_switched() {
+                warn!("重新选择第 {} 行第 {} 列的物品后等待切换失败: {}", row + 1, col + 1, e);
+            }
+        }
+        utils::sleep(extra_wait);
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn sample_initial_color(&mut self) -> Result<()> {
-        self.initial_flag = self.capture_flag()?;
+        self.initial_flag = ColorFlag::new(self.capture_flag()?.to_vec(), FLAG_MAX_DISTANCE);
         Ok(())
     }
 
@@ -244,11 +264,8 @@ impl StarRailRepositoryScanController {
         let flag = self.capture_flag()?;
         // println!("{:?}", &flag[..20]);
         // let mut same_count = 0;
-        for y in 0..self.window_info.flag_rect.height as usize {
-            if color_distance(&self.initial_flag[y], &flag[y]) < 10 {
-                // same_count += 1;
-                return Ok(())
-            }
+        if self.initial_flag.matches(&flag) {
+            return Ok(())
         }
         // let ratio = same_count as f64 / self.window_info.flag_rect.height;
         // println!("{:?}", ratio);
@@ -317,6 +334,7 @@ impl StarRailRepositoryScanController {
     }
 
     pub fn scroll_rows(&mut self, count: i32) -> ScrollResult {
+        self.selected = None;
         if cfg!(not(target_os = "macos")) && self.scrolled_rows >= 5 {
             let length = self.estimate_scroll_length(count);
 
@@ -363,10 +381,10 @@ impl StarRailRepositoryScanController {
                 self.game_info.window.origin()
             )?;
 
-            let pool = calc_pool(im.as_raw()) as f64;
+            let pool = ColorHistogram::from_image(&im, POOL_BINS_PER_CHANNEL);
 
-            if (pool - self.pool).abs() > 0.000001 {
-                self.pool = pool;
+            if self.pool.as_ref() != Some(&pool) {
+                self.pool = Some(pool);
                 diff_flag = true;
                 consecutive_time = 0;
             } else if diff_flag {
@@ -1,9 +1,14 @@
 use anyhow::anyhow;
-use clap::{ArgMatches, Args, command};
+use clap::{ArgMatches, Args, FromArgMatches, command};
 use log::info;
 use yas::export::ExportAssets;
+use yas::auto_layout::{LandmarkCrop, LandmarkDefinition};
+use yas::correction::CorrectionConfig;
 use yas::game_info::{GameInfo, GameInfoBuilder};
-use yas::window_info::{load_window_info_repo, WindowInfoRepository};
+use yas::rating::{RatingConfig, RatingReport};
+use yas::validation::ValidationConfig;
+use yas::window_info::{load_window_info_repo, WindowInfoConfig, WindowInfoRepository};
+use crate::echo::{WWEcho, WWEchoCorrector, WWEchoValidator};
 use crate::scanner::{WWEchoScanner, WWEchoScannerConfig};
 use crate::scanner_controller::WWRepositoryLayoutConfig;
 use anyhow::Result;
@@ -23,16 +28,70 @@ impl WWEchoScannerApplication {
         let mut cmd = command!();
         cmd = <WWEchoScannerConfig as Args>::augment_args_for_update(cmd);
         cmd = <WWRepositoryLayoutConfig as Args>::augment_args_for_update(cmd);
+        cmd = <WindowInfoConfig as Args>::augment_args_for_update(cmd);
+        cmd = <CorrectionConfig as Args>::augment_args_for_update(cmd);
+        cmd = <ValidationConfig as Args>::augment_args_for_update(cmd);
+        cmd = <RatingConfig as Args>::augment_args_for_update(cmd);
         // cmd = <ExportRelicConfig as Args>::augment_args_for_update(cmd);
         cmd
     }
 
-    fn get_window_info_repository() -> WindowInfoRepository {
+    pub fn get_window_info_repository() -> WindowInfoRepository {
         load_window_info_repo!(
             "../../window_info/windows2560x1440.json"
         )
     }
 
+    /// The built-in window info, overridden by `--window-info` and `--auto-layout` if given
+    fn load_window_info_repository(arg_matches: &ArgMatches, game_info: &GameInfo) -> Result<WindowInfoRepository> {
+        let config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        let mut repo = config.load_repository(Self::get_window_info_repository())?;
+        config.apply_auto_layout(&mut repo, game_info)?;
+        Ok(repo)
+    }
+
+    /// Landmarks for `--auto-layout`, the grid and the panel move independently when the aspect ratio changes
+    pub fn get_landmark_definitions() -> Vec<LandmarkDefinition> {
+        vec![
+            LandmarkDefinition {
+                name: "count_label",
+                crop: LandmarkCrop::Rect("ww_echo_item_count_rect"),
+                keys: &["ww_echo_item_count_rect"],
+            },
+            LandmarkDefinition {
+                name: "grid_first_cell",
+                crop: LandmarkCrop::Around("ww_repository_scan_margin_pos", 60.0),
+                keys: &["ww_repository_scan_margin_pos", "ww_repository_flag_pos"],
+            },
+            LandmarkDefinition {
+                name: "panel",
+                crop: LandmarkCrop::Rect("ww_repository_panel_rect"),
+                keys: &[
+                    "ww_repository_panel_rect",
+                    "ww_repository_pool_rect",
+                    "ww_echo_title_rect",
+                    "ww_echo_level_rect",
+                    "ww_echo_star_pos",
+                    "ww_echo_main_stat1_name_rect",
+                    "ww_echo_main_stat1_value_rect",
+                    "ww_echo_main_stat2_name_rect",
+                    "ww_echo_main_stat2_value_rect",
+                    "ww_echo_sub_stat0_name_rect",
+                    "ww_echo_sub_stat0_value_rect",
+                    "ww_echo_sub_stat1_name_rect",
+                    "ww_echo_sub_stat1_value_rect",
+                    "ww_echo_sub_stat2_name_rect",
+                    "ww_echo_sub_stat2_value_rect",
+                    "ww_echo_sub_stat3_name_rect",
+                    "ww_echo_sub_stat3_value_rect",
+                    "ww_echo_sub_stat4_name_rect",
+                    "ww_echo_sub_stat4_value_rect",
+                    "ww_echo_equip_rect",
+                ],
+            },
+        ]
+    }
+
     fn get_game_info() -> anyhow::Result<GameInfo> {
         let game_info = GameInfoBuilder::new()
             .add_local_window_name("鸣潮")
@@ -48,13 +107,23 @@ impl WWEchoScannerApplication {
         println!("START");
         // Self::init();
         let arg_matches = &self.arg_matches;
-        let window_info_repository = Self::get_window_info_repository();
         let game_info = Self::get_game_info()?;
+        let window_info_repository = Self::load_window_info_repository(arg_matches, &game_info)?;
 
         info!("window: {:?}", game_info.window);
         info!("ui: {:?}", game_info.ui);
         info!("cloud: {}", game_info.is_cloud);
         info!("resolution family: {:?}", game_info.resolution_family);
+        window_info_repository.log_resolution_summary(
+            game_info.window.to_rect_usize().size(),
+            game_info.ui,
+            game_info.platform,
+        );
+
+        let window_info_config = WindowInfoConfig::from_arg_matches(arg_matches)?;
+        if window_info_config.dump_if_required(&window_info_repository, &game_info)? {
+            return Ok(());
+        }
 
         #[cfg(target_os = "windows")]
         {
@@ -70,7 +139,34 @@ impl WWEchoScannerApplication {
             game_info.clone()
         )?;
 
-        let results = scanner.scan()?;
+        let mut results = scanner.scan()?;
+
+        let correction_config = CorrectionConfig::from_arg_matches(arg_matches)?;
+        let mut corrector = WWEchoCorrector::new(correction_config.max_distance);
+        for (index, item) in results.iter_mut().enumerate() {
+            corrector.correct(index, item);
+        }
+        // there is no export of echoes yet, so the corrections are only logged
+        corrector.report().log();
+
+        let validation_config = ValidationConfig::from_arg_matches(arg_matches)?;
+        if !validation_config.no_validation {
+            let mut validator = WWEchoValidator::new(!validation_config.no_autocorrect);
+            for (index, item) in results.iter_mut().enumerate() {
+                validator.validate(index, item);
+            }
+            validator.report().log();
+        }
+
+        // like the corrections, the ratings are only logged
+        let rating_config = RatingConfig::from_arg_matches(arg_matches)?;
+        let profiles = rating_config.load_profiles::<WWEcho>()?;
+        if !profiles.is_empty() {
+            let echoes = results.iter()
+                .filter_map(WWEcho::from_scan_result)
+                .collect::<Vec<_>>();
+            RatingReport::new(&profiles, &echoes, rating_config.top).log();
+        }
 
         for item in results.iter() {
             println!("{:?}", item);
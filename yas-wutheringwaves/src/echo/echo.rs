@@ -1,4 +1,5 @@
 use crate::echo::{WWEchoName, WWStat};
+use crate::scanner::WWEchoScanResult;
 
 pub struct WWEcho {
     pub name: WWEchoName,
@@ -9,3 +10,28 @@ pub struct WWEcho {
     pub star: usize,
     pub lock: bool,
 }
+
+impl WWEcho {
+    /// None if the name or a main stat is not recognized, sub stats which are not recognized are left out
+    pub fn from_scan_result(result: &WWEchoScanResult) -> Option<WWEcho> {
+        let name = WWEchoName::from_chs(&result.name)?;
+        let main_stat1 = WWStat::from_chs_raw(&result.main_stat1_name, &result.main_stat1_value)?;
+        let main_stat2 = WWStat::from_chs_raw(&result.main_stat2_name, &result.main_stat2_value)?;
+        let sub_stats = result.sub_stat_names.iter()
+            .zip(result.sub_stat_values.iter())
+            .filter(|(name, _)| !name.is_empty())
+            .filter_map(|(name, value)| WWStat::from_chs_raw(name, value))
+            .collect();
+
+        Some(WWEcho {
+            name,
+            main_stat1,
+            main_stat2,
+            sub_stats,
+            level: result.level,
+            star: result.star,
+            // the lock is not scanned yet
+            lock: false,
+        })
+    }
+}
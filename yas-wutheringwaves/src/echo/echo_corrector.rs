@@ -0,0 +1,38 @@
+use yas::correction::{CorrectionReport, Dictionary, TextCorrector};
+
+use crate::echo::{ECHO_NAMES_CHS, STAT_NAMES_CHS};
+use crate::scanner::WWEchoScanResult;
+
+/// Snaps the names in echo scan results to the known ones
+pub struct WWEchoCorrector {
+    corrector: TextCorrector,
+    echo_names: Dictionary,
+    stat_names: Dictionary,
+}
+
+impl WWEchoCorrector {
+    pub fn new(max_distance: usize) -> WWEchoCorrector {
+        WWEchoCorrector {
+            corrector: TextCorrector::new(max_distance),
+            echo_names: Dictionary::new(ECHO_NAMES_CHS),
+            stat_names: Dictionary::new(STAT_NAMES_CHS),
+        }
+    }
+
+    /// `index` is the position of the result in the scan results
+    pub fn correct(&mut self, index: usize, result: &mut WWEchoScanResult) {
+        let corrector = &mut self.corrector;
+
+        result.name = corrector.correct(index, "title", &result.name, &self.echo_names);
+        result.main_stat1_name = corrector.correct(index, "main_stat1_name", &result.main_stat1_name, &self.stat_names);
+        result.main_stat2_name = corrector.correct(index, "main_stat2_name", &result.main_stat2_name, &self.stat_names);
+        for (i, name) in result.sub_stat_names.iter_mut().enumerate() {
+            let field = format!("sub_stat_name_{}", i + 1);
+            *name = corrector.correct(index, &field, name, &self.stat_names);
+        }
+    }
+
+    pub fn report(&self) -> &CorrectionReport {
+        &self.corrector.report
+    }
+}
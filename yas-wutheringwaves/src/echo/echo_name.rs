@@ -1,4 +1,14 @@
-// use yas_derive_wuthering_waves::yas_wuthering_waves_echoes;
+// the older format of echoes.json, `[{"name": "...", "name_chs": "..."}]`, is accepted as well
+yas_derive::yas_game_data! {
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, strum_macros::Display)]
+    pub enum WWEchoName = "data/echoes.json";
+}
 
-yas_derive_wuthering_waves::yas_wuthering_waves_echoes!("yas-wutheringwaves/data/echoes.json");
+impl WWEchoName {
+    pub fn from_chs(chs: &str) -> Option<Self> {
+        Self::from_name("zh-cn", chs)
+    }
+}
 
+/// Names of all echoes that `WWEchoName::from_chs` accepts
+pub const ECHO_NAMES_CHS: &[&str] = WWEchoName::NAMES_ZH_CN;
@@ -0,0 +1,35 @@
+use yas::rating::Rateable;
+
+use crate::echo::{WWEcho, WWStatName};
+
+/// The keys in weight profiles are the stat names, e.g. `CriticalRate`, `ATKPercentage`
+/// the rolls are counted in the highest rolls of 5 star echoes
+impl Rateable for WWEcho {
+    fn rated_stats(&self) -> Vec<(String, f64)> {
+        self.sub_stats.iter()
+            .filter_map(|stat| {
+                let highest = stat.name.sub_stat_values(5)?.max()?;
+                Some((stat.name.to_string(), stat.value / highest))
+            })
+            .collect()
+    }
+
+    fn stat_keys() -> Vec<String> {
+        WWStatName::ALL.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn crit_value(&self) -> f64 {
+        let value = self.sub_stats.iter()
+            .map(|stat| match stat.name {
+                WWStatName::CriticalRate => stat.value * 2.0,
+                WWStatName::CriticalDamage => stat.value,
+                _ => 0.0,
+            })
+            .sum::<f64>();
+        value * 100.0
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}星 +{}", self.name, self.star, self.level)
+    }
+}
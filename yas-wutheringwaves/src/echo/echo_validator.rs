@@ -0,0 +1,64 @@
+use yas::validation::{StatValidator, StatValues, ValidationReport};
+
+use crate::echo::WWStat;
+use crate::scanner::WWEchoScanResult;
+
+/// Checks the stat values in echo scan results against the ranges of the main stats and the sub stat rolls
+pub struct WWEchoValidator {
+    validator: StatValidator,
+}
+
+impl WWEchoValidator {
+    pub fn new(autocorrect: bool) -> WWEchoValidator {
+        WWEchoValidator {
+            validator: StatValidator::new(autocorrect),
+        }
+    }
+
+    /// Validate the stat `value` text in place, a stat which cannot be parsed is left to the conversion
+    fn validate_stat(
+        &mut self,
+        index: usize,
+        field: &str,
+        name: &str,
+        value: &mut String,
+        attainable: impl Fn(&WWStat) -> Option<StatValues>,
+    ) {
+        let stat = match WWStat::from_chs_raw(name, value) {
+            Some(v) => v,
+            None => return,
+        };
+        let values = match attainable(&stat) {
+            Some(v) => v,
+            None => return,
+        };
+
+        let validated = self.validator.validate(
+            index,
+            field,
+            &stat.name.to_string(),
+            stat.value,
+            &values,
+            stat.name.display_unit(),
+        );
+        if validated != stat.value {
+            *value = stat.name.format_value(validated);
+        }
+    }
+
+    /// `index` is the position of the result in the scan results
+    pub fn validate(&mut self, index: usize, result: &mut WWEchoScanResult) {
+        let star = result.star;
+
+        self.validate_stat(index, "main_stat1_value", &result.main_stat1_name, &mut result.main_stat1_value, |s| s.name.main_stat_values(star));
+        self.validate_stat(index, "main_stat2_value", &result.main_stat2_name, &mut result.main_stat2_value, |s| s.name.main_stat_values(star));
+        for (i, (name, value)) in result.sub_stat_names.iter().zip(result.sub_stat_values.iter_mut()).enumerate() {
+            let field = format!("sub_stat_value_{}", i + 1);
+            self.validate_stat(index, &field, name, value, |s| s.name.sub_stat_values(star));
+        }
+    }
+
+    pub fn report(&self) -> &ValidationReport {
+        &self.validator.report
+    }
+}
@@ -1,7 +1,13 @@
-pub use echo_name::WWEchoName;
-pub use stats::{WWStat, WWStatName};
+pub use echo_name::{WWEchoName, ECHO_NAMES_CHS};
+pub use stats::{WWStat, WWStatName, STAT_NAMES_CHS};
 pub use echo::WWEcho;
+pub use echo_corrector::WWEchoCorrector;
+pub use echo_validator::WWEchoValidator;
 
 mod echo;
+mod echo_corrector;
+mod echo_validator;
+mod echo_rating;
 mod echo_name;
+mod stat_table;
 mod stats;
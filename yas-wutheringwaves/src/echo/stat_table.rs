@@ -0,0 +1,75 @@
+use yas::validation::StatValues;
+
+use crate::echo::WWStatName;
+
+impl WWStatName {
+    fn is_flat(&self) -> bool {
+        matches!(self, WWStatName::ATK | WWStatName::HP | WWStatName::DEF)
+    }
+
+    /// The difference between two adjacent displayed values, in the unit of `WWStat::value`
+    pub fn display_unit(&self) -> f64 {
+        if self.is_flat() { 1.0 } else { 0.001 }
+    }
+
+    fn value_of_displayed(&self, displayed: f64) -> f64 {
+        if self.is_flat() { displayed } else { displayed / 100.0 }
+    }
+
+    /// The range of the main stats of 5 star echoes of any cost, from level 0 to 25
+    /// a main stat at level 0 is 20% of that at level 25
+    #[rustfmt::skip]
+    pub fn main_stat_values(&self, star: usize) -> Option<StatValues> {
+        use WWStatName::*;
+        if star != 5 {
+            return None;
+        }
+
+        let (min, max) = match self {
+            CriticalRate => (22.0, 22.0),
+            CriticalDamage => (44.0, 44.0),
+            ATKPercentage => (18.0, 33.0),
+            HPPercentage => (22.8, 33.0),
+            DEFPercentage => (18.0, 41.8),
+            HealingBonus => (26.4, 26.4),
+            EnergyRegeneration => (32.0, 32.0),
+            GlacioBonus | AeroBonus | FusionBonus | ElectroBonus | HavocBonus | SpectroBonus => (30.0, 30.0),
+            ATK => (100.0, 150.0),
+            HP => (2280.0, 2280.0),
+            _ => return None,
+        };
+        Some(StatValues::Range(self.value_of_displayed(min * 0.2), self.value_of_displayed(max)))
+    }
+
+    /// The values a sub stat of a 5 star echo can have, it is rolled only once
+    #[rustfmt::skip]
+    pub fn sub_stat_values(&self, star: usize) -> Option<StatValues> {
+        use WWStatName::*;
+        if star != 5 {
+            return None;
+        }
+
+        let values: &[f64] = match self {
+            CriticalRate => &[6.3, 6.9, 7.5, 8.1, 8.7, 9.3, 9.9, 10.5],
+            CriticalDamage => &[12.6, 13.8, 15.0, 16.2, 17.4, 18.6, 19.8, 21.0],
+            ATKPercentage | HPPercentage => &[6.4, 7.1, 7.9, 8.6, 9.4, 10.1, 10.9, 11.6],
+            BasicAttackBonus | HeavyAttackBonus | ResonanceSkillBonus | ResonanceLiberationBonus => &[6.4, 7.1, 7.9, 8.6, 9.4, 10.1, 10.9, 11.6],
+            DEFPercentage => &[8.1, 9.0, 10.0, 10.9, 11.8, 12.8, 13.8, 14.7],
+            EnergyRegeneration => &[6.8, 7.6, 8.4, 9.2, 10.0, 10.8, 11.6, 12.4],
+            ATK => &[30.0, 40.0, 50.0, 60.0],
+            HP => &[320.0, 360.0, 390.0, 430.0, 470.0, 510.0, 540.0, 580.0],
+            DEF => &[40.0, 50.0, 60.0, 70.0],
+            _ => return None,
+        };
+        Some(StatValues::Discrete(values.iter().map(|&v| self.value_of_displayed(v)).collect()))
+    }
+
+    /// The value as it is displayed in the game, e.g. "10.5%"
+    pub fn format_value(&self, value: f64) -> String {
+        if self.is_flat() {
+            format!("{}", value.round())
+        } else {
+            format!("{:.1}%", value * 100.0)
+        }
+    }
+}
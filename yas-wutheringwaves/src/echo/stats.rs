@@ -1,55 +1,36 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq, strum_macros::Display)]
-pub enum WWStatName {
-    CriticalDamage,
-    CriticalRate,
-    GlacioBonus,
-    AeroBonus,
-    FusionBonus,
-    ElectroBonus,
-    HavocBonus,
-    SpectroBonus,
-    EnergyRegeneration,
-    ATK,
-    ATKPercentage,
-    HP,
-    HPPercentage,
-    DEF,
-    DEFPercentage,
-    HealingBonus,
-    BasicAttackBonus,
-    HeavyAttackBonus,
-    ResonanceSkillBonus,
-    ResonanceLiberationBonus,
+yas_derive::yas_game_data! {
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, strum_macros::Display)]
+    pub enum WWStatName = "data/stats.json";
 }
 
 impl WWStatName {
     pub fn from_chs(chs: &str, is_percentage: bool) -> Option<Self> {
-        let ret = match chs {
-            "暴击伤害" => Self::CriticalDamage,
-            "暴击率" => Self::CriticalRate,
-            "冷凝伤害加成" => Self::GlacioBonus,
-            "气动伤害加成" => Self::AeroBonus,
-            "热熔伤害加成" => Self::FusionBonus,
-            "导电伤害加成" => Self::ElectroBonus,
-            "湮灭伤害加成" => Self::HavocBonus,
-            "衍射伤害加成" => Self::SpectroBonus,
-            "共鸣效率" => Self::EnergyRegeneration,
-            "攻击" => if is_percentage { Self::ATKPercentage } else { Self::ATK },
-            "防御" => if is_percentage { Self::DEFPercentage } else { Self::DEF },
-            "生命" => if is_percentage { Self::HPPercentage } else { Self::HP },
-            "治疗效果加成" => Self::HealingBonus,
-            "普攻伤害加成" => Self::BasicAttackBonus,
-            "重击伤害加成" => Self::HeavyAttackBonus,
-            "共鸣技能伤害加成" => Self::ResonanceSkillBonus,
-            "共鸣解放伤害加成" => Self::ResonanceLiberationBonus,
-            _ => return None,
-        };
-
-        Some(ret)
+        Self::from_stat_name("zh-cn", chs, is_percentage)
     }
 }
 
+/// Names of all stats that `WWStatName::from_chs` accepts
+pub const STAT_NAMES_CHS: &[&str] = WWStatName::NAMES_ZH_CN;
+
 pub struct WWStat {
     pub name: WWStatName,
     pub value: f64,
 }
+
+impl WWStat {
+    /// e.g. "暴击率" and "10.5%", percentages are parsed as fractions
+    pub fn from_chs_raw(name: &str, value: &str) -> Option<WWStat> {
+        let is_percentage = value.contains('%');
+        let name = WWStatName::from_chs(name, is_percentage)?;
+
+        let mut value = value.replace(['%', ','], "").parse::<f64>().ok()?;
+        if is_percentage {
+            value /= 100.0;
+        }
+
+        Some(WWStat {
+            name,
+            value,
+        })
+    }
+}
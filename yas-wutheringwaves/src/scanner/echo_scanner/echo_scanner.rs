@@ -2,8 +2,6 @@ use std::cell::RefCell;
 use std::ops::{CoroutineState, Coroutine};
 use std::pin::Pin;
 use std::rc::Rc;
-use std::sync::mpsc;
-use std::sync::mpsc::Sender;
 use std::time::SystemTime;
 
 use anyhow::Result;
@@ -14,7 +12,8 @@ use clap::FromArgMatches;
 
 use yas::capture::{Capturer, GenericCapturer, StreamingCapturer};
 use yas::game_info::GameInfo;
-use yas::ocr::{ImageToText, yas_ocr_model};
+use yas::ocr::{ImageToText, LowConfidenceReport, OCRConfig, OCRModelRegistry, yas_ocr_model};
+use yas::screen_state::{ScreenProbe, ScreenState, ScreenStateClassifier};
 use yas::window_info::{WindowInfoRepository, FromWindowInfoRepository};
 
 use crate::scanner::echo_scanner::echo_scanner_config::WWEchoScannerConfig;
@@ -24,6 +23,12 @@ use crate::scanner::echo_scanner::message_item::SendItem;
 use crate::scanner::echo_scanner::scan_result::WWEchoScanResult;
 use crate::scanner_controller::{ReturnResult, WWRepositoryLayoutConfig, WWRepositoryLayoutScanController};
 
+fn load_yas_model(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    Ok(Box::new(
+        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json", config.backend(), config.intra_threads)?
+    ))
+}
+
 pub struct WWEchoScanner {
     scanner_config: WWEchoScannerConfig,
     window_info: EchoScannerWindowInfo,
@@ -31,15 +36,17 @@ pub struct WWEchoScanner {
     image_to_text: Box<dyn ImageToText<RgbImage> + Send>,
     controller: Rc<RefCell<WWRepositoryLayoutScanController>>,
     capturer: Rc<dyn Capturer<RgbImage>>,
+    low_confidence_report: LowConfidenceReport,
 }
 
 impl WWEchoScanner {
-    fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-        let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-            yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-        );
-        // let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(yas::ocr::PPOCRChV4RecInfer::new()?);
-        Ok(model)
+    /// The models which can be chosen by `--ocr-model`, `yas` is the one trained for the game
+    pub fn get_model_registry() -> OCRModelRegistry {
+        OCRModelRegistry::new().with_model("yas", load_yas_model)
+    }
+
+    pub fn get_image_to_text(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+        Self::get_model_registry().load(&config.model, config)
     }
 
     fn get_capturer() -> Result<Rc<dyn Capturer<RgbImage>>> {
@@ -52,7 +59,9 @@ impl WWEchoScanner {
         controller_config: WWRepositoryLayoutConfig,
         game_info: GameInfo
     ) -> anyhow::Result<Self> {
+        let image_to_text = Self::get_image_to_text(&config.ocr)?;
         Ok(Self {
+            low_confidence_report: LowConfidenceReport::new(config.low_confidence),
             scanner_config: config,
             window_info: EchoScannerWindowInfo::from_window_info_repository(
                 game_info.window.to_rect_usize().size(),
@@ -66,7 +75,7 @@ impl WWEchoScanner {
                 game_info.clone()
             )?)),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             capturer: Self::get_capturer()?,
         })
     }
@@ -82,14 +91,17 @@ impl WWEchoScanner {
             game_info.platform,
             window_info_repo
         )?;
+        let scanner_config = WWEchoScannerConfig::from_arg_matches(arg_matches)?;
+        let image_to_text = Self::get_image_to_text(&scanner_config.ocr)?;
         Ok(Self {
-            scanner_config: WWEchoScannerConfig::from_arg_matches(arg_matches)?,
+            low_confidence_report: LowConfidenceReport::new(scanner_config.low_confidence),
+            scanner_config,
             window_info,
             controller: Rc::new(RefCell::new(
                 WWRepositoryLayoutScanController::from_arg_matches(window_info_repo, arg_matches, game_info.clone())?
             )),
             game_info,
-            image_to_text: Self::get_image_to_text()?,
+            image_to_text,
             capturer: Self::get_capturer()?,
         })
     }
@@ -103,6 +115,36 @@ impl WWEchoScanner {
     //     )
     // }
 
+    fn get_screen_state_classifier(&self) -> ScreenStateClassifier {
+        let mut classifier = ScreenStateClassifier::new();
+        classifier.add_loading_rule();
+        if let Some(rect) = self.window_info.dialog_button_rect {
+            classifier.add_rule(ScreenState::Dialog, vec![ScreenProbe::text(rect, &["确认", "取消"])]);
+        }
+        classifier.add_rule(
+            ScreenState::EchoInventory,
+            vec![ScreenProbe::text(self.window_info.item_count_rect, &["声骸"])]
+        );
+        if let Some(rect) = self.window_info.character_anchor_rect {
+            classifier.add_rule(ScreenState::Character, vec![ScreenProbe::text(rect, &["属性"])]);
+        }
+        classifier
+    }
+
+    /// Make sure the game is showing the echo inventory before any click happens
+    fn check_screen_state(&self) -> Result<()> {
+        if self.scanner_config.skip_screen_check {
+            return Ok(());
+        }
+
+        let frame = self.capturer.capture_rect(self.game_info.window)?;
+        self.get_screen_state_classifier().ensure(
+            &frame,
+            self.image_to_text.as_ref(),
+            ScreenState::EchoInventory
+        )
+    }
+
     /// Get Echo count
     fn get_item_count(&self) -> Result<usize> {
         let max_count = 2000;
@@ -127,20 +169,31 @@ impl WWEchoScanner {
         }
     }
 
+    /// Recognized fields of the last scan that the user should double check
+    pub fn low_confidence_report(&self) -> &LowConfidenceReport {
+        &self.low_confidence_report
+    }
+
     pub fn scan(&mut self) -> Result<Vec<WWEchoScanResult>> {
         info!("开始扫描，使用鼠标右键中断扫描");
 
+        self.check_screen_state()?;
+
         let now = SystemTime::now();
 
-        let (image_tx, image_rx) = mpsc::channel::<SendItem>();
         let count = self.get_item_count()?;
 
-        let worker = WWEchoScannerWorker::new(
-            self.window_info.clone(),
-            self.scanner_config.clone()
-        )?;
+        let worker_count = self.scanner_config.ocr.workers.max(1);
+        let mut workers = Vec::with_capacity(worker_count);
+        for _ in 0..worker_count {
+            workers.push(WWEchoScannerWorker::new(
+                self.window_info.clone(),
+                self.scanner_config.clone()
+            )?);
+        }
 
-        let worker_join_handle = worker.run(image_rx);
+        let (image_tx, worker_join_handle) = WWEchoScannerWorker::run(workers, self.scanner_config.ocr.queue_size);
+        info!("创建 {} 个识别线程", worker_count);
         let panel_rect = self.window_info.panel_rect.to_rect_i32().translate(self.game_info.window.origin());
         let streaming_capturer = StreamingCapturer::new(panel_rect);
         let (capturer_join_handle, cancel_image_capturer) = streaming_capturer.start_transform(image_tx, |x| SendItem { panel_image: x });
@@ -165,7 +218,9 @@ impl WWEchoScanner {
         cancel_image_capturer();
 
         capturer_join_handle.join();
-        let result = worker_join_handle.join().unwrap();
+        let (result, low_confidence_report) = worker_join_handle.join().unwrap();
+        low_confidence_report.log();
+        self.low_confidence_report = low_confidence_report;
 
         Ok(result)
 
@@ -1,4 +1,5 @@
 use clap::Args;
+use yas::ocr::OCRConfig;
 
 #[derive(clap::Args, Clone)]
 pub struct WWEchoScannerConfig {
@@ -19,4 +20,15 @@ pub struct WWEchoScannerConfig {
 
     #[arg(id = "number", long, help = "指定声骸数量", value_name = "NUMBER")]
     pub number: Option<usize>,
+
+    /// Do not check whether the game is showing the echo inventory before scanning
+    #[arg(id = "skip-screen-check", long = "skip-screen-check", help = "跳过界面检查")]
+    pub skip_screen_check: bool,
+
+    /// OCR results less confident than this are listed for double checking
+    #[arg(id = "low-confidence", long = "low-confidence", help = "置信度低于该值的识别结果将被列出以供核对", value_name = "CONFIDENCE", default_value_t = 0.9)]
+    pub low_confidence: f32,
+
+    #[command(flatten)]
+    pub ocr: OCRConfig,
 }
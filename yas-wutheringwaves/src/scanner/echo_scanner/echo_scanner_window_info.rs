@@ -59,4 +59,12 @@ pub struct EchoScannerWindowInfo {
 
     #[window_info(rename = "ww_repository_item_col")]
     pub col: i32,
+
+    // a text region only shown in the character screen, optional
+    #[window_info(rename = "ww_screen_character_anchor_rect")]
+    pub character_anchor_rect: Option<Rect<f64>>,
+
+    // the button region of a popup dialog, optional
+    #[window_info(rename = "ww_screen_dialog_button_rect")]
+    pub dialog_button_rect: Option<Rect<f64>>,
 }
@@ -1,21 +1,25 @@
-use std::collections::HashSet;
-use std::sync::mpsc::Receiver;
+use std::collections::{HashMap, HashSet};
 use std::thread::JoinHandle;
 use image::{GenericImageView, RgbImage};
-use yas::ocr::{ImageToText, yas_ocr_model};
+use yas::ocr::{LowConfidenceReport, OCRModelSet, OCRResult, RegionRequirement, ScanConfidence};
+use crate::scanner::echo_scanner::WWEchoScanner;
 use crate::scanner::echo_scanner::echo_scanner_config::WWEchoScannerConfig;
 use crate::scanner::echo_scanner::echo_scanner_window_info::EchoScannerWindowInfo;
 use anyhow::Result;
 use log::{error, info, warn};
 use rayon::iter::ParallelBridge;
 use yas::positioning::{Pos, Rect};
+use yas::worker_pool::{ordered_worker_pool, PoolSender};
 use crate::scanner::echo_scanner::message_item::SendItem;
+use crate::scanner::echo_scanner::region_requirements::get_region_requirements;
 use crate::scanner::echo_scanner::scan_result::WWEchoScanResult;
 
 pub struct WWEchoScannerWorker {
-    model: Box<dyn ImageToText<RgbImage> + Send>,
+    models: OCRModelSet,
     window_info: EchoScannerWindowInfo,
     config: WWEchoScannerConfig,
+    /// the text of the OCR regions which can be parsed, by field name
+    region_requirements: HashMap<&'static str, RegionRequirement>,
 }
 
 fn parse_level(s: &str) -> Result<usize> {
@@ -30,39 +34,65 @@ fn parse_level(s: &str) -> Result<usize> {
     return Ok(level);
 }
 
-fn get_image_to_text() -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
-    let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(
-        yas_ocr_model!("./models/model_training.onnx", "./models/index_2_word.json")?
-    );
-    // let model: Box<dyn ImageToText<RgbImage> + Send> = Box::new(PPOCRChV4RecInfer::new()?);
-    Ok(model)
-}
-
 impl WWEchoScannerWorker {
     pub fn new(
         window_info: EchoScannerWindowInfo,
         config: WWEchoScannerConfig,
     ) -> Result<Self> {
         Ok(Self {
-            model: get_image_to_text()?,
+            models: WWEchoScanner::get_model_registry().load_set(&config.ocr)?,
             window_info,
             config,
+            region_requirements: get_region_requirements(),
         })
     }
 
-    fn model_inference(&self, rect: Rect<f64>, captured_img: &RgbImage) -> Result<String> {
-        let relative_rect = rect.translate(Pos {
+    /// the rects are regions of the echo panel, which are recognized in batches
+    /// the texts are in the same order as `fields`
+    fn model_inference(
+        &self,
+        fields: &[(&str, Rect<f64>)],
+        captured_img: &RgbImage,
+        confidence: &mut ScanConfidence
+    ) -> Result<Vec<String>> {
+        let relative_rects = fields.iter().map(|(_, rect)| rect.translate(Pos {
             x: -self.window_info.panel_rect.left,
             y: -self.window_info.panel_rect.top,
-        });
-
-        let raw_img = captured_img.view(
-            relative_rect.left as u32, relative_rect.top as u32, relative_rect.width as u32, relative_rect.height as u32
-        ).to_image();
+        })).collect::<Vec<_>>();
+
+        let raw_images = relative_rects.iter().map(|rect| captured_img.view(
+            rect.left as u32, rect.top as u32, rect.width as u32, rect.height as u32
+        ).to_image()).collect::<Vec<_>>();
+
+        let regions = fields.iter()
+            .zip(raw_images.iter())
+            .map(|(&(field, _), im)| (field, im, self.region_requirements.get(field)))
+            .collect::<Vec<_>>();
+
+        // the requirements only pick the regions for the fallback model, the decoding is not constrained
+        let batch_size = self.config.ocr.batch_size;
+        let mut results = self.models.recognize(&regions, batch_size, false)?;
+
+        // a frame which cannot be parsed is recognized again with the fallback model first,
+        // and dropped if it still cannot be, the following frames show the same item
+        let unparseable = |results: &[OCRResult]| regions.iter()
+            .zip(results.iter())
+            .find(|((_, _, requirement), result)| requirement.map_or(false, |r| !r.is_parseable(&result.text)))
+            .map(|((field, _, _), result)| (*field, result.text.clone()));
+        if unparseable(&results).is_some() {
+            results = self.models.recognize_fallback_first(&regions, batch_size, false)?;
+            if let Some((field, text)) = unparseable(&results) {
+                return Err(anyhow::anyhow!("{} 的识别结果 \"{}\" 无法解析", field, text));
+            }
+        }
 
-        let inference_result = self.model.image_to_text(&raw_img, false);
+        let mut texts = Vec::with_capacity(results.len());
+        for ((&(field, _), rect), result) in fields.iter().zip(relative_rects).zip(results) {
+            confidence.push(field, rect, &result);
+            texts.push(result.text);
+        }
 
-        inference_result
+        Ok(texts)
     }
 
     fn determine_star(&self, im: &RgbImage) -> Result<usize> {
@@ -77,27 +107,46 @@ impl WWEchoScannerWorker {
         Ok(5)
     }
 
-    fn parse_item(&self, item: SendItem) -> Result<WWEchoScanResult> {
+    fn parse_item(&self, item: &SendItem) -> Result<WWEchoScanResult> {
         let image = &item.panel_image;
-
-        let str_title = self.model_inference(self.window_info.title_rect, &image)?;
-        let str_main_stat1_name = self.model_inference(self.window_info.main_stat1_name_rect, &image)?;
-        let str_main_stat1_value = self.model_inference(self.window_info.main_stat1_value_rect, &image)?;
-        let str_main_stat2_name = self.model_inference(self.window_info.main_stat2_name_rect, &image)?;
-        let str_main_stat2_value = self.model_inference(self.window_info.main_stat2_value_rect, &image)?;
-
-        let str_sub_stat0_name = self.model_inference(self.window_info.sub_stat_name_1, &image)?;
-        let str_sub_stat1_name = self.model_inference(self.window_info.sub_stat_name_2, &image)?;
-        let str_sub_stat2_name = self.model_inference(self.window_info.sub_stat_name_3, &image)?;
-        let str_sub_stat3_name = self.model_inference(self.window_info.sub_stat_name_4, &image)?;
-        let str_sub_stat4_name = self.model_inference(self.window_info.sub_stat_name_5, &image)?;
-        let str_sub_stat0_value = self.model_inference(self.window_info.sub_stat_value_1, &image)?;
-        let str_sub_stat1_value = self.model_inference(self.window_info.sub_stat_value_2, &image)?;
-        let str_sub_stat2_value = self.model_inference(self.window_info.sub_stat_value_3, &image)?;
-        let str_sub_stat3_value = self.model_inference(self.window_info.sub_stat_value_4, &image)?;
-        let str_sub_stat4_value = self.model_inference(self.window_info.sub_stat_value_5, &image)?;
-
-        let str_level = self.model_inference(self.window_info.level_rect, &image)?;
+        let mut confidence = ScanConfidence::default();
+
+        let fields = [
+            ("title", self.window_info.title_rect),
+            ("main_stat1_name", self.window_info.main_stat1_name_rect),
+            ("main_stat1_value", self.window_info.main_stat1_value_rect),
+            ("main_stat2_name", self.window_info.main_stat2_name_rect),
+            ("main_stat2_value", self.window_info.main_stat2_value_rect),
+            ("sub_stat_name_1", self.window_info.sub_stat_name_1),
+            ("sub_stat_name_2", self.window_info.sub_stat_name_2),
+            ("sub_stat_name_3", self.window_info.sub_stat_name_3),
+            ("sub_stat_name_4", self.window_info.sub_stat_name_4),
+            ("sub_stat_name_5", self.window_info.sub_stat_name_5),
+            ("sub_stat_value_1", self.window_info.sub_stat_value_1),
+            ("sub_stat_value_2", self.window_info.sub_stat_value_2),
+            ("sub_stat_value_3", self.window_info.sub_stat_value_3),
+            ("sub_stat_value_4", self.window_info.sub_stat_value_4),
+            ("sub_stat_value_5", self.window_info.sub_stat_value_5),
+            ("level", self.window_info.level_rect),
+        ];
+        let [
+            str_title,
+            str_main_stat1_name,
+            str_main_stat1_value,
+            str_main_stat2_name,
+            str_main_stat2_value,
+            str_sub_stat0_name,
+            str_sub_stat1_name,
+            str_sub_stat2_name,
+            str_sub_stat3_name,
+            str_sub_stat4_name,
+            str_sub_stat0_value,
+            str_sub_stat1_value,
+            str_sub_stat2_value,
+            str_sub_stat3_value,
+            str_sub_stat4_value,
+            str_level,
+        ]: [String; 16] = self.model_inference(&fields, image, &mut confidence)?.try_into().unwrap();
         // let str_equip = self.model_inference(self.window_info., &image)?;
 
         let star = self.determine_star(&image)?;
@@ -126,21 +175,35 @@ impl WWEchoScannerWorker {
             // equip: item.equip + &str_equip,
             star,
             // lock: item.lock,
+            confidence,
         })
     }
 
-    pub fn run(self, rx: Receiver<SendItem>) -> JoinHandle<Vec<WWEchoScanResult>> {
-        std::thread::spawn(move || {
+    /// Recognize the items with a pool of workers, and collect the results in the order of the items
+    /// the results are returned once the sender is dropped
+    pub fn run(
+        workers: Vec<WWEchoScannerWorker>,
+        queue_size: usize,
+    ) -> (PoolSender<SendItem>, JoinHandle<(Vec<WWEchoScanResult>, LowConfidenceReport)>) {
+        let config = workers[0].config.clone();
+        let info = workers[0].window_info.clone();
+
+        let (tx, rx) = ordered_worker_pool(workers, queue_size, |worker: &mut WWEchoScannerWorker, item: SendItem| {
+            let result = worker.parse_item(&item);
+            (item, result)
+        });
+
+        let join_handle = std::thread::spawn(move || {
             let mut results = Vec::new();
+            let mut low_confidence = LowConfidenceReport::new(config.low_confidence);
             let mut hash = HashSet::new();
             let mut consecutive_dup_count = 0;
 
-            let is_verbose = self.config.verbose;
-            let min_level = self.config.min_level;
-            let info = self.window_info.clone();
+            let is_verbose = config.verbose;
+            let min_level = config.min_level;
 
-            for (_cnt, item) in rx.into_iter().enumerate() {
-                let result = match self.parse_item(item) {
+            for (cnt, (item, result)) in rx.enumerate() {
+                let result = match result {
                     Ok(v) => v,
                     Err(e) => {
                         // error!("识别错误: {}", e);
@@ -157,11 +220,12 @@ impl WWEchoScannerWorker {
                     // warn!("识别到重复物品: {:#?}", result);
                 } else {
                     consecutive_dup_count = 0;
+                    low_confidence.add(cnt, &result.confidence, &item.panel_image);
                     hash.insert(result.clone());
                     results.push(result);
                 }
 
-                // if consecutive_dup_count >= info.col && !self.config.ignore_dup {
+                // if consecutive_dup_count >= info.col && !config.ignore_dup {
                 //     error!("识别到连续多个重复物品，可能为翻页错误，或者为非背包顶部开始扫描");
                 //     break;
                 // }
@@ -169,7 +233,9 @@ impl WWEchoScannerWorker {
 
             info!("识别结束，非重复物品数量: {}", hash.len());
 
-            results
-        })
+            (results, low_confidence)
+        });
+
+        (tx, join_handle)
     }
 }
@@ -1,5 +1,7 @@
 pub use echo_scanner_config::WWEchoScannerConfig;
 pub use echo_scanner::WWEchoScanner;
+pub use echo_scanner_window_info::EchoScannerWindowInfo;
+pub use scan_result::WWEchoScanResult;
 
 mod message_item;
 mod echo_scanner_config;
@@ -7,3 +9,4 @@ mod echo_scanner_window_info;
 mod scan_result;
 mod echo_scanner_worker;
 mod echo_scanner;
+mod region_requirements;
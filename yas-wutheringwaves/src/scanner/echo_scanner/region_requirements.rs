@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use yas::ocr::{RegionRequirement, TextConstraint};
+
+/// e.g. `150` or `10.5%`
+fn stat_value() -> TextConstraint {
+    TextConstraint::Any(vec![
+        TextConstraint::Number { thousands_separator: true, max_decimals: 0 },
+        TextConstraint::Sequence(vec![
+            TextConstraint::Number { thousands_separator: false, max_decimals: 1 },
+            TextConstraint::Literal("%"),
+        ]),
+    ])
+}
+
+/// The text of the OCR regions which can be parsed, by field name
+/// echoes have up to five sub stats, which are unlocked by leveling up
+pub fn get_region_requirements() -> HashMap<&'static str, RegionRequirement> {
+    let level = TextConstraint::Number { thousands_separator: false, max_decimals: 0 };
+
+    let mut result = HashMap::from([
+        ("main_stat1_value", RegionRequirement::required(stat_value())),
+        ("main_stat2_value", RegionRequirement::required(stat_value())),
+        ("level", RegionRequirement::required(TextConstraint::Any(vec![
+            TextConstraint::Sequence(vec![TextConstraint::Literal("+"), level.clone()]),
+            level,
+        ]))),
+    ]);
+    for name in ["sub_stat_value_1", "sub_stat_value_2", "sub_stat_value_3", "sub_stat_value_4", "sub_stat_value_5"] {
+        result.insert(name, RegionRequirement::optional(stat_value()));
+    }
+
+    result
+}
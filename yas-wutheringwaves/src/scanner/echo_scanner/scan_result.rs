@@ -1,3 +1,5 @@
+use yas::ocr::ScanConfidence;
+
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub struct WWEchoScanResult {
     pub name: String,
@@ -10,4 +12,5 @@ pub struct WWEchoScanResult {
     // pub equip: String,
     pub level: usize,
     pub star: usize,
+    pub confidence: ScanConfidence,
 }
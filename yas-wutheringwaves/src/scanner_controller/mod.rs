@@ -1,3 +1,3 @@
-pub use repository::{WWRepositoryLayoutScanController, WWRepositoryLayoutConfig, ReturnResult};
+pub use repository::{WWRepositoryLayoutScanController, WWRepositoryLayoutConfig, WWRepositoryLayoutWindowinfo, ReturnResult};
 
 mod repository;
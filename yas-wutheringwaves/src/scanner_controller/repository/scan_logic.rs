@@ -15,17 +15,20 @@ use yas::positioning::Pos;
 use yas::profiler::Profiler;
 use yas::system_control::SystemControl;
 use yas::utils;
-use yas::utils::color_distance;
+use yas::vision::ColorFlag;
 use yas::window_info::{FromWindowInfoRepository, WindowInfoRepository};
 
 use crate::scanner_controller::repository::{WWRepositoryLayoutConfig, WWRepositoryLayoutWindowinfo};
 
+/// The squared color distance within which the flag pixel is unchanged
+const FLAG_MAX_DISTANCE: usize = 50;
+
 pub struct WWRepositoryLayoutScanController {
     /// A value computed from a region of the panel, to detect whether an item changes
     pool: u64,
 
     /// Stores initial gap colors for line gap detection
-    initial_flag: Rgb<u8>,
+    initial_flag: ColorFlag,
 
     /// How many rows were scrolled
     scrolled_rows: u32,
@@ -85,7 +88,7 @@ impl WWRepositoryLayoutScanController {
 
             pool: 0,
 
-            initial_flag: Rgb([0, 0, 0]),
+            initial_flag: ColorFlag::from_pixel(Rgb([0, 0, 0]), FLAG_MAX_DISTANCE),
 
             scrolled_rows: 0,
             avg_scroll_one_row: 0.0,
@@ -261,22 +264,15 @@ impl WWRepositoryLayoutScanController {
     }
 
     pub fn sample_initial_color(&mut self) -> Result<()> {
-        self.initial_flag = self.capture_flag()?;
-        println!("initial color: {:?}", self.initial_flag);
+        self.initial_flag = ColorFlag::from_pixel(self.capture_flag()?, FLAG_MAX_DISTANCE);
+        println!("initial color: {:?}", self.initial_flag.colors());
         Ok(())
     }
 
     pub fn check_flag(&self) -> Result<bool> {
         let flag = self.capture_flag()?;
         println!("{:?}", &flag);
-        // let mut same_count = 0;
-        let threshold = 50;
-
-        if color_distance(&self.initial_flag, &flag) < threshold {
-            return Ok(true);
-        }
-
-        Ok(false)
+        Ok(self.initial_flag.matches_pixel(&flag))
     }
 
     pub fn align_row(&mut self) -> Result<()> {
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+use log::info;
+
+use crate::auto_layout::{Landmark, LandmarkSet};
+use crate::positioning::{Pos, Rect, Scalable, Size};
+use crate::vision::{match_template_multi_scale, TemplateMatch};
+use crate::window_info::{Anchor, WindowInfoRepository, WindowInfoTemplatePerSize, WindowInfoType};
+
+/// Landmarks with a lower score are considered not found
+pub const DEFAULT_MIN_SCORE: f64 = 0.6;
+
+/// Move a value recorded relative to the landmark in the reference window to the matched landmark
+fn follow_landmark(value: WindowInfoType, landmark: &Landmark, m: &TemplateMatch) -> WindowInfoType {
+    let map_x = |x: f64| m.pos.x as f64 + (x - landmark.rect.left) * m.scale;
+    let map_y = |y: f64| m.pos.y as f64 + (y - landmark.rect.top) * m.scale;
+
+    match value {
+        WindowInfoType::Pos(pos) => WindowInfoType::Pos(Pos {
+            x: map_x(pos.x),
+            y: map_y(pos.y),
+        }),
+        WindowInfoType::Rect(rect) => WindowInfoType::Rect(Rect {
+            left: map_x(rect.left),
+            top: map_y(rect.top),
+            width: rect.width * m.scale,
+            height: rect.height * m.scale,
+        }),
+        _ => value.scale(m.scale),
+    }
+}
+
+/// Locate the landmarks in a frame, and synthesize the window info for the size of the frame
+pub fn detect_layout(
+    frame: &RgbImage,
+    landmarks: &LandmarkSet,
+    repo: &WindowInfoRepository,
+    min_score: f64
+) -> Result<WindowInfoTemplatePerSize> {
+    let window_size = Size::new(frame.width() as usize, frame.height() as usize);
+    let gray = image::DynamicImage::ImageRgb8(frame.clone()).to_luma8();
+
+    let nominal = Anchor::factor(landmarks.reference_size, window_size);
+    let scales = (-4..=4).map(|i| nominal * (1.0 + i as f64 * 0.05)).collect::<Vec<_>>();
+
+    let mut matches = Vec::new();
+    let mut not_found = Vec::new();
+    for landmark in landmarks.landmarks.iter() {
+        let template = landmark.template.as_ref()
+            .ok_or(anyhow!("地标 {} 没有模板图片", landmark.name))?;
+
+        match match_template_multi_scale(&gray, template, &scales) {
+            Some(m) if m.score >= min_score => {
+                info!("地标 {}: ({}, {})，缩放 {:.3}，匹配度 {:.3}", landmark.name, m.pos.x, m.pos.y, m.scale, m.score);
+                matches.push((landmark, m));
+            },
+            Some(m) => not_found.push(format!("{}（匹配度 {:.3}）", landmark.name, m.score)),
+            None => not_found.push(landmark.name.clone()),
+        }
+    }
+    if !not_found.is_empty() {
+        return Err(anyhow!("未找到地标: {}", not_found.join(", ")));
+    }
+
+    let mut scales = matches.iter().map(|(_, m)| m.scale).collect::<Vec<_>>();
+    scales.sort_by(|a, b| a.total_cmp(b));
+    let scale = scales.get(scales.len() / 2).copied().unwrap_or(nominal);
+
+    let mut data = HashMap::new();
+    let mut anchors = HashMap::new();
+    for name in repo.data.keys() {
+        let (value, _) = match repo.resolve_value(name, landmarks.reference_size, landmarks.ui, landmarks.platform) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let claimed = matches.iter().find(|(l, _)| l.keys.iter().any(|k| k == name));
+        let value = match claimed {
+            Some((landmark, m)) => follow_landmark(value, landmark, m),
+            None => value.scale(scale),
+        };
+        data.insert(name.clone(), value);
+        if let Some(anchor) = repo.anchors.get(name) {
+            anchors.insert(name.clone(), *anchor);
+        }
+    }
+
+    Ok(WindowInfoTemplatePerSize {
+        current_resolution: window_size,
+        platform: landmarks.platform,
+        ui: landmarks.ui,
+        data,
+        anchors,
+    })
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use image::{GrayImage, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::game_info::{Platform, UI};
+use crate::positioning::{Rect, Size};
+use crate::utils::crop_rect;
+use crate::window_info::{WindowInfoRepository, WindowInfoType};
+
+/// Where the template of a landmark is cropped
+#[derive(Debug, Clone, Copy)]
+pub enum LandmarkCrop {
+    /// the region of a rect key
+    Rect(&'static str),
+    /// a square around a pos key, with the half size in pixels of the reference window
+    Around(&'static str, f64),
+}
+
+/// A UI element which is easy to find, and the window info keys that move together with it
+/// keys not claimed by any landmark are treated as relative values, and only scaled
+#[derive(Debug, Clone, Copy)]
+pub struct LandmarkDefinition {
+    pub name: &'static str,
+    pub crop: LandmarkCrop,
+    pub keys: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Landmark {
+    pub name: String,
+    /// where the template is in the reference window
+    pub rect: Rect<f64>,
+    pub keys: Vec<String>,
+    #[serde(skip)]
+    pub template: Option<GrayImage>,
+}
+
+/// Landmark templates cropped from a screenshot of a known window size
+/// saved as a directory of `landmarks.json` and a png for each landmark
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandmarkSet {
+    pub reference_size: Size<usize>,
+    pub ui: UI,
+    pub platform: Platform,
+    pub landmarks: Vec<Landmark>,
+}
+
+impl LandmarkSet {
+    /// Crop the landmarks out of a screenshot, whose window info is known
+    pub fn export(
+        frame: &RgbImage,
+        definitions: &[LandmarkDefinition],
+        repo: &WindowInfoRepository,
+        ui: UI,
+        platform: Platform
+    ) -> Result<LandmarkSet> {
+        let reference_size = Size::new(frame.width() as usize, frame.height() as usize);
+        let mut landmarks = Vec::new();
+
+        for definition in definitions.iter() {
+            let rect = match definition.crop {
+                LandmarkCrop::Rect(key) => repo.get_auto_scale::<Rect<f64>>(key, reference_size, ui, platform),
+                LandmarkCrop::Around(key, half) => repo.get_auto_scale::<WindowInfoType>(key, reference_size, ui, platform)
+                    .and_then(|v| match v {
+                        WindowInfoType::Pos(pos) => Some(Rect {
+                            left: pos.x - half,
+                            top: pos.y - half,
+                            width: half * 2.0,
+                            height: half * 2.0,
+                        }),
+                        _ => None,
+                    }),
+            };
+            let rect = rect.ok_or(anyhow!("无法确定地标 {} 的位置", definition.name))?;
+            let template = crop_rect(frame, &rect)
+                .ok_or(anyhow!("地标 {} 超出截图范围", definition.name))?;
+
+            landmarks.push(Landmark {
+                name: String::from(definition.name),
+                rect,
+                keys: definition.keys.iter().map(|k| String::from(*k)).collect(),
+                template: Some(image::DynamicImage::ImageRgb8(template).to_luma8()),
+            });
+        }
+
+        Ok(LandmarkSet {
+            reference_size,
+            ui,
+            platform,
+            landmarks,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        for landmark in self.landmarks.iter() {
+            if let Some(template) = landmark.template.as_ref() {
+                template.save(dir.join(format!("{}.png", landmark.name)))?;
+            }
+        }
+        std::fs::write(dir.join("landmarks.json"), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<LandmarkSet> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(|e| anyhow!("无法读取地标模板目录 {}: {}", dir.display(), e))? {
+            let path = entry?.path();
+            let contents = std::fs::read(&path).map_err(|e| anyhow!("无法读取地标模板 {}: {}", path.display(), e))?;
+            files.push((path, contents));
+        }
+
+        Self::from_files(files.iter().map(|(path, contents)| (path.as_path(), contents.as_slice())))
+    }
+
+    /// `landmarks.json` and a png named after every landmark, among `files`
+    pub fn from_files<'a, I: IntoIterator<Item = (&'a Path, &'a [u8])>>(files: I) -> Result<LandmarkSet> {
+        let files = files.into_iter()
+            .filter_map(|(path, contents)| Some((path.file_name()?.to_string_lossy().to_string(), contents)))
+            .collect::<Vec<_>>();
+        let find = |name: &str| files.iter().find(|(n, _)| n == name).map(|(_, contents)| *contents);
+
+        let json = find("landmarks.json").ok_or(anyhow!("缺少 landmarks.json"))?;
+        let mut result: LandmarkSet = serde_json::from_slice(json)?;
+
+        for landmark in result.landmarks.iter_mut() {
+            let filename = format!("{}.png", landmark.name);
+            let png = find(&filename).ok_or(anyhow!("缺少地标模板 {}", filename))?;
+            let template = image::load_from_memory(png)
+                .map_err(|e| anyhow!("无法读取地标模板 {}: {}", filename, e))?
+                .to_luma8();
+            landmark.template = Some(template);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LANDMARKS_JSON: &str = r#"{
+        "reference_size": { "width": 160, "height": 96 },
+        "ui": "Desktop",
+        "platform": "Windows",
+        "landmarks": [
+            { "name": "ring", "rect": { "left": 56.0, "top": 26.0, "width": 28.0, "height": 28.0 }, "keys": ["ring_rect"] }
+        ]
+    }"#;
+
+    fn ring_png() -> Vec<u8> {
+        std::fs::read(format!("{}/fixtures/vision/ring_template.png", env!("CARGO_MANIFEST_DIR"))).unwrap()
+    }
+
+    #[test]
+    fn test_from_files() {
+        let png = ring_png();
+        let files = [
+            (Path::new("landmarks/landmarks.json"), LANDMARKS_JSON.as_bytes()),
+            (Path::new("landmarks/ring.png"), png.as_slice()),
+        ];
+        let set = LandmarkSet::from_files(files).unwrap();
+
+        assert_eq!(set.reference_size, Size::new(160, 96));
+        assert_eq!(set.landmarks[0].template.as_ref().map(|t| t.dimensions()), Some((28, 28)));
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let png = ring_png();
+        assert!(LandmarkSet::from_files([(Path::new("ring.png"), png.as_slice())]).is_err());
+        assert!(LandmarkSet::from_files([(Path::new("landmarks.json"), LANDMARKS_JSON.as_bytes())]).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod landmark;
+mod auto_layout;
+
+pub use landmark::{Landmark, LandmarkCrop, LandmarkDefinition, LandmarkSet};
+pub use auto_layout::{detect_layout, DEFAULT_MIN_SCORE};
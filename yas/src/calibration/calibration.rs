@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+use log::{info, warn};
+
+use crate::draw_capture_region::draw_capture_region::{draw_number_label, DrawCaptureRegion};
+use crate::game_info::{Platform, UI};
+use crate::ocr::ImageToText;
+use crate::positioning::{Pos, Rect, Size};
+use crate::utils::crop_rect;
+use crate::window_info::{WindowInfoKey, WindowInfoRepository, WindowInfoSource, WindowInfoTemplatePerSize, WindowInfoType};
+
+/// The state of a single window info key on a screenshot
+#[derive(Debug, Clone)]
+pub struct CalibrationEntry {
+    pub key: WindowInfoKey,
+    pub value: Option<WindowInfoType>,
+    pub source: Option<WindowInfoSource>,
+    /// the OCR result of a rect
+    pub text: Option<String>,
+    /// why this entry cannot be written into a window info file
+    pub problem: Option<String>,
+    /// whether the value is adjusted by hand
+    pub adjusted: bool,
+}
+
+/// Resolve window info keys on a screenshot, to check and author a window info file
+pub struct Calibration {
+    image: RgbImage,
+    ui: UI,
+    platform: Platform,
+    entries: Vec<CalibrationEntry>,
+}
+
+impl Calibration {
+    pub fn new(image: RgbImage, keys: &[WindowInfoKey], repo: &WindowInfoRepository, ui: UI, platform: Platform) -> Self {
+        let window_size = Size::new(image.width() as usize, image.height() as usize);
+
+        let mut entries: Vec<CalibrationEntry> = Vec::new();
+        for key in keys.iter() {
+            // the same key can be read by multiple structs
+            if entries.iter().any(|e| e.key.name == key.name) {
+                continue;
+            }
+
+            let resolved = repo.resolve_value(key.name, window_size, ui, platform);
+            let mut entry = CalibrationEntry {
+                key: *key,
+                value: resolved.map(|x| x.0),
+                source: resolved.map(|x| x.1),
+                text: None,
+                problem: None,
+                adjusted: false,
+            };
+            entry.problem = Self::validate(&entry, &image);
+            entries.push(entry);
+        }
+
+        Calibration {
+            image,
+            ui,
+            platform,
+            entries,
+        }
+    }
+
+    fn validate(entry: &CalibrationEntry, image: &RgbImage) -> Option<String> {
+        let width = image.width() as f64;
+        let height = image.height() as f64;
+
+        match entry.value {
+            None if entry.key.optional => None,
+            None => Some(String::from("缺失")),
+            Some(WindowInfoType::Rect(rect)) => {
+                if rect.width <= 0.0 || rect.height <= 0.0 {
+                    Some(String::from("宽高必须为正数"))
+                } else if rect.left < 0.0 || rect.top < 0.0 || rect.left + rect.width > width || rect.top + rect.height > height {
+                    Some(String::from("超出截图范围"))
+                } else {
+                    None
+                }
+            },
+            Some(WindowInfoType::Pos(pos)) => {
+                if pos.x < 0.0 || pos.y < 0.0 || pos.x >= width || pos.y >= height {
+                    Some(String::from("超出截图范围"))
+                } else {
+                    None
+                }
+            },
+            Some(WindowInfoType::Size(size)) if size.width < 0.0 || size.height < 0.0 => {
+                Some(String::from("宽高不能为负数"))
+            },
+            Some(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[CalibrationEntry] {
+        &self.entries
+    }
+
+    /// Replace the value of an entry by hand, its OCR result is cleared
+    pub fn set_value(&mut self, index: usize, value: WindowInfoType) -> Result<()> {
+        let entry = self.entries.get_mut(index)
+            .ok_or_else(|| anyhow!("没有编号为 {} 的窗口信息", index))?;
+        entry.value = Some(value);
+        entry.source = None;
+        entry.text = None;
+        entry.adjusted = true;
+        entry.problem = Self::validate(entry, &self.image);
+
+        Ok(())
+    }
+
+    /// OCR every rect, to show what the scanner would read
+    pub fn run_ocr(&mut self, ocr: &dyn ImageToText<RgbImage>) -> Result<()> {
+        for index in 0..self.entries.len() {
+            self.run_ocr_on(index, ocr)?;
+        }
+
+        Ok(())
+    }
+
+    /// OCR the entry if it is a rect
+    pub fn run_ocr_on(&mut self, index: usize, ocr: &dyn ImageToText<RgbImage>) -> Result<()> {
+        let entry = &mut self.entries[index];
+        if let Some(WindowInfoType::Rect(rect)) = entry.value {
+            if let Some(im) = crop_rect(&self.image, &rect) {
+                entry.text = Some(ocr.image_to_text(&im, false)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw every rect and pos on the screenshot, labeled by the index in `entries`
+    pub fn render_overlay(&self) -> RgbImage {
+        let mut image = self.image.clone();
+        let yellow = image::Rgb([255, 255, 0]);
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let value = match entry.value {
+                Some(v) => v,
+                None => continue,
+            };
+            value.draw_capture_region(&mut image);
+
+            match value {
+                WindowInfoType::Rect(rect) => draw_number_label(&mut image, rect.left as i32, rect.top as i32 - 15, index, yellow),
+                WindowInfoType::Pos(pos) => draw_number_label(&mut image, pos.x as i32 + 7, pos.y as i32 - 7, index, yellow),
+                _ => (),
+            }
+        }
+
+        image
+    }
+
+    pub fn log_report(&self) {
+        for index in 0..self.entries.len() {
+            self.log_entry(index);
+        }
+    }
+
+    pub fn log_entry(&self, index: usize) {
+        let entry = &self.entries[index];
+        let value = match entry.value {
+            Some(v) => format!("{:?}", v),
+            None => String::from("-"),
+        };
+        let source = match entry.source {
+            Some(s) => s.to_string(),
+            None if entry.adjusted => String::from("手动调整"),
+            None => String::from("-"),
+        };
+        let text = entry.text.as_ref().map_or(String::new(), |t| format!("，识别: \"{}\"", t));
+
+        match entry.problem.as_ref() {
+            Some(problem) => warn!("#{} {}: {}（{}）{}，问题: {}", index, entry.key.name, value, source, text, problem),
+            None => info!("#{} {}: {}（{}）{}", index, entry.key.name, value, source, text),
+        }
+    }
+
+    /// Build a window info file for the size of the screenshot, fails if any entry has a problem
+    pub fn to_template(&self, repo: &WindowInfoRepository) -> Result<WindowInfoTemplatePerSize> {
+        let problems = self.entries.iter()
+            .filter_map(|e| e.problem.as_ref().map(|p| format!("{}: {}", e.key.name, p)))
+            .collect::<Vec<_>>();
+        if !problems.is_empty() {
+            return Err(anyhow!("窗口信息校验失败: {}", problems.join("; ")));
+        }
+
+        let mut data = HashMap::new();
+        let mut anchors = HashMap::new();
+        for entry in self.entries.iter() {
+            if let Some(value) = entry.value {
+                data.insert(String::from(entry.key.name), value);
+                if let Some(anchor) = repo.anchors.get(entry.key.name) {
+                    anchors.insert(String::from(entry.key.name), *anchor);
+                }
+            }
+        }
+
+        Ok(WindowInfoTemplatePerSize {
+            current_resolution: Size::new(self.image.width() as usize, self.image.height() as usize),
+            platform: self.platform,
+            ui: self.ui,
+            data,
+            anchors,
+        })
+    }
+}
+
+/// Parse numbers separated by commas or spaces as a value of the same type as `current`,
+/// the type of a missing value is told by the count of the numbers, 4 for a rect and 2 for a pos
+pub fn parse_value(current: Option<WindowInfoType>, s: &str) -> Result<WindowInfoType> {
+    let numbers = s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse::<f64>().map_err(|_| anyhow!("无法解析数值: {}", x)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let value = match (current, numbers.as_slice()) {
+        (Some(WindowInfoType::Rect(_)) | None, &[left, top, width, height]) =>
+            WindowInfoType::Rect(Rect::new(left, top, width, height)),
+        (Some(WindowInfoType::Pos(_)) | None, &[x, y]) => WindowInfoType::Pos(Pos::new(x, y)),
+        (Some(WindowInfoType::Size(_)), &[width, height]) => WindowInfoType::Size(Size::new(width, height)),
+        (Some(WindowInfoType::Float(_)) | None, &[v]) => WindowInfoType::Float(v),
+        (Some(WindowInfoType::InvariantFloat(_)), &[v]) => WindowInfoType::InvariantFloat(v),
+        (Some(WindowInfoType::InvariantInt(_)), &[v]) if v.fract() == 0.0 => WindowInfoType::InvariantInt(v as i32),
+        _ => return Err(anyhow!("数值的个数或类型不符: {}", s)),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        let rect = WindowInfoType::Rect(Rect::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(parse_value(Some(rect), "10, 20 30,40").unwrap(), WindowInfoType::Rect(Rect::new(10.0, 20.0, 30.0, 40.0)));
+        assert!(parse_value(Some(rect), "10,20").is_err());
+
+        let size = WindowInfoType::Size(Size::new(1.0, 1.0));
+        assert_eq!(parse_value(Some(size), "3,4").unwrap(), WindowInfoType::Size(Size::new(3.0, 4.0)));
+        assert!(parse_value(Some(WindowInfoType::InvariantInt(1)), "2.5").is_err());
+        assert!(parse_value(None, "1,x").is_err());
+    }
+
+    #[test]
+    fn test_parse_missing_value() {
+        assert_eq!(parse_value(None, "5 6").unwrap(), WindowInfoType::Pos(Pos::new(5.0, 6.0)));
+        assert_eq!(parse_value(None, "1 2 3 4").unwrap(), WindowInfoType::Rect(Rect::new(1.0, 2.0, 3.0, 4.0)));
+    }
+
+    #[test]
+    fn test_set_value() {
+        let keys = [WindowInfoKey { name: "rect", optional: false }];
+        let repo = WindowInfoRepository::new();
+        let mut calibration = Calibration::new(RgbImage::new(100, 50), &keys, &repo, UI::Desktop, Platform::Windows);
+        assert_eq!(calibration.entries()[0].problem.as_deref(), Some("缺失"));
+
+        calibration.set_value(0, WindowInfoType::Rect(Rect::new(90.0, 0.0, 20.0, 10.0))).unwrap();
+        assert_eq!(calibration.entries()[0].problem.as_deref(), Some("超出截图范围"));
+
+        calibration.set_value(0, WindowInfoType::Rect(Rect::new(10.0, 0.0, 20.0, 10.0))).unwrap();
+        assert!(calibration.entries()[0].adjusted);
+        assert!(calibration.entries()[0].problem.is_none());
+        assert!(calibration.set_value(1, WindowInfoType::Float(1.0)).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+mod calibration;
+
+pub use calibration::{parse_value, Calibration, CalibrationEntry};
@@ -1,11 +1,12 @@
 use std::sync::{Arc, atomic};
 use std::sync::atomic::AtomicBool;
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::Receiver;
 use std::thread::JoinHandle;
 use std::thread;
 use image::{GenericImage, RgbImage};
 use crate::capture::{Capturer, GenericCapturer};
 use crate::positioning::Rect;
+use crate::worker_pool::PoolSender;
 use anyhow::Result;
 
 pub struct StreamingCapturer {
@@ -24,7 +25,8 @@ impl StreamingCapturer where {
         }
     }
 
-    pub fn start_transform<F, S>(self, tx: Sender<S>, transform: F) -> (JoinHandle<Result<()>>, impl Fn())
+    /// Capturing pauses while the queue of `tx` is full
+    pub fn start_transform<F, S>(self, mut tx: PoolSender<S>, transform: F) -> (JoinHandle<Result<()>>, impl Fn())
     where
         F: Fn(RgbImage) -> S + Send + Sync + 'static,
         S: Send + Sync + 'static
@@ -0,0 +1,14 @@
+#[derive(Clone, clap::Args)]
+pub struct CorrectionConfig {
+    /// The max number of characters edited when a recognized name is snapped to a known one, 0 disables the correction
+    #[arg(id = "max-correction-distance", long = "max-correction-distance", help = "将识别结果纠正为已知名称时允许修改的最大字符数，为 0 时不纠正", value_name = "DISTANCE", default_value_t = 2)]
+    pub max_distance: usize,
+}
+
+impl Default for CorrectionConfig {
+    fn default() -> Self {
+        CorrectionConfig {
+            max_distance: 2,
+        }
+    }
+}
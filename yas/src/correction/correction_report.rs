@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::export::ExportAssets;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Correction {
+    /// the position of the item in the scan results, starting from 0
+    pub index: usize,
+    pub field: String,
+    pub raw: String,
+    /// None if the match is ambiguous, then the text is left as it is
+    pub corrected: Option<String>,
+    pub distance: usize,
+    /// the equally near words of an ambiguous match
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+}
+
+impl Correction {
+    /// e.g. `title: "角斗土的留恋" → "角斗士的留恋"`, for the exports which are not JSON
+    pub fn describe(&self) -> String {
+        match self.corrected.as_ref() {
+            Some(corrected) => format!("{}: \"{}\" → \"{}\"", self.field, self.raw, corrected),
+            None => format!("{}: \"{}\" 未纠正，可能为 {}", self.field, self.raw, self.candidates.join("、")),
+        }
+    }
+}
+
+/// The corrections of the exported item at `index`, as returned by `CorrectionReport::by_exported_item`,
+/// empty if none are attached
+pub fn corrections_of(corrections: &[Vec<Correction>], index: usize) -> &[Correction] {
+    corrections.get(index).map_or(&[], |c| c.as_slice())
+}
+
+/// Every recognized text which is snapped to a known name, or could not be for the match is ambiguous
+#[derive(Debug, Clone, Default)]
+pub struct CorrectionReport {
+    pub items: Vec<Correction>,
+}
+
+impl CorrectionReport {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn ambiguous(&self) -> impl Iterator<Item = &Correction> {
+        self.items.iter().filter(|c| c.corrected.is_none())
+    }
+
+    pub fn log(&self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let ambiguous_count = self.ambiguous().count();
+        info!("纠正了 {} 个识别结果", self.items.len() - ambiguous_count);
+        if ambiguous_count == 0 {
+            return;
+        }
+
+        warn!("以下 {} 个识别结果有多个同样接近的名称，未纠正，请核对：", ambiguous_count);
+        for item in self.ambiguous() {
+            warn!("第 {} 个物品 {}: \"{}\"，可能为 {}", item.index + 1, item.field, item.raw, item.candidates.join("、"));
+        }
+    }
+
+    /// The corrections of every exported item, to attach to the item in the exports
+    /// `scan_indices` holds the index in the scan results of every exported item, a rejected item is not exported
+    pub fn by_exported_item(&self, scan_indices: &[usize]) -> Vec<Vec<Correction>> {
+        scan_indices.iter()
+            .map(|&index| self.items.iter().filter(|c| c.index == index).cloned().collect())
+            .collect()
+    }
+
+    /// Add `corrections.json` to the export assets
+    pub fn emit_to(&self, output_dir: &Path, export_assets: &mut ExportAssets) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let contents = serde_json::to_string_pretty(&self.items).unwrap();
+        export_assets.add_asset(
+            Some(String::from("corrections")),
+            output_dir.join("corrections.json"),
+            contents.into_bytes(),
+            Some(String::from("识别结果的纠正记录")),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correction(index: usize, corrected: Option<&str>) -> Correction {
+        Correction {
+            index,
+            field: String::from("title"),
+            raw: String::from("角斗土的留恋"),
+            corrected: corrected.map(String::from),
+            distance: 1,
+            candidates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_by_exported_item() {
+        let report = CorrectionReport {
+            items: vec![correction(0, Some("角斗士的留恋")), correction(2, None), correction(2, Some("角斗士的留恋"))],
+        };
+        // the item at 1 is rejected
+        let corrections = report.by_exported_item(&[0, 2, 3]);
+
+        assert_eq!(corrections.iter().map(|c| c.len()).collect::<Vec<_>>(), vec![1, 2, 0]);
+        assert_eq!(corrections[0][0].describe(), "title: \"角斗土的留恋\" → \"角斗士的留恋\"");
+    }
+}
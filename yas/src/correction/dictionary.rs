@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use edit_distance::edit_distance;
+
+/// The valid names which OCR output is snapped to
+pub struct Dictionary {
+    words: Vec<String>,
+    set: HashSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictionaryMatch {
+    Exact,
+    Corrected {
+        word: String,
+        distance: usize,
+    },
+    /// more than one word is the nearest, none of them is taken
+    Ambiguous {
+        candidates: Vec<String>,
+        distance: usize,
+    },
+    NotFound,
+}
+
+impl Dictionary {
+    pub fn new<I, S>(words: I) -> Dictionary where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let set = words.into_iter().map(|w| String::from(w.as_ref())).collect::<HashSet<_>>();
+        let mut words = set.iter().cloned().collect::<Vec<_>>();
+        words.sort();
+
+        Dictionary {
+            words,
+            set,
+        }
+    }
+
+    pub fn contains(&self, s: &str) -> bool {
+        self.set.contains(s)
+    }
+
+    /// Find the nearest words of `s` within `max_distance` edits of characters
+    /// a word is only taken if less than half of its characters are edited, so short names are not mistaken for each other
+    pub fn lookup(&self, s: &str, max_distance: usize) -> DictionaryMatch {
+        if self.contains(s) {
+            return DictionaryMatch::Exact;
+        }
+        if s.is_empty() || max_distance == 0 {
+            return DictionaryMatch::NotFound;
+        }
+
+        let mut best_distance = usize::MAX;
+        let mut candidates = Vec::new();
+        for word in self.words.iter() {
+            let distance = edit_distance(s, word);
+            if distance > max_distance || distance * 2 >= word.chars().count() {
+                continue;
+            }
+
+            if distance < best_distance {
+                best_distance = distance;
+                candidates.clear();
+            }
+            if distance == best_distance {
+                candidates.push(word.clone());
+            }
+        }
+
+        match candidates.len() {
+            0 => DictionaryMatch::NotFound,
+            1 => DictionaryMatch::Corrected {
+                word: candidates.pop().unwrap(),
+                distance: best_distance,
+            },
+            _ => DictionaryMatch::Ambiguous {
+                candidates,
+                distance: best_distance,
+            },
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod dictionary;
+mod text_corrector;
+mod correction_report;
+mod correction_config;
+
+pub use dictionary::{Dictionary, DictionaryMatch};
+pub use text_corrector::TextCorrector;
+pub use correction_report::{corrections_of, Correction, CorrectionReport};
+pub use correction_config::CorrectionConfig;
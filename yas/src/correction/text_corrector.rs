@@ -0,0 +1,46 @@
+use crate::correction::{Correction, CorrectionReport, Dictionary, DictionaryMatch};
+
+/// Snaps recognized text to dictionaries, and keeps a record of what is changed
+pub struct TextCorrector {
+    pub max_distance: usize,
+    pub report: CorrectionReport,
+}
+
+impl TextCorrector {
+    pub fn new(max_distance: usize) -> TextCorrector {
+        TextCorrector {
+            max_distance,
+            report: CorrectionReport::default(),
+        }
+    }
+
+    /// The nearest word of `text` in the dictionary, or `text` itself if there is no such word or more than one
+    /// `index` and `field` tell where the text is from in the report
+    pub fn correct(&mut self, index: usize, field: &str, text: &str, dictionary: &Dictionary) -> String {
+        match dictionary.lookup(text, self.max_distance) {
+            DictionaryMatch::Exact | DictionaryMatch::NotFound => String::from(text),
+            DictionaryMatch::Corrected { word, distance } => {
+                self.report.items.push(Correction {
+                    index,
+                    field: String::from(field),
+                    raw: String::from(text),
+                    corrected: Some(word.clone()),
+                    distance,
+                    candidates: Vec::new(),
+                });
+                word
+            },
+            DictionaryMatch::Ambiguous { candidates, distance } => {
+                self.report.items.push(Correction {
+                    index,
+                    field: String::from(field),
+                    raw: String::from(text),
+                    corrected: None,
+                    distance,
+                    candidates,
+                });
+                String::from(text)
+            },
+        }
+    }
+}
@@ -1,32 +1,39 @@
 use crate::positioning::{Pos, Rect};
+use crate::window_info::WindowInfoType;
 
 
 pub trait DrawCaptureRegion {
     fn draw_capture_region(&self, image: &mut image::RgbImage);
 }
 
-// todo other types
+/// Put a pixel, ignoring positions outside of the image
+fn put_pixel_checked(image: &mut image::RgbImage, x: i32, y: i32, color: image::Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+        image.put_pixel(x as u32, y as u32, color);
+    }
+}
+
 impl DrawCaptureRegion for Pos<f64> {
     fn draw_capture_region(&self, image: &mut image::RgbImage) {
         let blue = image::Rgb([0, 0, 255]);
 
-        let x = self.x as u32;
-        let y = self.y as u32;
+        let x = self.x as i32;
+        let y = self.y as i32;
 
         for i in x - 1..=x + 1 {
             for j in y - 1..=y + 1 {
-                image.put_pixel(i, j, blue);
+                put_pixel_checked(image, i, j, blue);
             }
         }
 
         for i in x - 5..=x + 5 {
-            image.put_pixel(i, y + 5, blue);
-            image.put_pixel(i, y - 5, blue);
+            put_pixel_checked(image, i, y + 5, blue);
+            put_pixel_checked(image, i, y - 5, blue);
         }
 
         for j in y - 5..=y + 5 {
-            image.put_pixel(x + 5, j, blue);
-            image.put_pixel(x - 5, j, blue);
+            put_pixel_checked(image, x + 5, j, blue);
+            put_pixel_checked(image, x - 5, j, blue);
         }
     }
 }
@@ -35,21 +42,80 @@ impl DrawCaptureRegion for Rect<f64> {
     fn draw_capture_region(&self, image: &mut image::RgbImage) {
         let red = image::Rgb([255, 0, 0]);
 
-        let left = self.left as u32;
-        let top = self.top as u32;
-        let width = self.width as u32;
-        let height = self.height as u32;
-        let bottom = top + height;
-        let right = left + width;
+        let left = self.left as i32;
+        let top = self.top as i32;
+        let bottom = top + self.height as i32;
+        let right = left + self.width as i32;
+
+        for x in left..=right {
+            put_pixel_checked(image, x, top, red);
+            put_pixel_checked(image, x, bottom, red);
+        }
 
-        for x in left..right {
-            image.put_pixel(x, top, red);
-            image.put_pixel(x, bottom, red);
+        for y in top..=bottom {
+            put_pixel_checked(image, left, y, red);
+            put_pixel_checked(image, right, y, red);
         }
+    }
+}
 
-        for y in top..bottom {
-            image.put_pixel(left, y, red);
-            image.put_pixel(right, y, red);
+impl DrawCaptureRegion for WindowInfoType {
+    fn draw_capture_region(&self, image: &mut image::RgbImage) {
+        match self {
+            WindowInfoType::Rect(rect) => rect.draw_capture_region(image),
+            WindowInfoType::Pos(pos) => pos.draw_capture_region(image),
+            _ => (),
         }
     }
-}
\ No newline at end of file
+}
+
+/// 3x5 bitmaps of digits, each row uses the lower 3 bits
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Draw a number at (x, y) with a black background, so that overlays can be labeled without a font
+pub fn draw_number_label(image: &mut image::RgbImage, x: i32, y: i32, number: usize, color: image::Rgb<u8>) {
+    let scale = 2;
+    let text = number.to_string();
+    let width = text.len() as i32 * 4 * scale + scale;
+    let height = 7 * scale;
+
+    for i in x..x + width {
+        for j in y..y + height {
+            put_pixel_checked(image, i, j, image::Rgb([0, 0, 0]));
+        }
+    }
+
+    for (index, c) in text.chars().enumerate() {
+        let bitmap = DIGITS[c.to_digit(10).unwrap() as usize];
+        let origin_x = x + scale + index as i32 * 4 * scale;
+        let origin_y = y + scale;
+        for (row, bits) in bitmap.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dx in 0..scale {
+                    for dy in 0..scale {
+                        put_pixel_checked(
+                            image,
+                            origin_x + col * scale + dx,
+                            origin_y + row as i32 * scale + dy,
+                            color
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
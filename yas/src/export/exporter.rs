@@ -28,6 +28,10 @@ impl ExportAssets {
         let mut stat = ExportStatistics::new();
 
         for item in self.assets.iter() {
+            if let Some(parent) = item.filename.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+
             let mut file = match File::create(&item.filename) {
                 Err(why) => {
                     stat.failed_items.push(StatisticItem::from_export_item(item));
@@ -2,9 +2,11 @@ pub use asset_emitter::AssetEmitter;
 pub use export_item::{ExportItem, StatisticItem};
 pub use export_statistics::ExportStatistics;
 pub use exporter::ExportAssets;
+pub use rejected_items::RejectedItems;
 
 mod exporter;
 mod export_item;
 mod export_statistics;
 mod asset_emitter;
+mod rejected_items;
 
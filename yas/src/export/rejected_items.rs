@@ -0,0 +1,99 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use log::warn;
+use serde::Serialize;
+
+use crate::export::ExportAssets;
+
+#[derive(Serialize)]
+struct RejectedEntry<'a, E> {
+    index: usize,
+    /// whether the item is exported without the field
+    exported: bool,
+    reason: String,
+    #[serde(flatten)]
+    error: &'a E,
+}
+
+/// Scanned items which cannot be converted for export, along with why
+/// `E` is the conversion error of the game, which carries the raw text
+#[derive(Debug, Clone)]
+pub struct RejectedItems<E> {
+    /// the position of the item in the scan results, and the error
+    pub items: Vec<(usize, E)>,
+    /// items which are exported without a field that cannot be converted, e.g. an unknown equipped character
+    pub dropped_fields: Vec<(usize, E)>,
+}
+
+impl<E> Default for RejectedItems<E> {
+    fn default() -> Self {
+        RejectedItems {
+            items: Vec::new(),
+            dropped_fields: Vec::new(),
+        }
+    }
+}
+
+impl<E: Display + Serialize> RejectedItems<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, index: usize, error: E) {
+        self.items.push((index, error));
+    }
+
+    pub fn push_dropped_field(&mut self, index: usize, error: E) {
+        self.dropped_fields.push((index, error));
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn log(&self) {
+        if !self.items.is_empty() {
+            warn!("以下 {} 个物品无法转换，未被导出：", self.items.len());
+            for (index, error) in self.items.iter() {
+                warn!("第 {} 个物品: {}", index + 1, error);
+            }
+        }
+
+        if !self.dropped_fields.is_empty() {
+            warn!("以下 {} 个物品的部分信息无法转换，导出时已忽略该信息：", self.dropped_fields.len());
+            for (index, error) in self.dropped_fields.iter() {
+                warn!("第 {} 个物品: {}", index + 1, error);
+            }
+        }
+    }
+
+    /// Add `rejected.json` to the export assets
+    pub fn emit_to(&self, output_dir: &Path, export_assets: &mut ExportAssets) {
+        if self.items.is_empty() && self.dropped_fields.is_empty() {
+            return;
+        }
+
+        let rejected = self.items.iter().map(|item| (false, item));
+        let dropped = self.dropped_fields.iter().map(|item| (true, item));
+        let mut entries = rejected.chain(dropped).map(|(exported, (index, error))| RejectedEntry {
+            index: *index,
+            exported,
+            reason: error.to_string(),
+            error,
+        }).collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.index);
+
+        let contents = serde_json::to_string_pretty(&entries).unwrap();
+        export_assets.add_asset(
+            Some(String::from("rejected")),
+            output_dir.join("rejected.json"),
+            contents.into_bytes(),
+            Some(String::from("无法转换的物品")),
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use clap::ValueEnum;
+
+/// The language of the game client, which decides the names and labels in the captured text
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, ValueEnum)]
+pub enum GameLanguage {
+    /// Simplified Chinese
+    #[default]
+    #[value(name = "zh-cn")]
+    ZhCn,
+    /// English
+    #[value(name = "en")]
+    En,
+}
+
+impl GameLanguage {
+    /// The language key of the game data, e.g. "zh-cn"
+    pub fn code(&self) -> &'static str {
+        match *self {
+            GameLanguage::ZhCn => "zh-cn",
+            GameLanguage::En => "en",
+        }
+    }
+
+    /// Whether the built-in yas model can read the text, it only knows Chinese characters and digits
+    pub fn is_supported_by_yas_model(&self) -> bool {
+        *self == GameLanguage::ZhCn
+    }
+}
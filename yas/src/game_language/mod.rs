@@ -0,0 +1,3 @@
+mod game_language;
+
+pub use game_language::GameLanguage;
@@ -2,8 +2,8 @@
 #![feature(concat_idents)]
 #![allow(unused_imports)]
 
-#[cfg(all(feature = "ort", feature = "tract_onnx"))]
-compile_error!("feature \"ort\" and \"tract_onnx\" cannot be enabled at the same time");
+#[cfg(not(any(feature = "ort", feature = "tract_onnx")))]
+compile_error!("at least one of feature \"ort\" and \"tract_onnx\" should be enabled");
 
 extern crate log;
 extern crate lazy_static;
@@ -19,3 +19,13 @@ pub mod system_control;
 pub mod ocr;
 pub mod positioning;
 pub mod profiler;
+pub mod screen_state;
+pub mod sort_order;
+pub mod game_language;
+pub mod calibration;
+pub mod auto_layout;
+pub mod vision;
+pub mod worker_pool;
+pub mod correction;
+pub mod validation;
+pub mod rating;
@@ -0,0 +1,159 @@
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::Path;
+
+use image::RgbImage;
+use log::warn;
+use serde::Serialize;
+
+use crate::export::ExportAssets;
+use crate::ocr::OCRResult;
+use crate::positioning::Rect;
+use crate::utils::crop_rect;
+
+#[derive(Debug, Clone)]
+pub struct FieldConfidence {
+    pub field: String,
+    /// where the field is, relative to the image it is recognized from
+    pub rect: Rect<f64>,
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// The OCR confidence of every field of a scanned item
+/// it is not a part of the identity of an item, so it is ignored by `Eq` and `Hash`
+#[derive(Debug, Clone, Default)]
+pub struct ScanConfidence {
+    pub fields: Vec<FieldConfidence>,
+}
+
+impl PartialEq for ScanConfidence {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ScanConfidence {}
+
+impl Hash for ScanConfidence {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl ScanConfidence {
+    pub fn push(&mut self, field: &str, rect: Rect<f64>, result: &OCRResult) {
+        self.fields.push(FieldConfidence {
+            field: String::from(field),
+            rect,
+            text: result.text.clone(),
+            confidence: result.confidence,
+        });
+    }
+
+    /// The confidence of the least confident field
+    pub fn min(&self) -> f32 {
+        self.fields.iter().map(|f| f.confidence).fold(1.0, f32::min)
+    }
+
+    pub fn low_fields(&self, threshold: f32) -> impl Iterator<Item = &FieldConfidence> {
+        self.fields.iter().filter(move |f| f.confidence < threshold)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LowConfidenceItem {
+    /// the position of the item in scan order, starting from 0
+    pub index: usize,
+    pub field: String,
+    pub text: String,
+    pub confidence: f32,
+    pub crop: Option<RgbImage>,
+}
+
+#[derive(Serialize)]
+struct LowConfidenceEntry<'a> {
+    index: usize,
+    field: &'a str,
+    text: &'a str,
+    confidence: f32,
+    image: Option<String>,
+}
+
+/// Recognized fields which the user should double check
+#[derive(Debug, Clone, Default)]
+pub struct LowConfidenceReport {
+    pub threshold: f32,
+    pub items: Vec<LowConfidenceItem>,
+}
+
+impl LowConfidenceReport {
+    pub fn new(threshold: f32) -> Self {
+        LowConfidenceReport {
+            threshold,
+            items: Vec::new(),
+        }
+    }
+
+    /// Record the low confidence fields of an item, `image` is what the fields are recognized from
+    pub fn add(&mut self, index: usize, confidence: &ScanConfidence, image: &RgbImage) {
+        for field in confidence.low_fields(self.threshold) {
+            self.items.push(LowConfidenceItem {
+                index,
+                field: field.field.clone(),
+                text: field.text.clone(),
+                confidence: field.confidence,
+                crop: crop_rect(image, &field.rect),
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn log(&self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        warn!("以下 {} 个识别结果置信度低于 {}，请核对：", self.items.len(), self.threshold);
+        for item in self.items.iter() {
+            warn!("第 {} 个物品 {}: \"{}\"（置信度 {:.3}）", item.index + 1, item.field, item.text, item.confidence);
+        }
+    }
+
+    /// Add `low_confidence.json` and the crop of every field to the export assets
+    pub fn emit_to(&self, output_dir: &Path, export_assets: &mut ExportAssets) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let mut entries = Vec::new();
+        for item in self.items.iter() {
+            let mut image = None;
+            if let Some(crop) = item.crop.as_ref() {
+                let filename = format!("low_confidence/{}_{}.png", item.index, item.field);
+                let mut bytes = Vec::new();
+                if crop.write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png).is_ok() {
+                    export_assets.add_asset(None, output_dir.join(&filename), bytes, None);
+                    image = Some(filename);
+                }
+            }
+
+            entries.push(LowConfidenceEntry {
+                index: item.index,
+                field: &item.field,
+                text: &item.text,
+                confidence: item.confidence,
+                image,
+            });
+        }
+
+        let contents = serde_json::to_string_pretty(&entries).unwrap();
+        export_assets.add_asset(
+            Some(String::from("low confidence")),
+            output_dir.join("low_confidence.json"),
+            contents.into_bytes(),
+            Some(String::from("置信度较低的识别结果")),
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use crate::ocr::OCRResult;
+use crate::ocr::constrained_decoding::TextConstraint;
+
+pub const DEFAULT_BEAM_WIDTH: usize = 16;
+
+/// Classes less probable than this are not tried in a time step
+const MIN_CLASS_PROBABILITY: f32 = 1e-4;
+const MAX_CANDIDATES_PER_STEP: usize = 8;
+
+fn log_add(a: f32, b: f32) -> f32 {
+    if a == f32::NEG_INFINITY {
+        return b;
+    }
+    if b == f32::NEG_INFINITY {
+        return a;
+    }
+    let m = a.max(b);
+    m + ((a - m).exp() + (b - m).exp()).ln()
+}
+
+/// Log probabilities of a prefix, ending with a blank or not
+#[derive(Debug, Clone, Copy)]
+struct Beam {
+    blank: f32,
+    non_blank: f32,
+    /// the last emitted class
+    last: Option<usize>,
+}
+
+impl Beam {
+    fn new(last: Option<usize>) -> Beam {
+        Beam {
+            blank: f32::NEG_INFINITY,
+            non_blank: f32::NEG_INFINITY,
+            last,
+        }
+    }
+
+    fn total(&self) -> f32 {
+        log_add(self.blank, self.non_blank)
+    }
+}
+
+/// CTC prefix beam search which only keeps the prefixes allowed by the constraint
+/// the result is the most likely text accepted by the constraint, or the empty text of a blank region,
+/// its confidence is the probability relative to the greedy path, capped at 1, so an unlikely result is low in confidence
+pub fn ctc_beam_search<'a, F>(
+    probs: &[Vec<f32>],
+    blank: usize,
+    word: F,
+    constraint: &TextConstraint,
+    beam_width: usize
+) -> OCRResult where F: Fn(usize) -> &'a str {
+    let mut beams: HashMap<String, Beam> = HashMap::new();
+    beams.insert(String::new(), Beam { blank: 0.0, ..Beam::new(None) });
+    let mut greedy = 0.0_f32;
+
+    for step in probs.iter() {
+        let blank_prob = step.get(blank).copied().unwrap_or(0.0).ln();
+        greedy += step.iter().cloned().fold(f32::MIN_POSITIVE, f32::max).ln();
+
+        let mut candidates = step.iter()
+            .cloned()
+            .enumerate()
+            .filter(|&(i, p)| i != blank && p >= MIN_CLASS_PROBABILITY)
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(MAX_CANDIDATES_PER_STEP);
+
+        let mut next: HashMap<String, Beam> = HashMap::new();
+        for (prefix, beam) in beams.iter() {
+            let total = beam.total();
+
+            let entry = next.entry(prefix.clone()).or_insert(Beam::new(beam.last));
+            entry.blank = log_add(entry.blank, total + blank_prob);
+
+            for &(index, p) in candidates.iter() {
+                let p = p.ln();
+                let extend_from = if beam.last == Some(index) {
+                    // a repeated class without a blank in between collapses into the same char
+                    let entry = next.get_mut(prefix).unwrap();
+                    entry.non_blank = log_add(entry.non_blank, beam.non_blank + p);
+                    beam.blank
+                } else {
+                    total
+                };
+
+                let extended = prefix.clone() + word(index);
+                if !constraint.allows_prefix(&extended) {
+                    continue;
+                }
+                let entry = next.entry(extended).or_insert(Beam::new(Some(index)));
+                entry.non_blank = log_add(entry.non_blank, extend_from + p);
+            }
+        }
+
+        let mut next = next.into_iter().collect::<Vec<_>>();
+        next.sort_by(|a, b| b.1.total().total_cmp(&a.1.total()));
+        next.truncate(beam_width);
+        beams = next.into_iter().collect();
+    }
+
+    let best = beams.into_iter()
+        .filter(|(prefix, _)| prefix.is_empty() || constraint.accepts(prefix))
+        .max_by(|a, b| a.1.total().total_cmp(&b.1.total()));
+    // every accepted prefix is pruned, nothing can be told about the text
+    let (text, beam) = match best {
+        Some(v) => v,
+        None => return OCRResult {
+            text: String::new(),
+            char_confidences: Vec::new(),
+            confidence: 0.0,
+        },
+    };
+
+    let confidence = (beam.total() - greedy).exp().min(1.0);
+    OCRResult {
+        char_confidences: vec![confidence; text.chars().count()],
+        text,
+        confidence,
+    }
+}
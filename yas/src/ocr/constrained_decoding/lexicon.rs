@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    terminal: bool,
+}
+
+/// A closed vocabulary stored as a trie of chars
+#[derive(Debug, Clone)]
+pub struct Lexicon {
+    nodes: Vec<TrieNode>,
+    len: usize,
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        Lexicon {
+            nodes: vec![TrieNode::default()],
+            len: 0,
+        }
+    }
+}
+
+impl Lexicon {
+    pub fn new<I, S>(words: I) -> Lexicon where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let mut result = Lexicon::default();
+        for word in words {
+            result.insert(word.as_ref());
+        }
+        result
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+        for c in word.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, next);
+                    next
+                }
+            };
+        }
+
+        if !self.nodes[node].terminal {
+            self.nodes[node].terminal = true;
+            self.len += 1;
+        }
+    }
+
+    fn walk(&self, s: &str) -> Option<usize> {
+        let mut node = 0;
+        for c in s.chars() {
+            node = *self.nodes[node].children.get(&c)?;
+        }
+        Some(node)
+    }
+
+    /// Whether some word starts with `s`
+    pub fn contains_prefix(&self, s: &str) -> bool {
+        self.walk(s).is_some()
+    }
+
+    pub fn contains(&self, s: &str) -> bool {
+        self.walk(s).map_or(false, |node| self.nodes[node].terminal)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
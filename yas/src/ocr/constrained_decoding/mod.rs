@@ -0,0 +1,7 @@
+mod lexicon;
+mod text_constraint;
+mod beam_search;
+
+pub use lexicon::Lexicon;
+pub use text_constraint::TextConstraint;
+pub use beam_search::{ctc_beam_search, DEFAULT_BEAM_WIDTH};
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use crate::ocr::constrained_decoding::Lexicon;
+
+/// The allowed text of an OCR region
+#[derive(Debug, Clone)]
+pub enum TextConstraint {
+    /// one of the words
+    Lexicon(Arc<Lexicon>),
+    /// exactly this text
+    Literal(&'static str),
+    /// e.g. `4,123` with `thousands_separator`, `10.5` with one decimal
+    Number {
+        thousands_separator: bool,
+        max_decimals: usize,
+    },
+    /// every constraint in order, e.g. a stat name, `+` and a value
+    Sequence(Vec<TextConstraint>),
+    /// any of the constraints
+    Any(Vec<TextConstraint>),
+}
+
+/// Where a number is after reading some text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NumberState {
+    /// digits in the current group, or in the whole integer part without separators
+    group_digits: usize,
+    seen_separator: bool,
+    /// the integer part is a single 0, only a decimal point can follow
+    leading_zero: bool,
+    /// None before the decimal point
+    decimals: Option<usize>,
+}
+
+/// Read a prefix of a number, None if it is not a prefix of any number
+/// with separators, the first group has 1 to 3 digits and the others have exactly 3
+fn number_state(s: &str, thousands_separator: bool, max_decimals: usize) -> Option<NumberState> {
+    let mut state = NumberState {
+        group_digits: 0,
+        seen_separator: false,
+        leading_zero: false,
+        decimals: None,
+    };
+
+    for c in s.chars() {
+        match (c, state.decimals) {
+            ('0'..='9', None) => {
+                if state.leading_zero || (thousands_separator && state.group_digits == 3) {
+                    return None;
+                }
+                if c == '0' && state.group_digits == 0 && !state.seen_separator {
+                    state.leading_zero = true;
+                }
+                state.group_digits += 1;
+            },
+            ('0'..='9', Some(d)) => {
+                if d >= max_decimals {
+                    return None;
+                }
+                state.decimals = Some(d + 1);
+            },
+            (',', None) if thousands_separator => {
+                if state.group_digits == 0 || state.leading_zero || (state.seen_separator && state.group_digits != 3) {
+                    return None;
+                }
+                state.seen_separator = true;
+                state.group_digits = 0;
+            },
+            ('.', None) if max_decimals > 0 => {
+                if state.group_digits == 0 || (state.seen_separator && state.group_digits != 3) {
+                    return None;
+                }
+                state.decimals = Some(0);
+            },
+            _ => return None,
+        }
+    }
+
+    Some(state)
+}
+
+fn number_accepts(state: NumberState) -> bool {
+    match state.decimals {
+        None => state.group_digits > 0 && (!state.seen_separator || state.group_digits == 3),
+        Some(d) => d > 0,
+    }
+}
+
+/// Split points of a string at char boundaries, including both ends
+fn split_points(s: &str) -> impl Iterator<Item = usize> + '_ {
+    s.char_indices().map(|(i, _)| i).chain(std::iter::once(s.len()))
+}
+
+impl TextConstraint {
+    pub fn lexicon<I, S>(words: I) -> TextConstraint where I: IntoIterator<Item = S>, S: AsRef<str> {
+        TextConstraint::Lexicon(Arc::new(Lexicon::new(words)))
+    }
+
+    /// Whether `s` is a complete text
+    pub fn accepts(&self, s: &str) -> bool {
+        match self {
+            TextConstraint::Lexicon(lexicon) => lexicon.contains(s),
+            TextConstraint::Literal(literal) => *literal == s,
+            TextConstraint::Number { thousands_separator, max_decimals } => {
+                number_state(s, *thousands_separator, *max_decimals).map_or(false, number_accepts)
+            },
+            TextConstraint::Sequence(parts) => Self::sequence_accepts(parts, s),
+            TextConstraint::Any(options) => options.iter().any(|c| c.accepts(s)),
+        }
+    }
+
+    /// Whether `s` can be extended to a complete text
+    pub fn allows_prefix(&self, s: &str) -> bool {
+        match self {
+            TextConstraint::Lexicon(lexicon) => lexicon.contains_prefix(s),
+            TextConstraint::Literal(literal) => literal.starts_with(s),
+            TextConstraint::Number { thousands_separator, max_decimals } => {
+                number_state(s, *thousands_separator, *max_decimals).is_some()
+            },
+            TextConstraint::Sequence(parts) => Self::sequence_allows_prefix(parts, s),
+            TextConstraint::Any(options) => options.iter().any(|c| c.allows_prefix(s)),
+        }
+    }
+
+    fn sequence_accepts(parts: &[TextConstraint], s: &str) -> bool {
+        match parts.split_first() {
+            None => s.is_empty(),
+            Some((first, rest)) => split_points(s)
+                .any(|i| first.accepts(&s[..i]) && Self::sequence_accepts(rest, &s[i..])),
+        }
+    }
+
+    fn sequence_allows_prefix(parts: &[TextConstraint], s: &str) -> bool {
+        match parts.split_first() {
+            None => s.is_empty(),
+            Some((first, rest)) => {
+                first.allows_prefix(s) || split_points(s)
+                    .any(|i| first.accepts(&s[..i]) && Self::sequence_allows_prefix(rest, &s[i..]))
+            },
+        }
+    }
+}
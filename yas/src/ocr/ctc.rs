@@ -0,0 +1,65 @@
+use crate::ocr::OCRResult;
+
+/// Turn the scores of a time step into probabilities, works for both logits and log probabilities
+pub fn softmax(scores: &mut [f32]) {
+    let max = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let mut sum = 0.0;
+    for v in scores.iter_mut() {
+        *v = (*v - max).exp();
+        sum += *v;
+    }
+    for v in scores.iter_mut() {
+        *v /= sum;
+    }
+}
+
+fn argmax(probs: &[f32]) -> (usize, f32) {
+    let mut max_index = 0;
+    let mut max_value = f32::NEG_INFINITY;
+    for (i, &v) in probs.iter().enumerate() {
+        if v > max_value {
+            max_value = v;
+            max_index = i;
+        }
+    }
+    (max_index, max_value)
+}
+
+/// Greedy CTC decoding of the class probabilities of each time step
+/// the confidence of a character is the highest probability among the steps it spans,
+/// and an empty result is as confident as its least confident blank step
+pub fn ctc_greedy_decode<'a, F>(probs: &[Vec<f32>], blank: usize, word: F) -> OCRResult
+    where F: Fn(usize) -> &'a str
+{
+    let mut text = String::new();
+    let mut char_confidences: Vec<f32> = Vec::new();
+    let mut min_blank = 1.0_f32;
+    let mut last = blank;
+
+    for step in probs.iter() {
+        let (index, p) = argmax(step);
+        if index == blank {
+            min_blank = min_blank.min(p);
+        } else if index == last {
+            // the word may be empty, then there is no char to update
+            if let Some(c) = char_confidences.last_mut() {
+                *c = c.max(p);
+            }
+        } else {
+            let w = word(index);
+            text.push_str(w);
+            // a word can be more than one char
+            for _ in w.chars() {
+                char_confidences.push(p);
+            }
+        }
+        last = index;
+    }
+
+    let confidence = char_confidences.iter().cloned().reduce(f32::min).unwrap_or(min_blank);
+    OCRResult {
+        text,
+        char_confidences,
+        confidence,
+    }
+}
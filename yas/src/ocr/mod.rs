@@ -1,10 +1,24 @@
 mod traits;
+mod ctc;
+mod confidence;
+mod ocr_config;
+mod onnx_session;
+mod constrained_decoding;
 mod yas_model;
 mod paddle_paddle_model;
+mod model_registry;
+mod ocr_samples;
 
 pub use yas_model::yas_ocr_model::YasOCRModel;
 pub use yas_model::yas_ocr_model::yas_ocr_model;
-pub use traits::ImageToText;
+pub use traits::{ImageToText, OCRResult};
+pub use ctc::{ctc_greedy_decode, softmax};
+pub use constrained_decoding::{ctc_beam_search, Lexicon, TextConstraint, DEFAULT_BEAM_WIDTH};
+pub use ocr_config::{OCRConfig, DEFAULT_INTRA_THREADS};
+pub use onnx_session::{OCRBackend, OnnxSession, OnnxTensor};
+pub use confidence::{FieldConfidence, ScanConfidence, LowConfidenceItem, LowConfidenceReport};
 pub use paddle_paddle_model::PPOCRModel;
 pub use paddle_paddle_model::PPOCRChV4RecInfer;
 pub use paddle_paddle_model::ppocr_model;
+pub use model_registry::{OCRModelLoader, OCRModelRegistry, OCRModelSet, RegionRequirement};
+pub use ocr_samples::{load_ocr_samples, ocr_samples_dir, OCR_SAMPLES_ENV};
@@ -0,0 +1,7 @@
+mod registry;
+mod model_set;
+mod region_requirement;
+
+pub use registry::{OCRModelLoader, OCRModelRegistry};
+pub use model_set::OCRModelSet;
+pub use region_requirement::RegionRequirement;
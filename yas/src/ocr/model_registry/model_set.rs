@@ -0,0 +1,95 @@
+use anyhow::Result;
+use image::RgbImage;
+use log::info;
+
+use crate::ocr::{ImageToText, OCRResult};
+use crate::ocr::model_registry::RegionRequirement;
+
+/// The model chosen by `--ocr-model`, and the one which recognizes again the regions it fails to
+pub struct OCRModelSet {
+    pub model: Box<dyn ImageToText<RgbImage> + Send>,
+    pub fallback: Option<Box<dyn ImageToText<RgbImage> + Send>>,
+}
+
+fn recognize_in_batches(
+    model: &dyn ImageToText<RgbImage>,
+    regions: &[(&str, &RgbImage, Option<&RegionRequirement>)],
+    batch_size: usize,
+    constrained: bool,
+) -> Result<Vec<OCRResult>> {
+    let inputs = regions.iter()
+        .map(|&(_, im, requirement)| {
+            let constraint = requirement.filter(|_| constrained).and_then(|r| r.constraint.as_ref());
+            (im, constraint)
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(batch_size.max(1)) {
+        results.extend(model.batch_image_to_text(batch, false)?);
+    }
+    Ok(results)
+}
+
+impl OCRModelSet {
+    /// Recognize named regions in batches, the results are in the same order as `regions`
+    /// the decoding is constrained by the requirement of each region if `constrained`,
+    /// and a region whose text cannot be parsed takes the text of the fallback model, if that can be
+    pub fn recognize(
+        &self,
+        regions: &[(&str, &RgbImage, Option<&RegionRequirement>)],
+        batch_size: usize,
+        constrained: bool,
+    ) -> Result<Vec<OCRResult>> {
+        recognize_with_fallback(self.model.as_ref(), self.fallback.as_deref(), regions, batch_size, constrained)
+    }
+
+    /// Like `recognize`, but the fallback model recognizes first if there is one, e.g. for an item which the model fails
+    pub fn recognize_fallback_first(
+        &self,
+        regions: &[(&str, &RgbImage, Option<&RegionRequirement>)],
+        batch_size: usize,
+        constrained: bool,
+    ) -> Result<Vec<OCRResult>> {
+        match self.fallback.as_deref() {
+            Some(fallback) => recognize_with_fallback(fallback, Some(self.model.as_ref()), regions, batch_size, constrained),
+            None => self.recognize(regions, batch_size, constrained),
+        }
+    }
+}
+
+fn recognize_with_fallback(
+    model: &dyn ImageToText<RgbImage>,
+    fallback: Option<&(dyn ImageToText<RgbImage> + Send)>,
+    regions: &[(&str, &RgbImage, Option<&RegionRequirement>)],
+    batch_size: usize,
+    constrained: bool,
+) -> Result<Vec<OCRResult>> {
+    let mut results = recognize_in_batches(model, regions, batch_size, constrained)?;
+
+    let fallback = match fallback {
+        Some(v) => v,
+        None => return Ok(results),
+    };
+
+    let failed = regions.iter().zip(results.iter())
+        .enumerate()
+        .filter(|(_, (&(_, _, requirement), result))| requirement.map_or(false, |r| !r.is_parseable(&result.text)))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        return Ok(results);
+    }
+
+    let failed_regions = failed.iter().map(|&i| regions[i]).collect::<Vec<_>>();
+    let fallback_results = recognize_in_batches(fallback, &failed_regions, batch_size, constrained)?;
+
+    for (&(name, _, requirement), (index, result)) in failed_regions.iter().zip(failed.into_iter().zip(fallback_results)) {
+        if requirement.map_or(true, |r| r.is_parseable(&result.text)) {
+            info!("{} 的识别结果 \"{}\" 无法解析，使用备用模型的结果 \"{}\"", name, results[index].text, result.text);
+            results[index] = result;
+        }
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,33 @@
+use crate::ocr::TextConstraint;
+
+/// What a scanner requires of the text in an OCR region
+#[derive(Clone)]
+pub struct RegionRequirement {
+    /// the text which can be parsed, None if any text can
+    pub constraint: Option<TextConstraint>,
+    /// whether the region can be empty, e.g. the fourth sub stat of an item which has only three
+    pub optional: bool,
+}
+
+impl RegionRequirement {
+    pub fn required(constraint: TextConstraint) -> RegionRequirement {
+        RegionRequirement {
+            constraint: Some(constraint),
+            optional: false,
+        }
+    }
+
+    pub fn optional(constraint: TextConstraint) -> RegionRequirement {
+        RegionRequirement {
+            constraint: Some(constraint),
+            optional: true,
+        }
+    }
+
+    pub fn is_parseable(&self, text: &str) -> bool {
+        if text.is_empty() {
+            return self.optional;
+        }
+        self.constraint.as_ref().is_none_or(|c| c.accepts(text))
+    }
+}
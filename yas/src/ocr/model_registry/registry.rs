@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+
+use crate::ocr::{ImageToText, OCRConfig, PPOCRChV4RecInfer, PPOCRModel, YasOCRModel};
+use crate::ocr::model_registry::OCRModelSet;
+
+pub type OCRModelLoader = fn(&OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>>;
+
+/// The OCR models which can be chosen by name with `--ocr-model`, other values are taken as paths of onnx files
+pub struct OCRModelRegistry {
+    loaders: Vec<(&'static str, OCRModelLoader)>,
+}
+
+fn load_ppocr(config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    Ok(Box::new(PPOCRChV4RecInfer::new_with_backend(config.backend(), config.intra_threads)?))
+}
+
+/// The dictionary is the file next to the model of the same name,
+/// a `.json` one for a yas model, or a `.txt` one for a PaddleOCR model
+fn load_model_file(path: &Path, config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+    let model = std::fs::read(path)?;
+
+    let json_path = path.with_extension("json");
+    let txt_path = path.with_extension("txt");
+    if json_path.is_file() {
+        let content = std::fs::read_to_string(json_path)?;
+        Ok(Box::new(YasOCRModel::new_with_backend(&model, &content, config.backend(), config.intra_threads)?))
+    } else if txt_path.is_file() {
+        Ok(Box::new(PPOCRModel::new_from_file_with_backend(path, txt_path, config.backend(), config.intra_threads)?))
+    } else {
+        Err(anyhow!("找不到模型 {} 的字典，需要同名的 .json（yas 模型）或 .txt（PaddleOCR 模型）文件", path.display()))
+    }
+}
+
+impl OCRModelRegistry {
+    /// A registry of the models shipped with yas_core, the game crates add their own ones
+    pub fn new() -> OCRModelRegistry {
+        OCRModelRegistry {
+            loaders: vec![("ppocr", load_ppocr)],
+        }
+    }
+
+    pub fn with_model(mut self, name: &'static str, loader: OCRModelLoader) -> OCRModelRegistry {
+        self.loaders.retain(|(n, _)| *n != name);
+        self.loaders.push((name, loader));
+        self
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.loaders.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Load a model by its name, or from an onnx file
+    pub fn load(&self, model: &str, config: &OCRConfig) -> Result<Box<dyn ImageToText<RgbImage> + Send>> {
+        if let Some((_, loader)) = self.loaders.iter().find(|(name, _)| *name == model) {
+            return loader(config);
+        }
+
+        let path = Path::new(model);
+        if path.is_file() {
+            load_model_file(path, config)
+        } else {
+            Err(anyhow!("未知的 OCR 模型: {}，可选 {} 或模型文件路径", model, self.names().join("、")))
+        }
+    }
+
+    /// Load the models of `--ocr-model` and `--ocr-fallback-model`
+    pub fn load_set(&self, config: &OCRConfig) -> Result<OCRModelSet> {
+        let fallback = match config.fallback_model.as_ref() {
+            Some(name) => Some(self.load(name, config)?),
+            None => None,
+        };
+
+        Ok(OCRModelSet {
+            model: self.load(&config.model, config)?,
+            fallback,
+        })
+    }
+}
+
+impl Default for OCRModelRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,55 @@
+use crate::ocr::OCRBackend;
+
+/// The intra-op threads of an OCR session, if not configured
+pub const DEFAULT_INTRA_THREADS: usize = 4;
+
+#[derive(Clone, clap::Args)]
+pub struct OCRConfig {
+    /// The library which runs the OCR model, onnxruntime if it is compiled
+    #[arg(id = "ocr-backend", long = "ocr-backend", help = "OCR 推理后端，默认为 ort（如已编译）", value_name = "BACKEND", value_enum)]
+    pub backend: Option<OCRBackend>,
+
+    /// The name of a built-in model, or the path of an onnx file with its dictionary next to it
+    #[arg(id = "ocr-model", long = "ocr-model", help = "OCR 模型：yas、ppocr，或 onnx 模型文件路径（字典为同目录下同名的 .json 或 .txt 文件）", value_name = "MODEL", default_value_t = String::from("yas"))]
+    pub model: String,
+
+    /// The model which recognizes again the regions whose text cannot be parsed
+    #[arg(id = "ocr-fallback-model", long = "ocr-fallback-model", help = "识别结果无法解析时改用的备用模型，取值同 --ocr-model", value_name = "MODEL")]
+    pub fallback_model: Option<String>,
+
+    /// The number of threads an OCR session uses for a single inference
+    #[arg(id = "ocr-threads", long = "ocr-threads", help = "OCR 推理线程数", value_name = "THREADS", default_value_t = DEFAULT_INTRA_THREADS)]
+    pub intra_threads: usize,
+
+    /// The max number of regions recognized in one inference, 1 disables batching
+    #[arg(id = "ocr-batch-size", long = "ocr-batch-size", help = "单次推理识别的最大区域数，为 1 时逐个区域识别", value_name = "SIZE", default_value_t = 16)]
+    pub batch_size: usize,
+
+    /// The number of OCR threads, each of which loads its own model
+    #[arg(id = "ocr-workers", long = "ocr-workers", help = "识别线程数，每个线程加载一份模型", value_name = "WORKERS", default_value_t = 1)]
+    pub workers: usize,
+
+    /// The max number of captured items waiting for OCR, capturing pauses when it is reached
+    #[arg(id = "queue-size", long = "queue-size", help = "等待识别的最大物品数，达到后暂停截图", value_name = "SIZE", default_value_t = 16)]
+    pub queue_size: usize,
+}
+
+impl Default for OCRConfig {
+    fn default() -> Self {
+        OCRConfig {
+            backend: None,
+            model: String::from("yas"),
+            fallback_model: None,
+            intra_threads: DEFAULT_INTRA_THREADS,
+            batch_size: 16,
+            workers: 1,
+            queue_size: 16,
+        }
+    }
+}
+
+impl OCRConfig {
+    pub fn backend(&self) -> OCRBackend {
+        self.backend.unwrap_or_default()
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+
+/// The environment variable of a directory of text regions captured from the games,
+/// e.g. the `low_confidence/` crops of a scan, which the backend parity tests recognize
+pub const OCR_SAMPLES_ENV: &str = "YAS_OCR_CROPS";
+
+/// The directory of `YAS_OCR_CROPS` if it is set, the synthetic fixtures otherwise
+pub fn ocr_samples_dir() -> PathBuf {
+    match std::env::var_os(OCR_SAMPLES_ENV) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/ocr"),
+    }
+}
+
+/// Every png or jpg in `dir`, sorted by file name and labelled by the file stem
+pub fn load_ocr_samples<P: AsRef<Path>>(dir: P) -> Result<Vec<(String, RgbImage)>> {
+    let dir = dir.as_ref();
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(|e| anyhow!("无法读取图片目录 {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "png" || ext == "jpg"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut samples = Vec::new();
+    for path in paths.iter() {
+        let image = image::open(path).map_err(|e| anyhow!("无法读取图片 {}: {}", path.display(), e))?;
+        let name = path.file_stem().map_or(String::new(), |s| s.to_string_lossy().to_string());
+        samples.push((name, image.to_rgb8()));
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/ocr");
+        let samples = load_ocr_samples(dir).unwrap();
+        let names = samples.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["crit_rate", "hp", "level", "sub_stat"]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "tract_onnx")]
+use tract_onnx::prelude::*;
+#[cfg(feature = "tract_onnx")]
+use tract_onnx::tract_hir::infer::InferenceOp;
+
+/// The library which runs the ONNX models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum OCRBackend {
+    /// onnxruntime, which needs the onnxruntime library at runtime
+    Ort,
+    /// tract, which is pure Rust
+    Tract,
+}
+
+impl Default for OCRBackend {
+    /// onnxruntime if it is compiled, otherwise tract
+    fn default() -> Self {
+        if cfg!(feature = "ort") {
+            OCRBackend::Ort
+        } else {
+            OCRBackend::Tract
+        }
+    }
+}
+
+impl OCRBackend {
+    pub fn is_compiled(self) -> bool {
+        match self {
+            OCRBackend::Ort => cfg!(feature = "ort"),
+            OCRBackend::Tract => cfg!(feature = "tract_onnx"),
+        }
+    }
+
+    /// Fails if the backend is not compiled
+    pub fn ensure_compiled(self) -> Result<()> {
+        if self.is_compiled() {
+            Ok(())
+        } else {
+            Err(self.not_compiled())
+        }
+    }
+
+    pub(crate) fn not_compiled(self) -> anyhow::Error {
+        anyhow!("OCR 后端 {:?} 未被编译，请启用对应的 feature", self)
+    }
+}
+
+#[cfg(feature = "tract_onnx")]
+pub type TractTypedModel = RunnableModel<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
+#[cfg(feature = "tract_onnx")]
+pub type TractInferenceModel = RunnableModel<InferenceFact, Box<dyn InferenceOp>, Graph<InferenceFact, Box<dyn InferenceOp>>>;
+
+/// A dense f32 tensor in row-major order
+#[derive(Debug, Clone)]
+pub struct OnnxTensor {
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+impl OnnxTensor {
+    pub fn new(shape: Vec<usize>, data: Vec<f32>) -> OnnxTensor {
+        assert_eq!(shape.iter().product::<usize>(), data.len());
+        OnnxTensor { shape, data }
+    }
+
+    pub fn from_shape_fn<F>(shape: &[usize], f: F) -> OnnxTensor where F: Fn(&[usize]) -> f32 {
+        let len = shape.iter().product::<usize>();
+        let mut index = vec![0; shape.len()];
+        let mut data = Vec::with_capacity(len);
+        for _ in 0..len {
+            data.push(f(&index));
+            // increase the index, the last axis changes the fastest
+            for axis in (0..shape.len()).rev() {
+                index[axis] += 1;
+                if index[axis] < shape[axis] {
+                    break;
+                }
+                index[axis] = 0;
+            }
+        }
+
+        OnnxTensor::new(shape.to_vec(), data)
+    }
+
+    pub fn get(&self, index: &[usize]) -> f32 {
+        let offset = index.iter()
+            .zip(self.shape.iter())
+            .fold(0, |acc, (&i, &size)| acc * size + i);
+        self.data[offset]
+    }
+}
+
+/// A loaded model of either backend, which takes one f32 tensor and outputs one
+pub enum OnnxSession {
+    #[cfg(feature = "ort")]
+    Ort(ort::Session),
+    #[cfg(feature = "tract_onnx")]
+    Tract(TractTypedModel),
+    /// for models whose shapes cannot be fully inferred before running
+    #[cfg(feature = "tract_onnx")]
+    TractInference(TractInferenceModel),
+}
+
+impl OnnxSession {
+    pub fn ort_from_memory(model: &[u8], intra_threads: usize) -> Result<OnnxSession> {
+        #[cfg(feature = "ort")]
+        {
+            let session = ort::Session::builder()?
+                .with_optimization_level(ort::GraphOptimizationLevel::Level3)?
+                .with_intra_threads(intra_threads.max(1))?
+                .commit_from_memory(model)?;
+            Ok(OnnxSession::Ort(session))
+        }
+        #[cfg(not(feature = "ort"))]
+        {
+            let _ = (model, intra_threads);
+            Err(OCRBackend::Ort.not_compiled())
+        }
+    }
+
+    pub fn ort_from_file<P: AsRef<Path>>(path: P, intra_threads: usize) -> Result<OnnxSession> {
+        #[cfg(feature = "ort")]
+        {
+            let session = ort::Session::builder()?
+                .with_optimization_level(ort::GraphOptimizationLevel::Level3)?
+                .with_intra_threads(intra_threads.max(1))?
+                .commit_from_file(path)?;
+            Ok(OnnxSession::Ort(session))
+        }
+        #[cfg(not(feature = "ort"))]
+        {
+            let _ = (path, intra_threads);
+            Err(OCRBackend::Ort.not_compiled())
+        }
+    }
+
+    pub fn backend(&self) -> OCRBackend {
+        match self {
+            #[cfg(feature = "ort")]
+            OnnxSession::Ort(_) => OCRBackend::Ort,
+            #[cfg(feature = "tract_onnx")]
+            OnnxSession::Tract(_) | OnnxSession::TractInference(_) => OCRBackend::Tract,
+        }
+    }
+
+    pub fn run(&self, input: OnnxTensor) -> Result<OnnxTensor> {
+        match self {
+            #[cfg(feature = "ort")]
+            OnnxSession::Ort(session) => {
+                let array = ndarray::ArrayD::from_shape_vec(input.shape, input.data)?;
+                let result = session.run(ort::inputs![array]?)?;
+                let output = result[0].try_extract_tensor::<f32>()?;
+                Ok(OnnxTensor::new(output.shape().to_vec(), output.iter().copied().collect()))
+            },
+            #[cfg(feature = "tract_onnx")]
+            OnnxSession::Tract(model) => {
+                let result = model.run(tvec!(Self::to_tract_tensor(input)?.into()))?;
+                Self::from_tract_tensor(&result[0])
+            },
+            #[cfg(feature = "tract_onnx")]
+            OnnxSession::TractInference(model) => {
+                let result = model.run(tvec!(Self::to_tract_tensor(input)?.into()))?;
+                Self::from_tract_tensor(&result[0])
+            },
+        }
+    }
+
+    #[cfg(feature = "tract_onnx")]
+    fn to_tract_tensor(input: OnnxTensor) -> Result<Tensor> {
+        Ok(tract_ndarray::ArrayD::from_shape_vec(input.shape, input.data)?.into())
+    }
+
+    #[cfg(feature = "tract_onnx")]
+    fn from_tract_tensor(output: &Tensor) -> Result<OnnxTensor> {
+        let view = output.to_array_view::<f32>()?;
+        Ok(OnnxTensor::new(view.shape().to_vec(), view.iter().copied().collect()))
+    }
+}
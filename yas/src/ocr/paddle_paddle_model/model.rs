@@ -1,38 +1,29 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::path::Path;
 use std::time::{Duration, SystemTime};
 use anyhow::Result;
 use image::{EncodableLayout, RgbImage};
+use log::warn;
 #[cfg(feature = "tract_onnx")]
 use tract_onnx::tract_hir::shapefactoid;
-use crate::ocr::ImageToText;
-use crate::ocr::paddle_paddle_model::preprocess::resize_img;
+use crate::ocr::{ImageToText, OCRBackend, OCRResult, DEFAULT_INTRA_THREADS};
+use crate::ocr::ctc::ctc_greedy_decode;
+use crate::ocr::constrained_decoding::{ctc_beam_search, TextConstraint, DEFAULT_BEAM_WIDTH};
+use crate::ocr::onnx_session::OnnxSession;
+use crate::ocr::paddle_paddle_model::preprocess::{normalize_images, resize_img};
 use crate::positioning::Shape3D;
 use crate::utils::read_file_to_string;
-#[cfg(feature = "ort")]
-use ort::GraphOptimizationLevel;
 #[cfg(feature = "tract_onnx")]
 use tract_onnx::prelude::*;
-#[cfg(feature = "tract_onnx")]
-use tract_onnx::tract_hir::infer::InferenceOp;
-
-#[cfg(feature = "tract_onnx")]
-use super::preprocess::normalize_image_to_tensor;
-#[cfg(feature = "ort")]
-use super::preprocess::normalize_image_to_ndarray;
-
-#[cfg(feature = "tract_onnx")]
-type ModelType = RunnableModel<InferenceFact, Box<dyn InferenceOp>, Graph<InferenceFact, Box<dyn InferenceOp>>>;
 
 pub struct PPOCRModel {
     index_to_word: Vec<String>,
-    #[cfg(feature = "tract_onnx")]
-    model: ModelType,
-    #[cfg(feature = "ort")]
-    model: ort::Session,
+    session: OnnxSession,
 
     inference_count: RefCell<usize>,
     inference_time: RefCell<Duration>,
+    /// cleared once a batch fails to run, after which the images are run one by one
+    batch_supported: Cell<bool>,
 }
 
 fn parse_index_to_word(s: &str, use_whitespace: bool) -> Vec<String> {
@@ -46,64 +37,76 @@ fn parse_index_to_word(s: &str, use_whitespace: bool) -> Vec<String> {
     result
 }
 
+/// The width of the input varies, so the model is not optimized
+#[cfg(feature = "tract_onnx")]
+fn load_tract_model(model: InferenceModel) -> Result<OnnxSession> {
+    let fact = InferenceFact::new().with_datum_type(DatumType::F32)
+        .with_shape(shapefactoid!(_, 3, _, _));
+
+    let model = model
+        .with_input_fact(0, fact)?
+        // .into_optimized()?
+        .into_runnable()?;
+    Ok(OnnxSession::TractInference(model))
+}
+
 impl PPOCRModel {
     pub fn new_from_file<P1, P2>(onnx_file: P1, words_file: P2) -> Result<PPOCRModel> where P1: AsRef<Path>, P2: AsRef<Path> {
+        Self::new_from_file_with_backend(onnx_file, words_file, OCRBackend::default(), DEFAULT_INTRA_THREADS)
+    }
+
+    pub fn new_from_file_with_backend<P1, P2>(onnx_file: P1, words_file: P2, backend: OCRBackend, intra_threads: usize) -> Result<PPOCRModel> where P1: AsRef<Path>, P2: AsRef<Path> {
         let words_str = std::fs::read_to_string(words_file)?;
         let index_to_word = parse_index_to_word(&words_str, true);
 
-        #[cfg(feature = "ort")]
-        let model = ort::Session::builder()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
-            .commit_from_file(onnx_file)?;
-
-        #[cfg(feature = "tract_onnx")]
-        let model = {
-            let fact = InferenceFact::new().with_datum_type(DatumType::F32)
-                .with_shape(shapefactoid!(_, 3, _, _));
-
-            tract_onnx::onnx()
-                .model_for_path(onnx_file)?
-                .with_input_fact(0, fact)?
-                // .into_optimized()?
-                .into_runnable()?
+        let session = match backend {
+            OCRBackend::Ort => OnnxSession::ort_from_file(onnx_file, intra_threads)?,
+            #[cfg(feature = "tract_onnx")]
+            OCRBackend::Tract => load_tract_model(tract_onnx::onnx().model_for_path(onnx_file)?)?,
+            #[cfg(not(feature = "tract_onnx"))]
+            OCRBackend::Tract => return Err(OCRBackend::Tract.not_compiled()),
         };
 
         Ok(Self {
             index_to_word,
-            model,
+            session,
             inference_count: RefCell::new(0),
             inference_time: RefCell::new(Duration::new(0, 0)),
+            batch_supported: Cell::new(true),
         })
     }
 
     pub fn new(onnx: &[u8], index_to_word: Vec<String>) -> Result<Self> {
-        #[cfg(feature = "ort")]
-        let model = ort::Session::builder()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
-            .commit_from_memory(onnx)?;
-
-        #[cfg(feature = "tract_onnx")]
-        let model = {
-            let fact = InferenceFact::new().with_datum_type(DatumType::F32)
-                .with_shape(shapefactoid!(_, 3, _, _));
-
-            tract_onnx::onnx()
-                .model_for_read(&mut onnx.as_bytes())?
-                .with_input_fact(0, fact)?
-                // .into_optimized()?
-                .into_runnable()?
+        Self::new_with_backend(onnx, index_to_word, OCRBackend::default(), DEFAULT_INTRA_THREADS)
+    }
+
+    pub fn new_with_intra_threads(onnx: &[u8], index_to_word: Vec<String>, intra_threads: usize) -> Result<Self> {
+        Self::new_with_backend(onnx, index_to_word, OCRBackend::default(), intra_threads)
+    }
+
+    /// `intra_threads` is ignored by tract, which runs in the calling thread
+    pub fn new_with_backend(onnx: &[u8], index_to_word: Vec<String>, backend: OCRBackend, intra_threads: usize) -> Result<Self> {
+        let session = match backend {
+            OCRBackend::Ort => OnnxSession::ort_from_memory(onnx, intra_threads)?,
+            #[cfg(feature = "tract_onnx")]
+            OCRBackend::Tract => load_tract_model(tract_onnx::onnx().model_for_read(&mut onnx.as_bytes())?)?,
+            #[cfg(not(feature = "tract_onnx"))]
+            OCRBackend::Tract => return Err(OCRBackend::Tract.not_compiled()),
         };
 
         Ok(Self {
             index_to_word,
-            model,
+            session,
             inference_count: RefCell::new(0),
             inference_time: RefCell::new(Duration::new(0, 0)),
+            batch_supported: Cell::new(true),
         })
     }
 
+    pub fn backend(&self) -> OCRBackend {
+        self.session.backend()
+    }
+
     pub fn get_average_inference_time(&self) -> Option<Duration> {
         if *self.inference_count.borrow() == 0 {
             None
@@ -116,91 +119,128 @@ impl PPOCRModel {
 }
 
 impl ImageToText<RgbImage> for PPOCRModel {
-    fn image_to_text(&self, image: &RgbImage, _is_preprocessed: bool) -> Result<String> {
-        let start_time = SystemTime::now();
+    fn image_to_text(&self, image: &RgbImage, is_preprocessed: bool) -> Result<String> {
+        Ok(self.image_to_text_with_confidence(image, is_preprocessed)?.text)
+    }
 
-        let resized_image = resize_img(Shape3D::new(3, 48, 320), image);
-
-        #[cfg(feature = "ort")]
-        let tensor = normalize_image_to_ndarray(&resized_image);
-        #[cfg(feature = "tract_onnx")]
-        let tensor = normalize_image_to_tensor(&resized_image);
-
-        #[cfg(feature = "ort")]
-        let result = self.model.run(ort::inputs![tensor]?)?;
-        #[cfg(feature = "tract_onnx")]
-        let result = self.model.run(tvec!(tensor.into()))?;
-
-        #[cfg(feature = "ort")]
-        let arr = result[0].try_extract_tensor()?;
-        #[cfg(feature = "tract_onnx")]
-        let arr = result[0].to_array_view::<f32>()?;
-
-        let shape = arr.shape();
-        // println!("{:?}", shape);
-
-        let mut text_index = Vec::new();
-
-        for i in 0..shape[1] {
-            let mut max_index = 0;
-            let mut max_value = -f32::INFINITY;
-            for j in 0..shape[2] {
-                let value = arr[[0, i, j]];
-                // println!("{}", value);
-                if value > max_value {
-                    max_value = value;
-                    max_index = j;
-                }
-            }
-            text_index.push(max_index);
-        }
+    fn image_to_text_with_confidence(&self, image: &RgbImage, _is_preprocessed: bool) -> Result<OCRResult> {
+        self.recognize(image, None)
+    }
 
-        let mut indices = Vec::new();
-        if text_index[0] != 0 {
-            indices.push(text_index[0]);
-        }
-        for i in 1..text_index.len() {
-            if text_index[i] != text_index[i - 1] && text_index[i] != 0 {
-                indices.push(text_index[i]);
-            }
-        }
+    fn image_to_text_constrained(&self, image: &RgbImage, _is_preprocessed: bool, constraint: &TextConstraint) -> Result<OCRResult> {
+        self.recognize(image, Some(constraint))
+    }
+
+    fn batch_image_to_text(&self, images: &[(&RgbImage, Option<&TextConstraint>)], _is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        self.recognize_batch(images)
+    }
+
+    fn get_average_inference_time(&self) -> Option<Duration> {
+        self.get_average_inference_time()
+    }
+}
+
+impl PPOCRModel {
+    fn recognize(&self, image: &RgbImage, constraint: Option<&TextConstraint>) -> Result<OCRResult> {
+        Ok(self.recognize_batch(&[(image, constraint)])?.remove(0))
+    }
 
-        let mut s = String::new();
-        for &index in indices.iter() {
-            s.push_str(&self.index_to_word[index - 1]);
+    /// The images are resized to the same height and run in one tensor, padded to the widest,
+    /// so the results may differ slightly from recognizing them one by one
+    fn recognize_batch(&self, images: &[(&RgbImage, Option<&TextConstraint>)]) -> Result<Vec<OCRResult>> {
+        if images.is_empty() {
+            return Ok(Vec::new());
         }
 
-        // println!("{:?}", text_index);
+        let start_time = SystemTime::now();
+
+        let resized_images = images.iter()
+            .map(|&(im, _)| resize_img(Shape3D::new(3, 48, 320), im))
+            .collect::<Vec<_>>();
+        let probs = self.run_batch(&resized_images)?;
 
-        // let s = format!("{:?}", shape);
+        // index 0 is the blank
+        let word = |i: usize| self.index_to_word[i - 1].as_str();
+        let results = probs.iter().zip(images.iter()).map(|(p, &(_, constraint))| match constraint {
+            Some(c) => ctc_beam_search(p, 0, word, c, DEFAULT_BEAM_WIDTH),
+            None => ctc_greedy_decode(p, 0, word),
+        }).collect();
 
         let elapsed_time = start_time.elapsed()?;
         *self.inference_time.borrow_mut() += elapsed_time;
-        *self.inference_count.borrow_mut() += 1;
+        *self.inference_count.borrow_mut() += images.len();
 
-        Ok(s)
+        Ok(results)
     }
 
-    fn get_average_inference_time(&self) -> Option<Duration> {
-        self.get_average_inference_time()
+    /// The softmaxed output of every image, falls back to one image per run if the model cannot batch
+    fn run_batch(&self, images: &[RgbImage]) -> Result<Vec<Vec<Vec<f32>>>> {
+        if images.len() > 1 && self.batch_supported.get() {
+            match self.run(images) {
+                Ok(v) => return Ok(v),
+                Err(e) => {
+                    warn!("OCR 模型不支持批量推理，改为逐个识别: {}", e);
+                    self.batch_supported.set(false);
+                }
+            }
+        }
+
+        let mut result = Vec::with_capacity(images.len());
+        for im in images.iter() {
+            result.extend(self.run(std::slice::from_ref(im))?);
+        }
+        Ok(result)
+    }
+
+    /// The output of the model is already softmaxed, of shape [N, T, C]
+    fn run(&self, images: &[RgbImage]) -> Result<Vec<Vec<Vec<f32>>>> {
+        let output = self.session.run(normalize_images(images))?;
+        let shape = &output.shape;
+
+        Ok((0..images.len()).map(|n| {
+            (0..shape[1]).map(|i| {
+                (0..shape[2]).map(|j| output.get(&[n, i, j])).collect::<Vec<f32>>()
+            }).collect::<Vec<_>>()
+        }).collect())
     }
 }
 
-pub macro ppocr_model($onnx:literal, $index_to_word:literal) {
-    {
-        let model_bytes = include_bytes!($onnx);
-        let index_to_word_str = include_str!($index_to_word);
+pub macro ppocr_model {
+    ($onnx:literal, $index_to_word:literal) => {
+        ppocr_model!($onnx, $index_to_word, DEFAULT_INTRA_THREADS)
+    },
+    ($onnx:literal, $index_to_word:literal, $intra_threads:expr) => {
+        {
+            let model_bytes = include_bytes!($onnx);
+            let index_to_word_str = include_str!($index_to_word);
+
+            let mut index_to_word_vec: Vec<String> = Vec::new();
+            for line in index_to_word_str.lines() {
+                index_to_word_vec.push(String::from(line));
+            }
+            index_to_word_vec.push(String::from(" "));
 
-        let mut index_to_word_vec: Vec<String> = Vec::new();
-        for line in index_to_word_str.lines() {
-            index_to_word_vec.push(String::from(line));
+            PPOCRModel::new_with_intra_threads(
+                model_bytes, index_to_word_vec, $intra_threads,
+            )
         }
-        index_to_word_vec.push(String::from(" "));
+    },
+    ($onnx:literal, $index_to_word:literal, $backend:expr, $intra_threads:expr) => {
+        {
+            let model_bytes = include_bytes!($onnx);
+            let index_to_word_str = include_str!($index_to_word);
+
+            let mut index_to_word_vec: Vec<String> = Vec::new();
+            for line in index_to_word_str.lines() {
+                index_to_word_vec.push(String::from(line));
+            }
+            index_to_word_vec.push(String::from(" "));
 
-        PPOCRModel::new(
-            model_bytes, index_to_word_vec,
-        )
-    }
+            PPOCRModel::new_with_backend(
+                model_bytes, index_to_word_vec, $backend, $intra_threads,
+            )
+        }
+    },
 }
 
 pub struct PPOCRChV4RecInfer {
@@ -209,8 +249,12 @@ pub struct PPOCRChV4RecInfer {
 
 impl PPOCRChV4RecInfer {
     pub fn new() -> Result<Self> {
+        Self::new_with_backend(OCRBackend::default(), DEFAULT_INTRA_THREADS)
+    }
+
+    pub fn new_with_backend(backend: OCRBackend, intra_threads: usize) -> Result<Self> {
         Ok(Self {
-            model: ppocr_model!("./ch_PP-OCRv4_rec_infer.onnx", "./ppocr_keys_v1.txt")?
+            model: ppocr_model!("./ch_PP-OCRv4_rec_infer.onnx", "./ppocr_keys_v1.txt", backend, intra_threads)?
         })
     }
 }
@@ -220,7 +264,59 @@ impl ImageToText<RgbImage> for PPOCRChV4RecInfer {
         self.model.image_to_text(image, is_preprocessed)
     }
 
+    fn image_to_text_with_confidence(&self, image: &RgbImage, is_preprocessed: bool) -> Result<OCRResult> {
+        self.model.image_to_text_with_confidence(image, is_preprocessed)
+    }
+
+    fn image_to_text_constrained(&self, image: &RgbImage, is_preprocessed: bool, constraint: &TextConstraint) -> Result<OCRResult> {
+        self.model.image_to_text_constrained(image, is_preprocessed, constraint)
+    }
+
+    fn batch_image_to_text(&self, images: &[(&RgbImage, Option<&TextConstraint>)], is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        self.model.batch_image_to_text(images, is_preprocessed)
+    }
+
     fn get_average_inference_time(&self) -> Option<Duration> {
         self.model.get_average_inference_time()
     }
 }
+
+#[cfg(all(test, feature = "ort", feature = "tract_onnx"))]
+mod tests {
+    use super::*;
+
+    use crate::ocr::{load_ocr_samples, ocr_samples_dir};
+
+    fn recognize_samples(backend: OCRBackend, samples: &[(String, RgbImage)]) -> Vec<OCRResult> {
+        let model = PPOCRChV4RecInfer::new_with_backend(backend, 1).unwrap();
+        samples.iter()
+            .map(|(_, image)| model.image_to_text_with_confidence(image, false).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_agrees_with_single() {
+        let model = PPOCRChV4RecInfer::new_with_backend(OCRBackend::Tract, 1).unwrap();
+        let samples = load_ocr_samples(ocr_samples_dir()).unwrap();
+
+        let inputs = samples.iter().map(|(_, im)| (im, None)).collect::<Vec<_>>();
+        let batch = model.batch_image_to_text(&inputs, false).unwrap();
+        for ((name, image), b) in samples.iter().zip(batch.iter()) {
+            let single = model.image_to_text_with_confidence(image, false).unwrap();
+            assert_eq!(single.text, b.text, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_backend_parity() {
+        let samples = load_ocr_samples(ocr_samples_dir()).unwrap();
+        assert!(!samples.is_empty());
+        let ort = recognize_samples(OCRBackend::Ort, &samples);
+        let tract = recognize_samples(OCRBackend::Tract, &samples);
+
+        for (((name, _), a), b) in samples.iter().zip(ort.iter()).zip(tract.iter()) {
+            assert_eq!(a.text, b.text, "{}", name);
+            assert!((a.confidence - b.confidence).abs() < 1e-3, "{}: {} {}", name, a.confidence, b.confidence);
+        }
+    }
+}
@@ -2,8 +2,7 @@ use image::{ImageBuffer, RgbImage};
 use crate::positioning::Shape3D;
 use anyhow::Result;
 use image::imageops::{FilterType, resize};
-#[cfg(feature = "tract_onnx")]
-use tract_onnx::prelude::{Tensor, tract_ndarray};
+use crate::ocr::onnx_session::OnnxTensor;
 
 /// Resize an image to the expected height, but the width can vary
 /// rec_image_shape: the expected shape to feed into the onnx model. CHW
@@ -20,33 +19,53 @@ pub fn resize_img(rec_image_shape: Shape3D<u32>, img: &RgbImage) -> RgbImage {
     resized_image
 }
 
-#[cfg(feature = "ort")]
-pub fn normalize_image_to_ndarray(img: &RgbImage) -> ndarray::Array4<f32> {
-    let height = img.height() as usize;
-    let width = img.width() as usize;
-    // let tensor: Tensor = tract_ndarray::Array4::from_shape_fn((1, 3, height, width), |(_, c, y, x)| {
-    //     let pix = img.get_pixel(x as u32, y as u32)[c];
-    //     let v = pix as f32 / 255.0_f32;
-    //     (v - 0.5) / 0.5
-    // }).into();
-    // tensor
-
-    let arr = ndarray::Array4::from_shape_fn((1, 3, height, width), |(_, c, y, x)| {
-        let pix = img.get_pixel(x as u32, y as u32)[c];
-        let v = pix as f32 / 255.0_f32;
-        (v - 0.5) / 0.5
-    });
-    arr
+/// Scale the pixels to [-1, 1], the tensor is of shape [1, 3, H, W]
+pub fn normalize_image(img: &RgbImage) -> OnnxTensor {
+    normalize_images(std::slice::from_ref(img))
 }
 
-#[cfg(feature = "tract_onnx")]
-pub fn normalize_image_to_tensor(img: &RgbImage) -> Tensor {
-    let height = img.height() as usize;
-    let width = img.width() as usize;
-    let tensor: Tensor = tract_ndarray::Array4::from_shape_fn((1, 3, height, width), |(_, c, y, x)| {
-        let pix = img.get_pixel(x as u32, y as u32)[c];
+/// Scale the pixels of images of the same height to [-1, 1], the tensor is of shape [N, 3, H, W]
+/// W is the widest width, narrower images are padded with 0 on the right, as PaddleOCR does
+pub fn normalize_images(images: &[RgbImage]) -> OnnxTensor {
+    let height = images.first().map_or(0, |im| im.height()) as usize;
+    let width = images.iter().map(|im| im.width()).max().unwrap_or(0) as usize;
+    OnnxTensor::from_shape_fn(&[images.len(), 3, height, width], |index| {
+        let img = &images[index[0]];
+        if index[3] >= img.width() as usize {
+            return 0.0;
+        }
+        let pix = img.get_pixel(index[3] as u32, index[2] as u32)[index[1]];
         let v = pix as f32 / 255.0_f32;
         (v - 0.5) / 0.5
-    }).into();
-    tensor
-}
\ No newline at end of file
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_images() {
+        let narrow = RgbImage::from_pixel(2, 4, Rgb([255, 0, 255]));
+        let wide = RgbImage::from_pixel(5, 4, Rgb([0, 255, 0]));
+        let tensor = normalize_images(&[narrow, wide]);
+
+        assert_eq!(tensor.shape, vec![2, 3, 4, 5]);
+        assert_eq!(tensor.get(&[0, 0, 3, 1]), 1.0);
+        assert_eq!(tensor.get(&[0, 1, 3, 1]), -1.0);
+        // padded on the right
+        assert_eq!(tensor.get(&[0, 0, 0, 2]), 0.0);
+        assert_eq!(tensor.get(&[1, 1, 0, 4]), 1.0);
+    }
+
+    #[test]
+    fn test_normalize_single_image() {
+        let image = RgbImage::from_pixel(3, 2, Rgb([0, 0, 0]));
+        let tensor = normalize_image(&image);
+
+        assert_eq!(tensor.shape, vec![1, 3, 2, 3]);
+        assert!(tensor.data.iter().all(|&v| v == -1.0));
+    }
+}
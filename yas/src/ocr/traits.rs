@@ -2,9 +2,51 @@ use std::time::Duration;
 
 use anyhow::Result;
 
+use crate::ocr::TextConstraint;
+
+/// Recognized text, along with how confident the model is about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OCRResult {
+    pub text: String,
+    /// the probability of each character in `text`
+    pub char_confidences: Vec<f32>,
+    /// the lowest probability among the characters
+    pub confidence: f32,
+}
+
+impl OCRResult {
+    /// For models which cannot tell the confidence
+    pub fn certain(text: String) -> OCRResult {
+        let count = text.chars().count();
+        OCRResult {
+            text,
+            char_confidences: vec![1.0; count],
+            confidence: 1.0,
+        }
+    }
+}
+
 pub trait ImageToText<ImageType> {
     fn image_to_text(&self, image: &ImageType, is_preprocessed: bool) -> Result<String>;
 
+    fn image_to_text_with_confidence(&self, image: &ImageType, is_preprocessed: bool) -> Result<OCRResult> {
+        Ok(OCRResult::certain(self.image_to_text(image, is_preprocessed)?))
+    }
+
+    /// Only yields text accepted by the constraint or empty text, an unlikely one is low in confidence
+    fn image_to_text_constrained(&self, image: &ImageType, is_preprocessed: bool, _constraint: &TextConstraint) -> Result<OCRResult> {
+        self.image_to_text_with_confidence(image, is_preprocessed)
+    }
+
+    /// Recognize several images, each with an optional constraint, the results are in the same order
+    /// models which support batch inference run them at once, others one by one
+    fn batch_image_to_text(&self, images: &[(&ImageType, Option<&TextConstraint>)], is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        images.iter().map(|&(image, constraint)| match constraint {
+            Some(c) => self.image_to_text_constrained(image, is_preprocessed, c),
+            None => self.image_to_text_with_confidence(image, is_preprocessed),
+        }).collect()
+    }
+
     fn get_average_inference_time(&self) -> Option<Duration>;
 }
 
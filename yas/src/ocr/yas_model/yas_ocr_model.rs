@@ -1,28 +1,59 @@
-use std::{cell::RefCell, time::Duration};
+use std::{cell::{Cell, RefCell}, time::Duration};
 use std::time::SystemTime;
 use image::{EncodableLayout, GrayImage, ImageBuffer, Luma, RgbImage};
+use log::warn;
 // use tract_onnx::prelude::*;
-use crate::ocr::traits::ImageToText;
+use crate::ocr::traits::{ImageToText, OCRResult};
+use crate::ocr::ctc::{ctc_greedy_decode, softmax};
+use crate::ocr::constrained_decoding::{ctc_beam_search, TextConstraint, DEFAULT_BEAM_WIDTH};
+use crate::ocr::onnx_session::{OCRBackend, OnnxSession, OnnxTensor};
+use crate::ocr::DEFAULT_INTRA_THREADS;
 use super::preprocess;
 use anyhow::Result;
 use crate::common::image_ext::*;
 #[cfg(feature = "tract_onnx")]
 use tract_onnx::prelude::*;
 
-#[cfg(feature = "tract_onnx")]
-type ModelType = RunnableModel<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
+type GrayF32Image = ImageBuffer<Luma<f32>, Vec<f32>>;
 
 pub struct YasOCRModel {
-    #[cfg(feature = "ort")]
-    model: ort::Session,
-    #[cfg(feature = "tract_onnx")]
-    model: ModelType,
+    session: OnnxSession,
     index_to_word: Vec<String>,
+    /// whether an inference can take more than one image, it is turned off once a batch fails
+    batch_supported: Cell<bool>,
 
     inference_time: RefCell<Duration>,   // in seconds
     invoke_count: RefCell<usize>,
 }
 
+/// Load the model with a symbolic batch size, or a batch size of 1 if the model does not allow it
+#[cfg(feature = "tract_onnx")]
+fn load_tract_model(model: &[u8]) -> Result<(OnnxSession, bool)> {
+    let batched = tract_onnx::onnx()
+        .model_for_read(&mut model.as_bytes())
+        .and_then(|m| {
+            let batch = m.symbol_table.sym("N");
+            m.with_input_fact(0, f32::fact([batch.to_dim(), 1.to_dim(), 32.to_dim(), 384.to_dim()]).into())?
+                .into_optimized()?
+                .into_runnable()
+        });
+    if let Ok(m) = batched {
+        return Ok((OnnxSession::Tract(m), true));
+    }
+
+    let m = tract_onnx::onnx()
+        .model_for_read(&mut model.as_bytes())?
+        .with_input_fact(0, f32::fact([1, 1, 32, 384]).into())?
+        .into_optimized()?
+        .into_runnable()?;
+    Ok((OnnxSession::Tract(m), false))
+}
+
+#[cfg(not(feature = "tract_onnx"))]
+fn load_tract_model(_model: &[u8]) -> Result<(OnnxSession, bool)> {
+    Err(OCRBackend::Tract.not_compiled())
+}
+
 impl YasOCRModel {
     pub fn get_average_inference_time(&self) -> Option<Duration> {
         let count = *self.invoke_count.borrow();
@@ -36,17 +67,19 @@ impl YasOCRModel {
     }
 
     pub fn new(model: &[u8], content: &str) -> Result<YasOCRModel> {
-        #[cfg(feature = "ort")]
-        let model = ort::Session::builder()?
-            .with_optimization_level(ort::GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
-            .commit_from_memory(model)?;
-        #[cfg(feature = "tract_onnx")]
-        let model = tract_onnx::onnx()
-            .model_for_read(&mut model.as_bytes())?
-            .with_input_fact(0, f32::fact([1, 1, 32, 384]).into())?
-            .into_optimized()?
-            .into_runnable()?;
+        Self::new_with_backend(model, content, OCRBackend::default(), DEFAULT_INTRA_THREADS)
+    }
+
+    pub fn new_with_intra_threads(model: &[u8], content: &str, intra_threads: usize) -> Result<YasOCRModel> {
+        Self::new_with_backend(model, content, OCRBackend::default(), intra_threads)
+    }
+
+    /// `intra_threads` is ignored by tract, which runs in the calling thread
+    pub fn new_with_backend(model: &[u8], content: &str, backend: OCRBackend, intra_threads: usize) -> Result<YasOCRModel> {
+        let (session, batch_supported) = match backend {
+            OCRBackend::Ort => (OnnxSession::ort_from_memory(model, intra_threads)?, true),
+            OCRBackend::Tract => load_tract_model(model)?,
+        };
 
         let json = serde_json::from_str::<serde_json::Value>(content)?;
 
@@ -62,81 +95,154 @@ impl YasOCRModel {
         let index_to_word = index_to_word.into_iter().map(|(_, v)| v).collect();
 
         Ok(YasOCRModel {
-            model,
+            session,
             index_to_word,
+            batch_supported: Cell::new(batch_supported),
             inference_time: RefCell::new(Duration::new(0, 0)),
             invoke_count: RefCell::new(0),
         })
     }
 
-    pub fn inference_string(&self, img: &ImageBuffer<Luma<f32>, Vec<f32>>) -> Result<String> {
-        let now = SystemTime::now();
+    pub fn backend(&self) -> OCRBackend {
+        self.session.backend()
+    }
 
-        #[cfg(feature = "ort")]
-        let tensor = ndarray::Array4::from_shape_fn((1, 1, 32, 384), |(_, _, y, x)| {
-            img.get_pixel(x as u32, y as u32)[0]
-        });
-        #[cfg(feature = "tract_onnx")]
-        let tensor: Tensor =
-            tract_ndarray::Array4::from_shape_fn((1, 1, 32, 384), |(_, _, y, x)| {
-                img.get_pixel(x as u32, y as u32)[0]
-            }).into();
-
-        #[cfg(feature = "ort")]
-        let result = self.model.run(ort::inputs![tensor]?)?;
-        #[cfg(feature = "tract_onnx")]
-        let result = self.model.run(tvec!(tensor.into()))?;
-
-        #[cfg(feature = "ort")]
-        let arr = result[0].try_extract_tensor()?;
-        #[cfg(feature = "tract_onnx")]
-        let arr = result[0].to_array_view::<f32>()?;
-
-        let shape = arr.shape();
-
-        let mut ans = String::new();
-        let mut last_word = String::new();
-        for i in 0..shape[0] {
-            let mut max_index = 0;
-            let mut max_value = -1.0_f32;
-            for j in 0..self.index_to_word.len() {
-                let value = arr[[i, 0, j]];
-                if value > max_value {
-                    max_value = value;
-                    max_index = j;
-                }
-            }
-            let word = &self.index_to_word[max_index];
-            if *word != last_word && word != "-" {
-                ans = ans + word;
-            }
+    pub fn inference_string(&self, img: &GrayF32Image) -> Result<String> {
+        Ok(self.inference(img, None)?.text)
+    }
 
-            last_word.clone_from(word);
+    /// Decode greedily, or with a beam search if there is a constraint
+    pub fn inference(&self, img: &GrayF32Image, constraint: Option<&TextConstraint>) -> Result<OCRResult> {
+        Ok(self.batch_inference(&[(img, constraint)])?.pop().unwrap())
+    }
+
+    /// Recognize preprocessed images in one tensor of shape [N, 1, 32, 384]
+    pub fn batch_inference(&self, images: &[(&GrayF32Image, Option<&TextConstraint>)]) -> Result<Vec<OCRResult>> {
+        if images.is_empty() {
+            return Ok(Vec::new());
         }
 
+        let now = SystemTime::now();
+
+        let tensors = images.iter().map(|&(im, _)| im).collect::<Vec<_>>();
+        let probs = self.run_batch(&tensors)?;
+
+        let blank = self.index_to_word.iter().position(|w| w == "-").unwrap_or(usize::MAX);
+        let word = |i: usize| self.index_to_word[i].as_str();
+        let ans = probs.iter().zip(images.iter()).map(|(p, &(_, constraint))| match constraint {
+            Some(c) => ctc_beam_search(p, blank, word, c, DEFAULT_BEAM_WIDTH),
+            None => ctc_greedy_decode(p, blank, word),
+        }).collect::<Vec<_>>();
+
         let time = now.elapsed()?;
-        
-        *self.invoke_count.borrow_mut() += 1;
+
+        *self.invoke_count.borrow_mut() += images.len();
         *self.inference_time.borrow_mut() += time;
 
         Ok(ans)
     }
+
+    /// The softmaxed output of every image, falls back to one image per run if the model cannot batch
+    fn run_batch(&self, images: &[&GrayF32Image]) -> Result<Vec<Vec<Vec<f32>>>> {
+        if images.len() > 1 && self.batch_supported.get() {
+            match self.run(images) {
+                Ok(v) => return Ok(v),
+                Err(e) => {
+                    warn!("OCR 模型不支持批量推理，改为逐个识别: {}", e);
+                    self.batch_supported.set(false);
+                }
+            }
+        }
+
+        let mut result = Vec::with_capacity(images.len());
+        for &im in images.iter() {
+            result.extend(self.run(&[im])?);
+        }
+        Ok(result)
+    }
+
+    fn run(&self, images: &[&GrayF32Image]) -> Result<Vec<Vec<Vec<f32>>>> {
+        let n = images.len();
+
+        let tensor = OnnxTensor::from_shape_fn(&[n, 1, 32, 384], |index| {
+            images[index[0]].get_pixel(index[3] as u32, index[2] as u32)[0]
+        });
+        let output = self.session.run(tensor)?;
+
+        // the output is of shape [T, N, C]
+        let shape = &output.shape;
+        anyhow::ensure!(shape.len() == 3 && shape[1] == n, "unexpected output shape {:?}", shape);
+
+        let probs = (0..n).map(|k| {
+            (0..shape[0]).map(|i| {
+                let mut step = (0..self.index_to_word.len()).map(|j| output.get(&[i, k, j])).collect::<Vec<f32>>();
+                softmax(&mut step);
+                step
+            }).collect::<Vec<_>>()
+        }).collect();
+
+        Ok(probs)
+    }
+
+    fn recognize(
+        &self,
+        image: &GrayF32Image,
+        is_preprocessed: bool,
+        constraint: Option<&TextConstraint>
+    ) -> Result<OCRResult> {
+        Ok(self.recognize_batch(&[(image, constraint)], is_preprocessed)?.pop().unwrap())
+    }
+
+    /// Mono images are blank and recognized as empty without running the model
+    fn recognize_batch(
+        &self,
+        images: &[(&GrayF32Image, Option<&TextConstraint>)],
+        is_preprocessed: bool
+    ) -> Result<Vec<OCRResult>> {
+        if is_preprocessed {
+            return self.batch_inference(images);
+        }
+
+        let preprocessed = images.iter()
+            .map(|&(im, _)| preprocess::pre_process(im.clone()))
+            .collect::<Vec<_>>();
+        let inputs = preprocessed.iter()
+            .zip(images.iter())
+            .filter(|((_, non_mono), _)| *non_mono)
+            .map(|((im, _), &(_, constraint))| (im, constraint))
+            .collect::<Vec<_>>();
+
+        let mut recognized = self.batch_inference(&inputs)?.into_iter();
+        Ok(preprocessed.iter().map(|(_, non_mono)| {
+            if *non_mono {
+                recognized.next().unwrap()
+            } else {
+                OCRResult::certain(String::new())
+            }
+        }).collect())
+    }
 }
 
 impl ImageToText<RgbImage> for YasOCRModel {
     fn image_to_text(&self, image: &RgbImage, is_preprocessed: bool) -> Result<String> {
-        assert!(!is_preprocessed);
-
-        let gray_image_float = preprocess::to_gray(image);
-        let (result, non_mono) = preprocess::pre_process(gray_image_float);
+        Ok(self.image_to_text_with_confidence(image, is_preprocessed)?.text)
+    }
 
-        if !non_mono {
-            return Ok(String::new());
-        }
+    fn image_to_text_with_confidence(&self, image: &RgbImage, is_preprocessed: bool) -> Result<OCRResult> {
+        assert!(!is_preprocessed);
+        self.recognize(&preprocess::to_gray(image), false, None)
+    }
 
-        let string_result = self.inference_string(&result)?;
+    fn image_to_text_constrained(&self, image: &RgbImage, is_preprocessed: bool, constraint: &TextConstraint) -> Result<OCRResult> {
+        assert!(!is_preprocessed);
+        self.recognize(&preprocess::to_gray(image), false, Some(constraint))
+    }
 
-        Ok(string_result)
+    fn batch_image_to_text(&self, images: &[(&RgbImage, Option<&TextConstraint>)], is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        assert!(!is_preprocessed);
+        let gray_images = images.iter().map(|&(im, _)| preprocess::to_gray(im)).collect::<Vec<_>>();
+        let inputs = gray_images.iter().zip(images.iter()).map(|(im, &(_, c))| (im, c)).collect::<Vec<_>>();
+        self.recognize_batch(&inputs, false)
     }
 
     fn get_average_inference_time(&self) -> Option<Duration> {
@@ -144,22 +250,21 @@ impl ImageToText<RgbImage> for YasOCRModel {
     }
 }
 
-impl ImageToText<ImageBuffer<Luma<f32>, Vec<f32>>> for YasOCRModel {
-    fn image_to_text(&self, image: &ImageBuffer<Luma<f32>, Vec<f32>>, is_preprocessed: bool) -> Result<String> {
-        if is_preprocessed {
-            let string_result = self.inference_string(image)?;
-            Ok(string_result)
-        } else {
-            let im = image.clone();
-            let (preprocess_result, non_mono) = preprocess::pre_process(im);
+impl ImageToText<GrayF32Image> for YasOCRModel {
+    fn image_to_text(&self, image: &GrayF32Image, is_preprocessed: bool) -> Result<String> {
+        Ok(self.image_to_text_with_confidence(image, is_preprocessed)?.text)
+    }
 
-            if !non_mono {
-                return Ok(String::new());
-            }
+    fn image_to_text_with_confidence(&self, image: &GrayF32Image, is_preprocessed: bool) -> Result<OCRResult> {
+        self.recognize(image, is_preprocessed, None)
+    }
 
-            let string_result = self.inference_string(&preprocess_result)?;
-            Ok(string_result)
-        }
+    fn image_to_text_constrained(&self, image: &GrayF32Image, is_preprocessed: bool, constraint: &TextConstraint) -> Result<OCRResult> {
+        self.recognize(image, is_preprocessed, Some(constraint))
+    }
+
+    fn batch_image_to_text(&self, images: &[(&GrayF32Image, Option<&TextConstraint>)], is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        self.recognize_batch(images, is_preprocessed)
     }
 
     fn get_average_inference_time(&self) -> Option<Duration> {
@@ -169,22 +274,58 @@ impl ImageToText<ImageBuffer<Luma<f32>, Vec<f32>>> for YasOCRModel {
 
 impl ImageToText<GrayImage> for YasOCRModel {
     fn image_to_text(&self, im: &GrayImage, is_preprocessed: bool) -> Result<String> {
-        let gray_f32_image: ImageBuffer<Luma<f32>, Vec<f32>> = im.to_f32_gray_image();
+        let gray_f32_image: GrayF32Image = im.to_f32_gray_image();
         self.image_to_text(&gray_f32_image, is_preprocessed)
     }
 
+    fn image_to_text_with_confidence(&self, im: &GrayImage, is_preprocessed: bool) -> Result<OCRResult> {
+        self.recognize(&im.to_f32_gray_image(), is_preprocessed, None)
+    }
+
+    fn image_to_text_constrained(&self, im: &GrayImage, is_preprocessed: bool, constraint: &TextConstraint) -> Result<OCRResult> {
+        self.recognize(&im.to_f32_gray_image(), is_preprocessed, Some(constraint))
+    }
+
+    fn batch_image_to_text(&self, images: &[(&GrayImage, Option<&TextConstraint>)], is_preprocessed: bool) -> Result<Vec<OCRResult>> {
+        let gray_images = images.iter().map(|&(im, _)| im.to_f32_gray_image()).collect::<Vec<_>>();
+        let inputs = gray_images.iter().zip(images.iter()).map(|(im, &(_, c))| (im, c)).collect::<Vec<_>>();
+        self.recognize_batch(&inputs, is_preprocessed)
+    }
+
     fn get_average_inference_time(&self) -> Option<Duration> {
         self.get_average_inference_time()
     }
 }
 
-pub macro yas_ocr_model($model_name:literal, $index_to_word:literal) {
-    {
-        let model_bytes = include_bytes!($model_name);
-        let index_to_word = include_str!($index_to_word);
+pub macro yas_ocr_model {
+    ($model_name:literal, $index_to_word:literal) => {
+        {
+            let model_bytes = include_bytes!($model_name);
+            let index_to_word = include_str!($index_to_word);
 
-        YasOCRModel::new(
-            model_bytes, index_to_word,
-        )
-    }
+            YasOCRModel::new(
+                model_bytes, index_to_word,
+            )
+        }
+    },
+    ($model_name:literal, $index_to_word:literal, $intra_threads:expr) => {
+        {
+            let model_bytes = include_bytes!($model_name);
+            let index_to_word = include_str!($index_to_word);
+
+            YasOCRModel::new_with_intra_threads(
+                model_bytes, index_to_word, $intra_threads,
+            )
+        }
+    },
+    ($model_name:literal, $index_to_word:literal, $backend:expr, $intra_threads:expr) => {
+        {
+            let model_bytes = include_bytes!($model_name);
+            let index_to_word = include_str!($index_to_word);
+
+            YasOCRModel::new_with_backend(
+                model_bytes, index_to_word, $backend, $intra_threads,
+            )
+        }
+    },
 }
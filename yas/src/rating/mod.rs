@@ -0,0 +1,9 @@
+mod weight_profile;
+mod rateable;
+mod rating_report;
+mod rating_config;
+
+pub use weight_profile::WeightProfile;
+pub use rateable::Rateable;
+pub use rating_report::{ProfileRanking, RatedItem, RatingReport};
+pub use rating_config::RatingConfig;
@@ -0,0 +1,14 @@
+/// An item which can be scored against weight profiles, e.g. an artifact
+pub trait Rateable {
+    /// The stats the weights apply to, by their keys in weight profiles, valued in the highest rolls they are worth
+    fn rated_stats(&self) -> Vec<(String, f64)>;
+
+    /// All the stat keys of the game, a key in weight profiles which is not one of them is likely misspelled
+    fn stat_keys() -> Vec<String> where Self: Sized;
+
+    /// Crit rate counted twice plus crit damage, in %
+    fn crit_value(&self) -> f64;
+
+    /// How the item is shown in the rating report
+    fn describe(&self) -> String;
+}
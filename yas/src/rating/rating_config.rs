@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+use crate::rating::{Rateable, WeightProfile};
+
+#[derive(Clone, clap::Args)]
+pub struct RatingConfig {
+    /// A YAML file of weight profiles, the items are scored against each of them
+    #[arg(id = "rating-profiles", long = "rating-profiles", help = "评分权重配置文件（YAML），按其中每个角色的权重为物品评分", value_name = "FILE")]
+    pub profiles: Option<String>,
+
+    /// How many of the highest scored items of each profile are in the report, 0 for all
+    #[arg(id = "rating-top", long = "rating-top", help = "评分报告中每个角色列出的物品数量，为 0 时列出全部", value_name = "COUNT", default_value_t = 20)]
+    pub top: usize,
+}
+
+impl Default for RatingConfig {
+    fn default() -> Self {
+        RatingConfig {
+            profiles: None,
+            top: 20,
+        }
+    }
+}
+
+impl RatingConfig {
+    /// The profiles in the file for items of `T`, none if it is not given
+    pub fn load_profiles<T: Rateable>(&self) -> Result<Vec<WeightProfile>> {
+        match self.profiles.as_ref() {
+            Some(path) => WeightProfile::load_file(path, &T::stat_keys()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use log::info;
+use serde::Serialize;
+
+use crate::export::ExportAssets;
+use crate::rating::{Rateable, WeightProfile};
+
+/// How many items of each profile are logged, the rest are in the report file
+const LOGGED_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RatedItem {
+    /// the position of the item in the exported items, starting from 0
+    pub index: usize,
+    pub score: f64,
+    pub crit_value: f64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileRanking {
+    pub profile: String,
+    /// from the highest score to the lowest
+    pub items: Vec<RatedItem>,
+}
+
+/// The items ranked by their scores against each weight profile
+#[derive(Debug, Clone, Default)]
+pub struct RatingReport {
+    pub rankings: Vec<ProfileRanking>,
+}
+
+impl RatingReport {
+    /// Rank the items against every profile, only the `top` highest of each are kept, 0 keeps all
+    pub fn new<T: Rateable>(profiles: &[WeightProfile], items: &[T], top: usize) -> RatingReport {
+        let rankings = profiles.iter().map(|profile| {
+            let mut rated = items.iter().enumerate()
+                .map(|(index, item)| RatedItem {
+                    index,
+                    score: profile.score(item),
+                    crit_value: item.crit_value(),
+                    description: item.describe(),
+                })
+                .collect::<Vec<_>>();
+            rated.sort_by(|a, b| b.score.total_cmp(&a.score));
+            if top > 0 {
+                rated.truncate(top);
+            }
+
+            ProfileRanking {
+                profile: profile.name.clone(),
+                items: rated,
+            }
+        }).collect();
+
+        RatingReport {
+            rankings,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rankings.is_empty()
+    }
+
+    pub fn log(&self) {
+        for ranking in self.rankings.iter() {
+            info!("{} 评分最高的物品：", ranking.profile);
+            for (rank, item) in ranking.items.iter().take(LOGGED_ITEMS).enumerate() {
+                info!("{}. {:.2} 分，暴击值 {:.1}，{}", rank + 1, item.score, item.crit_value, item.description);
+            }
+        }
+    }
+
+    /// Add `ratings.json` to the export assets
+    pub fn emit_to(&self, output_dir: &Path, export_assets: &mut ExportAssets) {
+        if self.rankings.is_empty() {
+            return;
+        }
+
+        let contents = serde_json::to_string_pretty(&self.rankings).unwrap();
+        export_assets.add_asset(
+            Some(String::from("ratings")),
+            output_dir.join("ratings.json"),
+            contents.into_bytes(),
+            Some(String::from("按角色权重的评分排名")),
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use log::warn;
+use serde::Deserialize;
+
+use crate::rating::Rateable;
+
+/// The weights of stats for a character, a roll of a stat with weight 1 scores 1
+///
+/// profiles are listed in a YAML file, e.g.
+/// ```yaml
+/// - name: 胡桃
+///   weights:
+///     critRate_: 1
+///     critDMG_: 1
+///     hp_: 0.8
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeightProfile {
+    pub name: String,
+    /// by the stat keys of the game, stats which are not listed weigh 0
+    pub weights: HashMap<String, f64>,
+}
+
+impl WeightProfile {
+    /// `stat_keys` are the known keys, the unknown ones in the file are warned about, as they always weigh 0
+    pub fn load_file<P: AsRef<Path>>(path: P, stat_keys: &[String]) -> Result<Vec<WeightProfile>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("无法读取评分权重配置 {}: {}", path.display(), e))?;
+        let profiles: Vec<WeightProfile> = serde_yaml::from_str(&contents)
+            .map_err(|e| anyhow!("评分权重配置 {} 格式错误: {}", path.display(), e))?;
+
+        for profile in profiles.iter() {
+            let unknown_keys = profile.unknown_keys(stat_keys);
+            if !unknown_keys.is_empty() {
+                warn!("评分权重配置 {} 中未知的属性: {}，这些属性的权重将被视为 0", profile.name, unknown_keys.join(", "));
+                warn!("可用的属性: {}", stat_keys.join(", "));
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// The keys of the weights which are not in `stat_keys`, sorted
+    pub fn unknown_keys(&self, stat_keys: &[String]) -> Vec<&str> {
+        let mut keys = self.weights.keys()
+            .filter(|key| !stat_keys.contains(key))
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// The weight of the stat of `key`, 0 if it is not listed
+    pub fn weight(&self, key: &str) -> f64 {
+        self.weights.get(key).copied().unwrap_or(0.0)
+    }
+
+    /// The sum of the weighted rolls of the item
+    pub fn score<T: Rateable>(&self, item: &T) -> f64 {
+        item.rated_stats().iter()
+            .map(|(key, rolls)| self.weight(key) * rolls)
+            .sum()
+    }
+}
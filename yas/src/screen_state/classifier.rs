@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use image::RgbImage;
+use log::info;
+
+use crate::ocr::ImageToText;
+use crate::screen_state::{ScreenProbe, ScreenState};
+
+/// A screen state is recognized if all of its probes match
+#[derive(Debug, Clone)]
+pub struct ScreenStateRule {
+    pub state: ScreenState,
+    pub probes: Vec<ScreenProbe>,
+}
+
+/// Classifies a captured window frame, rules are tried in the order they are added
+pub struct ScreenStateClassifier {
+    rules: Vec<ScreenStateRule>,
+}
+
+impl ScreenStateClassifier {
+    /// The default max standard deviation of luma for a frame to be considered as a loading screen
+    pub const LOADING_MAX_STD_DEV: f64 = 3.0;
+
+    pub fn new() -> Self {
+        ScreenStateClassifier {
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn add_rule(&mut self, state: ScreenState, probes: Vec<ScreenProbe>) -> &mut Self {
+        self.rules.push(ScreenStateRule {
+            state,
+            probes,
+        });
+        self
+    }
+
+    pub fn add_loading_rule(&mut self) -> &mut Self {
+        self.add_rule(ScreenState::Loading, vec![ScreenProbe::Uniform {
+            max_std_dev: Self::LOADING_MAX_STD_DEV,
+        }])
+    }
+
+    pub fn classify(&self, frame: &RgbImage, ocr: &dyn ImageToText<RgbImage>) -> Result<ScreenState> {
+        'rule: for rule in self.rules.iter() {
+            for probe in rule.probes.iter() {
+                if !probe.matches(frame, ocr)? {
+                    continue 'rule;
+                }
+            }
+            return Ok(rule.state);
+        }
+
+        Ok(ScreenState::Unknown)
+    }
+
+    /// Returns an error with a readable message if the frame is not in the expected state
+    pub fn ensure(&self, frame: &RgbImage, ocr: &dyn ImageToText<RgbImage>, expected: ScreenState) -> Result<()> {
+        let state = self.classify(frame, ocr)?;
+        info!("当前界面：{}", state);
+
+        if state == expected {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "当前界面为{}，请切换到{}后再开始扫描（可使用 --skip-screen-check 跳过检查）",
+                state, expected
+            ))
+        }
+    }
+}
+
+impl Default for ScreenStateClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
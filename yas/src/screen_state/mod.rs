@@ -0,0 +1,7 @@
+mod screen_state;
+mod probe;
+mod classifier;
+
+pub use screen_state::ScreenState;
+pub use probe::ScreenProbe;
+pub use classifier::{ScreenStateClassifier, ScreenStateRule};
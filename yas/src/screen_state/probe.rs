@@ -0,0 +1,63 @@
+use anyhow::Result;
+use image::RgbImage;
+
+use crate::ocr::ImageToText;
+use crate::positioning::Rect;
+use crate::utils::crop_rect;
+
+/// A single check performed on a captured frame, all positions are relative to the window
+#[derive(Debug, Clone)]
+pub enum ScreenProbe {
+    /// OCR the region, and match if the text contains any of the anchors
+    Text {
+        rect: Rect<f64>,
+        anchors: Vec<String>,
+    },
+    /// Match if the whole frame is (almost) a single color, e.g. a loading screen
+    Uniform {
+        max_std_dev: f64,
+    },
+}
+
+impl ScreenProbe {
+    pub fn text(rect: Rect<f64>, anchors: &[&str]) -> Self {
+        ScreenProbe::Text {
+            rect,
+            anchors: anchors.iter().map(|x| String::from(*x)).collect(),
+        }
+    }
+
+    pub fn matches(&self, frame: &RgbImage, ocr: &dyn ImageToText<RgbImage>) -> Result<bool> {
+        match self {
+            ScreenProbe::Text { rect, anchors } => {
+                let im = match crop_rect(frame, rect) {
+                    Some(im) => im,
+                    None => return Ok(false),
+                };
+                let text = ocr.image_to_text(&im, false)?;
+                Ok(anchors.iter().any(|anchor| text.contains(anchor.as_str())))
+            },
+            ScreenProbe::Uniform { max_std_dev } => {
+                Ok(luma_std_dev(frame) <= *max_std_dev)
+            },
+        }
+    }
+}
+
+fn luma_std_dev(frame: &RgbImage) -> f64 {
+    let count = (frame.width() * frame.height()) as f64;
+    if count == 0.0 {
+        return 0.0;
+    }
+
+    let mut sum = 0.0;
+    let mut sum2 = 0.0;
+    for p in frame.pixels() {
+        let luma = p[0] as f64 * 0.299 + p[1] as f64 * 0.587 + p[2] as f64 * 0.114;
+        sum += luma;
+        sum2 += luma * luma;
+    }
+
+    let mean = sum / count;
+    (sum2 / count - mean * mean).max(0.0).sqrt()
+}
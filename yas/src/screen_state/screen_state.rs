@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// What the game window is currently showing
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum ScreenState {
+    ArtifactInventory,
+    RelicInventory,
+    EchoInventory,
+    Character,
+    Loading,
+    Dialog,
+    Unknown,
+}
+
+impl ScreenState {
+    pub fn to_zh_cn(&self) -> &'static str {
+        match *self {
+            ScreenState::ArtifactInventory => "背包圣遗物界面",
+            ScreenState::RelicInventory => "背包遗器界面",
+            ScreenState::EchoInventory => "背包声骸界面",
+            ScreenState::Character => "角色界面",
+            ScreenState::Loading => "加载界面",
+            ScreenState::Dialog => "弹窗",
+            ScreenState::Unknown => "未知界面",
+        }
+    }
+}
+
+impl fmt::Display for ScreenState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_zh_cn())
+    }
+}
@@ -0,0 +1,5 @@
+mod sort_order;
+mod sort_order_switcher;
+
+pub use sort_order::SortOrder;
+pub use sort_order_switcher::{SortOrderLayout, SortOrderSwitcher};
@@ -0,0 +1,68 @@
+use clap::ValueEnum;
+
+use crate::game_language::GameLanguage;
+
+/// The sort order of an inventory list, items are assumed to be in descending order
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum SortOrder {
+    /// Sorted by star (rarity) first
+    Rarity,
+    /// Sorted by level first
+    Level,
+    /// Sorted by the time the item is obtained
+    Time,
+}
+
+impl SortOrder {
+    /// Parse the OCR result of a sort dropdown, e.g. "品质顺序", "等级顺序", "获取时间"
+    pub fn from_zh_cn(s: &str) -> Option<SortOrder> {
+        if s.contains("品质") || s.contains("稀有度") {
+            Some(SortOrder::Rarity)
+        } else if s.contains("等级") {
+            Some(SortOrder::Level)
+        } else if s.contains("时间") || s.contains("获取") {
+            Some(SortOrder::Time)
+        } else {
+            None
+        }
+    }
+
+    /// Parse the OCR result of a sort dropdown, e.g. "Sort by Rarity", "Sort by Level", "Time Acquired"
+    pub fn from_en(s: &str) -> Option<SortOrder> {
+        let s = s.to_lowercase();
+        if s.contains("rarity") || s.contains("quality") {
+            Some(SortOrder::Rarity)
+        } else if s.contains("level") {
+            Some(SortOrder::Level)
+        } else if s.contains("time") || s.contains("acquired") {
+            Some(SortOrder::Time)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_text(s: &str, language: GameLanguage) -> Option<SortOrder> {
+        match language {
+            GameLanguage::ZhCn => SortOrder::from_zh_cn(s),
+            GameLanguage::En => SortOrder::from_en(s),
+        }
+    }
+
+    pub fn to_zh_cn(&self) -> &'static str {
+        match *self {
+            SortOrder::Rarity => "品质顺序",
+            SortOrder::Level => "等级顺序",
+            SortOrder::Time => "获取时间",
+        }
+    }
+
+    /// Whether the scan can stop at the first item below min star
+    pub fn can_stop_at_star(order: Option<SortOrder>) -> bool {
+        order == Some(SortOrder::Rarity)
+    }
+
+    /// Whether the scan can stop at the first item below min level
+    pub fn can_stop_at_level(order: Option<SortOrder>) -> bool {
+        order == Some(SortOrder::Level)
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use image::RgbImage;
+use log::{info, warn};
+
+use crate::capture::Capturer;
+use crate::game_language::GameLanguage;
+use crate::ocr::ImageToText;
+use crate::positioning::{Pos, Rect};
+use crate::system_control::SystemControl;
+use crate::utils;
+
+use super::SortOrder;
+
+/// Where the sort dropdown of an inventory is, and where its options are once it is opened,
+/// relative to the game window, None if it is not known for the resolution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortOrderLayout {
+    pub sort_rect: Option<Rect<f64>>,
+    pub rarity_pos: Option<Pos<f64>>,
+    pub level_pos: Option<Pos<f64>>,
+    pub time_pos: Option<Pos<f64>>,
+}
+
+/// Reads and switches the sort order of an inventory
+pub struct SortOrderSwitcher<'a> {
+    layout: SortOrderLayout,
+    window: Rect<i32>,
+    language: GameLanguage,
+    capturer: &'a dyn Capturer<RgbImage>,
+    image_to_text: &'a dyn ImageToText<RgbImage>,
+}
+
+impl<'a> SortOrderSwitcher<'a> {
+    pub fn new(
+        layout: SortOrderLayout,
+        window: Rect<i32>,
+        language: GameLanguage,
+        capturer: &'a dyn Capturer<RgbImage>,
+        image_to_text: &'a dyn ImageToText<RgbImage>,
+    ) -> Self {
+        SortOrderSwitcher {
+            layout,
+            window,
+            language,
+            capturer,
+            image_to_text,
+        }
+    }
+
+    /// Read the current sort order from the sort dropdown, None if it cannot be recognized
+    pub fn get_sort_order(&self) -> Result<Option<SortOrder>> {
+        let rect = match self.layout.sort_rect {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let im = self.capturer.capture_relative_to(rect.to_rect_i32(), self.window.origin())?;
+        let s = self.image_to_text.image_to_text(&im, false)?;
+
+        Ok(SortOrder::from_text(&s, self.language))
+    }
+
+    fn click_relative(&self, pos: Pos<f64>) -> Result<()> {
+        let mut system_control = SystemControl::new();
+        system_control.mouse_move_to(
+            self.window.left + pos.x as i32,
+            self.window.top + pos.y as i32,
+        )?;
+        utils::sleep(100);
+        system_control.mouse_click()?;
+        utils::sleep(500);
+        Ok(())
+    }
+
+    /// Open the sort dropdown and select `order`
+    pub fn set_sort_order(&self, order: SortOrder) -> Result<()> {
+        let option_pos = match order {
+            SortOrder::Rarity => self.layout.rarity_pos,
+            SortOrder::Level => self.layout.level_pos,
+            SortOrder::Time => self.layout.time_pos,
+        };
+        let (rect, option_pos) = match (self.layout.sort_rect, option_pos) {
+            (Some(rect), Some(pos)) => (rect, pos),
+            _ => return Err(anyhow::anyhow!("当前分辨率不支持切换排序方式，请手动切换为{}", order.to_zh_cn())),
+        };
+
+        self.click_relative(Pos {
+            x: rect.left + rect.width / 2.0,
+            y: rect.top + rect.height / 2.0,
+        })?;
+        self.click_relative(option_pos)
+    }
+
+    /// Switch to `target` if any, and return the order the scan runs in
+    pub fn prepare(&self, target: Option<SortOrder>) -> Result<Option<SortOrder>> {
+        let mut order = self.get_sort_order()?;
+
+        if let Some(target) = target {
+            if order != Some(target) {
+                info!("切换排序方式为{}", target.to_zh_cn());
+                self.set_sort_order(target)?;
+                order = self.get_sort_order()?;
+                if order != Some(target) {
+                    return Err(anyhow::anyhow!("切换排序方式失败，请手动切换为{}", target.to_zh_cn()));
+                }
+            }
+        }
+
+        match order {
+            Some(v) => info!("排序方式: {}", v.to_zh_cn()),
+            None => warn!("无法识别排序方式，将扫描全部物品后再按星级和等级过滤"),
+        }
+        Ok(order)
+    }
+}
@@ -1,5 +1,9 @@
 use std::io::Read;
 
+use image::{GenericImageView, RgbImage};
+
+use crate::positioning::Rect;
+
 /// Calculate the square of the distance (not the distance itself)
 pub fn color_distance(c1: &image::Rgb<u8>, c2: &image::Rgb<u8>) -> usize {
     let x = c1.0[0] as i32 - c2.0[0] as i32;
@@ -11,3 +15,17 @@ pub fn color_distance(c1: &image::Rgb<u8>, c2: &image::Rgb<u8>) -> usize {
 pub fn press_any_key_to_continue() {
     let _ = std::io::stdin().read(&mut [0u8]).unwrap();
 }
+
+/// Crop a rect out of an image, None if the rect is (partially) outside the image
+pub fn crop_rect(image: &RgbImage, rect: &Rect<f64>) -> Option<RgbImage> {
+    let rect = rect.to_rect_i32();
+    if rect.left < 0 || rect.top < 0 || rect.width <= 0 || rect.height <= 0 {
+        return None;
+    }
+    let rect = rect.to_rect_u32();
+    if rect.left + rect.width > image.width() || rect.top + rect.height > image.height() {
+        return None;
+    }
+
+    Some(image.view(rect.left, rect.top, rect.width, rect.height).to_image())
+}
@@ -0,0 +1,11 @@
+mod stat_values;
+mod stat_validator;
+mod validation_report;
+mod validation_config;
+mod roll_counts;
+
+pub use stat_values::StatValues;
+pub use stat_validator::StatValidator;
+pub use validation_report::{StatIssue, ValidationReport};
+pub use validation_config::ValidationConfig;
+pub use roll_counts::{choose_roll_counts, RollCountChoice};
@@ -0,0 +1,126 @@
+/// The candidates chosen for the sub stats of an item, and how many sub stats it started with
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollCountChoice {
+    /// in the order of the sub stats, the index of the chosen candidate, None if the sub stat has none
+    pub chosen: Vec<Option<usize>>,
+    /// the number of sub stats at level 0, None if it cannot be told
+    pub initial_sub_stats: Option<usize>,
+}
+
+/// Choose one candidate for every sub stat so that the total number of rolls agrees with the upgrades,
+/// preferring the smallest total error, None if no choice agrees
+///
+/// `candidates` holds the (rolls, error) of every candidate decomposition of a sub stat, None if it is absent.
+/// A sub stat without any candidate is left out, it has between 1 and `1 + upgrades` rolls.
+/// The item starts with one of `initial_counts` sub stats, and every upgrade rolls a sub stat once,
+/// which adds a sub stat until there are 4
+pub fn choose_roll_counts(
+    candidates: &[Option<Vec<(usize, f64)>>],
+    initial_counts: &[usize],
+    upgrades: usize,
+) -> Option<RollCountChoice> {
+    let present = candidates.iter().filter(|c| c.is_some()).count();
+    let unknown = candidates.iter().flatten().filter(|c| c.is_empty()).count();
+
+    // every combination of the candidates, with the total number of rolls and the total error
+    let mut combinations: Vec<(Vec<usize>, usize, f64)> = vec![(Vec::new(), 0, 0.0)];
+    for c in candidates.iter().flatten().filter(|c| !c.is_empty()) {
+        combinations = combinations.iter()
+            .flat_map(|(chosen, rolls, error)| c.iter().enumerate().map(move |(i, (r, e))| {
+                let mut chosen = chosen.clone();
+                chosen.push(i);
+                (chosen, rolls + r, error + e)
+            }))
+            .collect();
+    }
+
+    let feasible = combinations.into_iter()
+        .filter_map(|(chosen, rolls, error)| {
+            let initial = initial_counts.iter().copied().find(|&n| {
+                let total = n + upgrades;
+                present == total.min(4) && rolls + unknown <= total && total <= rolls + unknown * (1 + upgrades)
+            })?;
+            Some((chosen, initial, error))
+        })
+        .collect::<Vec<_>>();
+
+    let best = feasible.iter().min_by(|a, b| a.2.total_cmp(&b.2))?;
+    let initial_sub_stats = if feasible.iter().all(|f| f.1 == best.1) {
+        Some(best.1)
+    } else {
+        None
+    };
+
+    let mut indices = best.0.iter();
+    let chosen = candidates.iter()
+        .map(|c| match c {
+            Some(c) if !c.is_empty() => indices.next().copied(),
+            _ => None,
+        })
+        .collect();
+
+    Some(RollCountChoice {
+        chosen,
+        initial_sub_stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_rolls() {
+        let candidates = vec![Some(vec![(1, 0.0)]); 4];
+        let choice = choose_roll_counts(&candidates, &[3, 4], 0).unwrap();
+
+        assert_eq!(choice.chosen, vec![Some(0); 4]);
+        assert_eq!(choice.initial_sub_stats, Some(4));
+    }
+
+    #[test]
+    fn test_rolls_agree_with_upgrades() {
+        // 5 rolls at the first upgrade, so the item started with 4 sub stats
+        let candidates = vec![
+            Some(vec![(2, 0.1)]),
+            Some(vec![(1, 0.0), (2, 0.3)]),
+            Some(vec![(1, 0.0)]),
+            Some(vec![(1, 0.0)]),
+        ];
+        let choice = choose_roll_counts(&candidates, &[3, 4], 1).unwrap();
+
+        assert_eq!(choice.chosen, vec![Some(0); 4]);
+        assert_eq!(choice.initial_sub_stats, Some(4));
+    }
+
+    #[test]
+    fn test_ambiguous_initial_sub_stats() {
+        // 4 or 5 rolls both fit an item with 4 sub stats after one upgrade
+        let candidates = vec![
+            Some(vec![(1, 0.0), (2, 0.0)]),
+            Some(vec![(1, 0.0)]),
+            Some(vec![(1, 0.0)]),
+            Some(vec![(1, 0.0)]),
+        ];
+        let choice = choose_roll_counts(&candidates, &[3, 4], 1).unwrap();
+
+        assert_eq!(choice.initial_sub_stats, None);
+    }
+
+    #[test]
+    fn test_sub_stat_without_candidates() {
+        let candidates = vec![Some(vec![(1, 0.0)]), Some(vec![]), Some(vec![(1, 0.0)]), None];
+        let choice = choose_roll_counts(&candidates, &[2, 3], 0).unwrap();
+
+        assert_eq!(choice.chosen, vec![Some(0), None, Some(0), None]);
+        assert_eq!(choice.initial_sub_stats, Some(3));
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // 3 sub stats at level 0 cannot have 4 rolls
+        let candidates = vec![Some(vec![(2, 0.0)]), Some(vec![(1, 0.0)]), Some(vec![(1, 0.0)]), None];
+
+        assert_eq!(choose_roll_counts(&candidates, &[3, 4], 0), None);
+    }
+}
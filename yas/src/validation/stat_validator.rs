@@ -0,0 +1,122 @@
+use crate::validation::{StatIssue, StatValues, ValidationReport};
+
+
+/// Checks parsed stat values against what the game can roll, and keeps a record of the impossible ones
+pub struct StatValidator {
+    /// whether an impossible value is replaced, when there is only one value it can be
+    pub autocorrect: bool,
+    pub report: ValidationReport,
+}
+
+impl StatValidator {
+    pub fn new(autocorrect: bool) -> StatValidator {
+        StatValidator {
+            autocorrect,
+            report: ValidationReport::default(),
+        }
+    }
+
+    /// The value itself if it is attainable, otherwise the only attainable value it may be a misreading of
+    /// `index` and `field` tell where the value is from in the report, `stat` is the name of the stat
+    pub fn validate(
+        &mut self,
+        index: usize,
+        field: &str,
+        stat: &str,
+        value: f64,
+        attainable: &StatValues,
+        tolerance: f64,
+    ) -> f64 {
+        if attainable.contains(value, tolerance) {
+            return value;
+        }
+
+        let corrected = attainable.single().or_else(|| {
+            // a dropped or an extra decimal point scales the value by a power of 10
+            let candidates = [value / 10.0, value * 10.0, value / 100.0, value * 100.0].into_iter()
+                .filter(|&v| attainable.contains(v, tolerance))
+                .collect::<Vec<_>>();
+            if candidates.len() == 1 {
+                Some(candidates[0])
+            } else {
+                None
+            }
+        }).filter(|_| self.autocorrect);
+
+        self.report.items.push(StatIssue {
+            index,
+            field: String::from(field),
+            stat: String::from(stat),
+            value,
+            corrected,
+        });
+        corrected.unwrap_or(value)
+    }
+
+    /// Record a value which is attainable by itself, but not along with the other values of the item
+    pub fn flag(&mut self, index: usize, field: &str, stat: &str, value: f64) {
+        self.report.items.push(StatIssue {
+            index,
+            field: String::from(field),
+            stat: String::from(stat),
+            value,
+            corrected: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolls() -> StatValues {
+        StatValues::from_rolls(&[0.0272, 0.0311, 0.035, 0.0389], 2)
+    }
+
+    #[test]
+    fn test_attainable_value() {
+        let mut validator = StatValidator::new(true);
+
+        assert_eq!(validator.validate(0, "sub_stat_1", "Critical", 0.035, &rolls(), 0.001), 0.035);
+        assert!(validator.report.items.is_empty());
+    }
+
+    #[test]
+    fn test_single_value() {
+        let mut validator = StatValidator::new(true);
+        let value = validator.validate(0, "main_stat", "Hp", 4730.0, &StatValues::Discrete(vec![4780.0]), 1.0);
+
+        assert_eq!(value, 4780.0);
+        assert_eq!(validator.report.items[0].corrected, Some(4780.0));
+    }
+
+    #[test]
+    fn test_decimal_point() {
+        let mut validator = StatValidator::new(true);
+        // 3.5% read without its decimal point
+        let value = validator.validate(1, "sub_stat_2", "Critical", 0.35, &rolls(), 0.001);
+
+        assert!((value - 0.035).abs() < 1e-9);
+        assert_eq!(validator.report.items[0].index, 1);
+    }
+
+    #[test]
+    fn test_no_autocorrect() {
+        let mut validator = StatValidator::new(false);
+        let value = validator.validate(0, "sub_stat_1", "Critical", 0.35, &rolls(), 0.001);
+
+        assert_eq!(value, 0.35);
+        assert_eq!(validator.report.items.len(), 1);
+        assert_eq!(validator.report.items[0].corrected, None);
+    }
+
+    #[test]
+    fn test_no_correction() {
+        let mut validator = StatValidator::new(true);
+        // neither a tenth nor a hundredth of the value is attainable, so it is kept
+        let value = validator.validate(0, "sub_stat_1", "Critical", 0.9, &rolls(), 0.001);
+
+        assert_eq!(value, 0.9);
+        assert_eq!(validator.report.items[0].corrected, None);
+    }
+}
@@ -0,0 +1,78 @@
+/// The values a stat can have, in the unit of the parsed stat
+#[derive(Debug, Clone)]
+pub enum StatValues {
+    /// one of the values, e.g. the sums of the rolls of a sub stat
+    Discrete(Vec<f64>),
+    /// any value in between, inclusive
+    Range(f64, f64),
+}
+
+impl StatValues {
+    /// The sums of 1 to `max_rolls` rolls, each of which is one of `tiers`
+    pub fn from_rolls(tiers: &[f64], max_rolls: usize) -> StatValues {
+        let mut sums = vec![0.0];
+        let mut values = Vec::new();
+        for _ in 0..max_rolls {
+            sums = sums.iter()
+                .flat_map(|s| tiers.iter().map(move |t| s + t))
+                .collect::<Vec<_>>();
+            sums.sort_by(|a, b| a.total_cmp(b));
+            sums.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+            values.extend_from_slice(&sums);
+        }
+
+        StatValues::Discrete(values)
+    }
+
+    /// Whether `value` is attainable, `tolerance` allows for the rounding of the displayed value
+    pub fn contains(&self, value: f64, tolerance: f64) -> bool {
+        match self {
+            StatValues::Discrete(values) => values.iter().any(|v| (v - value).abs() <= tolerance),
+            StatValues::Range(min, max) => value >= min - tolerance && value <= max + tolerance,
+        }
+    }
+
+    /// The only attainable value, if there is one
+    pub fn single(&self) -> Option<f64> {
+        match self {
+            StatValues::Discrete(values) if values.len() == 1 => Some(values[0]),
+            StatValues::Range(min, max) if min == max => Some(*min),
+            _ => None,
+        }
+    }
+
+    /// The highest attainable value, None if there is none
+    pub fn max(&self) -> Option<f64> {
+        match self {
+            StatValues::Discrete(values) => values.iter().copied().reduce(f64::max),
+            StatValues::Range(_, max) => Some(*max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_rolls() {
+        let values = StatValues::from_rolls(&[0.7, 0.8, 0.9, 1.0], 2);
+
+        assert!(values.contains(0.7, 1e-6));
+        assert!(values.contains(1.5, 1e-6));
+        assert!(values.contains(2.0, 1e-6));
+        assert!(!values.contains(0.5, 1e-6));
+        assert!(!values.contains(2.1, 1e-6));
+        assert_eq!(values.max(), Some(2.0));
+        assert_eq!(values.single(), None);
+    }
+
+    #[test]
+    fn test_from_rolls_tolerance() {
+        let values = StatValues::from_rolls(&[2.72, 3.11, 3.5, 3.89], 1);
+
+        // a displayed 3.9 is a roll of 3.89 rounded
+        assert!(values.contains(3.9, 0.06));
+        assert!(!values.contains(3.9, 0.001));
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Clone, Default, clap::Args)]
+pub struct ValidationConfig {
+    /// Do not check the stat values against what the game can roll
+    #[arg(id = "no-stat-validation", long = "no-stat-validation", help = "不校验属性数值是否可能出现")]
+    pub no_validation: bool,
+
+    /// Only record the impossible stat values, instead of correcting them where there is only one value they can be
+    #[arg(id = "no-stat-autocorrect", long = "no-stat-autocorrect", help = "不自动校正不可能出现的属性数值，仅记录")]
+    pub no_autocorrect: bool,
+}
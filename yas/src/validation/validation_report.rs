@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::export::ExportAssets;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatIssue {
+    /// the position of the item in the scan results, starting from 0
+    pub index: usize,
+    pub field: String,
+    pub stat: String,
+    pub value: f64,
+    /// None if the value is left as it is
+    pub corrected: Option<f64>,
+}
+
+/// Every parsed stat value which the game cannot roll, and what it is corrected to
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub items: Vec<StatIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn uncorrected(&self) -> impl Iterator<Item = &StatIssue> {
+        self.items.iter().filter(|issue| issue.corrected.is_none())
+    }
+
+    pub fn log(&self) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let uncorrected_count = self.uncorrected().count();
+        info!("校正了 {} 个不可能出现的属性数值", self.items.len() - uncorrected_count);
+        if uncorrected_count == 0 {
+            return;
+        }
+
+        warn!("以下 {} 个属性数值不可能出现，未校正，请核对：", uncorrected_count);
+        for item in self.uncorrected() {
+            warn!("第 {} 个物品 {}: {} 的数值 {}", item.index + 1, item.field, item.stat, item.value);
+        }
+    }
+
+    /// Add `validation.json` to the export assets
+    pub fn emit_to(&self, output_dir: &Path, export_assets: &mut ExportAssets) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let contents = serde_json::to_string_pretty(&self.items).unwrap();
+        export_assets.add_asset(
+            Some(String::from("validation")),
+            output_dir.join("validation.json"),
+            contents.into_bytes(),
+            Some(String::from("属性数值的校验记录")),
+        );
+    }
+}
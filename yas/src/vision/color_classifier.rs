@@ -0,0 +1,96 @@
+use image::Rgb;
+
+use crate::utils::color_distance;
+
+/// The squared distance between black and white
+const MAX_COLOR_DISTANCE: f64 = 255.0 * 255.0 * 3.0;
+
+#[derive(Debug, Clone)]
+pub struct ColorClassification<T> {
+    pub label: T,
+    /// squared distance to the nearest prototype
+    pub distance: usize,
+    /// in [0, 1], low if the color is about as close to the second nearest prototype
+    pub confidence: f64,
+}
+
+/// Classify a color by the nearest prototype
+#[derive(Debug, Clone)]
+pub struct ColorClassifier<T> {
+    prototypes: Vec<(T, Rgb<u8>)>,
+}
+
+impl<T: Clone> ColorClassifier<T> {
+    pub fn new(prototypes: &[(T, Rgb<u8>)]) -> Self {
+        assert!(!prototypes.is_empty(), "a color classifier needs at least one prototype");
+        ColorClassifier {
+            prototypes: prototypes.to_vec(),
+        }
+    }
+
+    pub fn classify(&self, color: &Rgb<u8>) -> ColorClassification<T> {
+        let mut distances = self.prototypes.iter()
+            .enumerate()
+            .map(|(i, (_, prototype))| (i, color_distance(prototype, color)))
+            .collect::<Vec<_>>();
+        distances.sort_by_key(|&(_, d)| d);
+
+        let (index, distance) = distances[0];
+        let nearest = (distance as f64).sqrt();
+        let confidence = match distances.get(1) {
+            Some(&(_, second)) if second > 0 => 1.0 - nearest / (second as f64).sqrt(),
+            Some(_) => 0.0,
+            None => 1.0 - nearest / MAX_COLOR_DISTANCE.sqrt(),
+        };
+
+        ColorClassification {
+            label: self.prototypes[index].0.clone(),
+            distance,
+            confidence,
+        }
+    }
+}
+
+impl ColorClassifier<usize> {
+    /// Labels are the indices of the colors
+    pub fn from_colors(colors: &[Rgb<u8>]) -> Self {
+        let prototypes = colors.iter().enumerate().map(|(i, c)| (i, *c)).collect::<Vec<_>>();
+        Self::new(&prototypes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::fixture;
+
+    /// the star colors of Genshin artifacts, from 1 to 5 stars
+    const STAR_COLORS: [Rgb<u8>; 5] = [
+        Rgb([113, 119, 139]),
+        Rgb([42, 143, 114]),
+        Rgb([81, 127, 203]),
+        Rgb([161, 86, 224]),
+        Rgb([188, 105, 50]),
+    ];
+
+    #[test]
+    fn test_star_colors() {
+        let image = fixture("star_colors.png").to_rgb8();
+        let classifier = ColorClassifier::from_colors(&STAR_COLORS);
+
+        // every block of 4 pixels is one of the colors with some noise
+        for (x, y, color) in image.enumerate_pixels() {
+            let result = classifier.classify(color);
+            assert_eq!(result.label, x as usize / 4, "pixel ({}, {})", x, y);
+            assert!(result.confidence > 0.5);
+        }
+    }
+
+    #[test]
+    fn test_confidence() {
+        let classifier = ColorClassifier::from_colors(&[Rgb([0, 0, 0]), Rgb([200, 200, 200])]);
+
+        assert_eq!(classifier.classify(&Rgb([0, 0, 0])).confidence, 1.0);
+        assert!(classifier.classify(&Rgb([100, 100, 100])).confidence < 0.01);
+    }
+}
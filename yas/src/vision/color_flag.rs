@@ -0,0 +1,69 @@
+use image::Rgb;
+
+use crate::utils::color_distance;
+
+/// The colors of a strip of pixels sampled once, to tell whether the items under it have moved
+#[derive(Debug, Clone)]
+pub struct ColorFlag {
+    colors: Vec<Rgb<u8>>,
+    /// a pixel whose squared distance to the sampled one is less than this is unchanged
+    pub max_distance: usize,
+}
+
+impl ColorFlag {
+    pub fn new(colors: Vec<Rgb<u8>>, max_distance: usize) -> Self {
+        ColorFlag {
+            colors,
+            max_distance,
+        }
+    }
+
+    pub fn from_pixel(color: Rgb<u8>, max_distance: usize) -> Self {
+        Self::new(vec![color], max_distance)
+    }
+
+    pub fn colors(&self) -> &[Rgb<u8>] {
+        &self.colors
+    }
+
+    /// Whether any pixel of `colors` is unchanged from the sampled one at the same position
+    pub fn matches(&self, colors: &[Rgb<u8>]) -> bool {
+        self.colors.iter()
+            .zip(colors.iter())
+            .any(|(a, b)| color_distance(a, b) < self.max_distance)
+    }
+
+    pub fn matches_pixel(&self, color: &Rgb<u8>) -> bool {
+        self.matches(std::slice::from_ref(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::fixture;
+
+    fn pixels(name: &str) -> Vec<Rgb<u8>> {
+        fixture(name).to_rgb8().pixels().copied().collect()
+    }
+
+    #[test]
+    fn test_strip() {
+        let flag = ColorFlag::new(pixels("flag_initial.png"), 10);
+
+        assert!(flag.matches(&pixels("flag_initial.png")));
+        assert!(!flag.matches(&pixels("flag_scrolling.png")));
+        // scrolled by a whole row, the gap between the rows is at the same place again
+        assert!(flag.matches(&pixels("flag_aligned.png")));
+    }
+
+    #[test]
+    fn test_pixel() {
+        let initial = pixels("flag_initial.png");
+        let scrolling = pixels("flag_scrolling.png");
+        let flag = ColorFlag::from_pixel(initial[0], 11);
+
+        assert!(flag.matches_pixel(&initial[1]));
+        assert!(!flag.matches_pixel(&scrolling[0]));
+    }
+}
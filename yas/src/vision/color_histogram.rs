@@ -0,0 +1,133 @@
+use image::{Rgb, RgbImage};
+
+use crate::positioning::Rect;
+use crate::vision::color_search::clamp_region;
+
+/// A quantized RGB histogram of a region
+/// equal histograms have the same count and color sum in every bin, so almost any change of the region tells them apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorHistogram {
+    bins_per_channel: usize,
+    counts: Vec<u32>,
+    sums: Vec<[u64; 3]>,
+    total: u32,
+}
+
+impl ColorHistogram {
+    /// `bins_per_channel` must divide 256, e.g. 4, 8, 16
+    pub fn from_region(image: &RgbImage, region: Rect<i32>, bins_per_channel: usize) -> ColorHistogram {
+        let bins = bins_per_channel * bins_per_channel * bins_per_channel;
+        let mut result = ColorHistogram {
+            bins_per_channel,
+            counts: vec![0; bins],
+            sums: vec![[0; 3]; bins],
+            total: 0,
+        };
+
+        if let Some(region) = clamp_region(image, region) {
+            for x in region.left..region.left + region.width {
+                for y in region.top..region.top + region.height {
+                    result.add(image.get_pixel(x, y));
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn from_image(image: &RgbImage, bins_per_channel: usize) -> ColorHistogram {
+        let region = Rect {
+            left: 0,
+            top: 0,
+            width: image.width() as i32,
+            height: image.height() as i32,
+        };
+        Self::from_region(image, region, bins_per_channel)
+    }
+
+    fn bin_of(&self, color: &Rgb<u8>) -> usize {
+        let size = 256 / self.bins_per_channel;
+        let r = color[0] as usize / size;
+        let g = color[1] as usize / size;
+        let b = color[2] as usize / size;
+        (r * self.bins_per_channel + g) * self.bins_per_channel + b
+    }
+
+    fn add(&mut self, color: &Rgb<u8>) {
+        let bin = self.bin_of(color);
+        self.counts[bin] += 1;
+        for c in 0..3 {
+            self.sums[bin][c] += color[c] as u64;
+        }
+        self.total += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// The normalized histogram, which sums to 1
+    pub fn normalized(&self) -> Vec<f64> {
+        let total = self.total.max(1) as f64;
+        self.counts.iter().map(|&c| c as f64 / total).collect()
+    }
+
+    /// The average color of the most populated bin, None if the region is empty
+    pub fn dominant_color(&self) -> Option<Rgb<u8>> {
+        let (bin, &count) = self.counts.iter().enumerate().max_by_key(|&(_, c)| *c)?;
+        if count == 0 {
+            return None;
+        }
+
+        let sum = self.sums[bin];
+        Some(Rgb([
+            (sum[0] / count as u64) as u8,
+            (sum[1] / count as u64) as u8,
+            (sum[2] / count as u64) as u8,
+        ]))
+    }
+
+    /// Histogram intersection, 1 for identical distributions and 0 for disjoint ones
+    pub fn intersection(&self, other: &ColorHistogram) -> f64 {
+        self.normalized().iter()
+            .zip(other.normalized().iter())
+            .map(|(a, b)| a.min(*b))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::color_distance;
+    use crate::vision::fixture;
+
+    #[test]
+    fn test_dominant_color() {
+        let image = fixture("locked_item.png").to_rgb8();
+        let histogram = ColorHistogram::from_image(&image, 8);
+
+        assert_eq!(histogram.total(), image.width() * image.height());
+        assert_eq!(histogram.intersection(&histogram), 1.0);
+        let color = histogram.dominant_color().unwrap();
+        assert!(image.pixels().any(|p| color_distance(p, &color) < 30));
+    }
+
+    #[test]
+    fn test_changed_region() {
+        let initial = fixture("flag_initial.png").to_rgb8();
+        let scrolling = fixture("flag_scrolling.png").to_rgb8();
+
+        assert_eq!(ColorHistogram::from_image(&initial, 8), ColorHistogram::from_image(&initial, 8));
+        assert_ne!(ColorHistogram::from_image(&initial, 8), ColorHistogram::from_image(&scrolling, 8));
+    }
+
+    #[test]
+    fn test_region_outside_of_image() {
+        let image = fixture("locked_item.png").to_rgb8();
+        let histogram = ColorHistogram::from_region(&image, Rect { left: 100, top: 100, width: 5, height: 5 }, 8);
+
+        assert_eq!(histogram.total(), 0);
+        assert_eq!(histogram.dominant_color(), None);
+    }
+}
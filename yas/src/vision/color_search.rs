@@ -0,0 +1,70 @@
+use image::{Rgb, RgbImage};
+
+use crate::positioning::{Pos, Rect};
+use crate::utils::color_distance;
+
+/// Clamp a rect into the image, None if nothing is left
+pub(crate) fn clamp_region(image: &RgbImage, region: Rect<i32>) -> Option<Rect<u32>> {
+    let left = region.left.max(0);
+    let top = region.top.max(0);
+    let right = (region.left + region.width).min(image.width() as i32);
+    let bottom = (region.top + region.height).min(image.height() as i32);
+    if left >= right || top >= bottom {
+        return None;
+    }
+
+    Some(Rect {
+        left: left as u32,
+        top: top as u32,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+    })
+}
+
+/// Find the first pixel in the region whose squared distance to `color` is less than `max_distance`
+/// the region can be partially outside of the image
+pub fn find_color(image: &RgbImage, region: Rect<i32>, color: &Rgb<u8>, max_distance: usize) -> Option<Pos<i32>> {
+    let region = clamp_region(image, region)?;
+
+    for x in region.left..region.left + region.width {
+        for y in region.top..region.top + region.height {
+            if color_distance(image.get_pixel(x, y), color) < max_distance {
+                return Some(Pos { x: x as i32, y: y as i32 });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::fixture;
+
+    #[test]
+    fn test_find_lock_color() {
+        let image = fixture("locked_item.png").to_rgb8();
+        let region = Rect { left: 10, top: 0, width: 10, height: 20 };
+
+        assert_eq!(find_color(&image, region, &Rgb([255, 138, 117]), 30), Some(Pos { x: 13, y: 7 }));
+    }
+
+    #[test]
+    fn test_region_without_color() {
+        let image = fixture("locked_item.png").to_rgb8();
+        let region = Rect { left: 0, top: 0, width: 10, height: 20 };
+
+        assert_eq!(find_color(&image, region, &Rgb([255, 138, 117]), 30), None);
+    }
+
+    #[test]
+    fn test_region_outside_of_image() {
+        let image = fixture("locked_item.png").to_rgb8();
+        let region = Rect { left: 20, top: -10, width: 20, height: 20 };
+
+        assert_eq!(find_color(&image, region, &Rgb([255, 138, 117]), 30), None);
+        let region = Rect { left: 100, top: 100, width: 5, height: 5 };
+        assert_eq!(find_color(&image, region, &Rgb([255, 138, 117]), 30), None);
+    }
+}
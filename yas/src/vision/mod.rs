@@ -0,0 +1,24 @@
+mod template_matching;
+mod color_search;
+mod color_histogram;
+mod color_flag;
+mod color_classifier;
+mod template_classifier;
+
+pub use template_matching::{match_template, match_template_multi_scale, TemplateMatch};
+pub use color_search::find_color;
+pub use color_histogram::ColorHistogram;
+pub use color_flag::ColorFlag;
+pub use color_classifier::{ColorClassification, ColorClassifier};
+pub use template_classifier::{TemplateClassification, TemplateClassifier};
+
+/// An image in `fixtures/vision`, or in a subdirectory of it
+#[cfg(test)]
+fn fixture(name: &str) -> image::DynamicImage {
+    image::open(fixture_path(name)).unwrap()
+}
+
+#[cfg(test)]
+fn fixture_path(name: &str) -> String {
+    format!("{}/fixtures/vision/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
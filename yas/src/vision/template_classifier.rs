@@ -0,0 +1,141 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use image::imageops::{resize, FilterType};
+use image::{GrayImage, RgbImage};
+
+use crate::vision::match_template;
+
+#[derive(Debug, Clone)]
+pub struct TemplateClassification<T> {
+    /// None if no template matches well enough
+    pub label: Option<T>,
+    /// normalized cross correlation of the best template, in [-1, 1]
+    pub score: f64,
+    /// in [0, 1], low if the second best template matches about as well
+    pub confidence: f64,
+}
+
+/// Classify an image by the template which matches best anywhere in it
+/// templates larger than the image are shrunk to fit, so they can be cropped a bit loosely
+#[derive(Debug, Clone)]
+pub struct TemplateClassifier<T> {
+    templates: Vec<(T, GrayImage)>,
+    /// a best score less than this is not a match
+    pub min_score: f64,
+}
+
+impl<T: Clone> TemplateClassifier<T> {
+    pub fn new(templates: Vec<(T, GrayImage)>, min_score: f64) -> Self {
+        TemplateClassifier {
+            templates,
+            min_score,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    fn score(image: &GrayImage, template: &GrayImage) -> Option<f64> {
+        let factor = (image.width() as f64 / template.width() as f64)
+            .min(image.height() as f64 / template.height() as f64);
+        if factor < 1.0 {
+            let width = ((template.width() as f64 * factor) as u32).max(1);
+            let height = ((template.height() as f64 * factor) as u32).max(1);
+            let template = resize(template, width, height, FilterType::Triangle);
+            return match_template(image, &template).map(|(_, score)| score);
+        }
+        match_template(image, template).map(|(_, score)| score)
+    }
+
+    pub fn classify(&self, image: &RgbImage) -> TemplateClassification<T> {
+        let gray = image::DynamicImage::ImageRgb8(image.clone()).to_luma8();
+        let mut scores = self.templates.iter()
+            .enumerate()
+            .filter_map(|(i, (_, template))| Some((i, Self::score(&gray, template)?)))
+            .collect::<Vec<_>>();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let (index, score) = match scores.first() {
+            Some(&v) => v,
+            None => return TemplateClassification { label: None, score: -1.0, confidence: 0.0 },
+        };
+        let confidence = match scores.get(1) {
+            Some(&(_, second)) if second < 1.0 => ((score - second) / (1.0 - second)).clamp(0.0, 1.0),
+            Some(_) => 0.0,
+            None => score.max(0.0),
+        };
+
+        TemplateClassification {
+            label: if score >= self.min_score { Some(self.templates[index].0.clone()) } else { None },
+            score,
+            confidence,
+        }
+    }
+}
+
+impl TemplateClassifier<String> {
+    /// Every png in `dir` is a template, labeled by its file name without the extension
+    pub fn load_dir<P: AsRef<Path>>(dir: P, min_score: f64) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(|e| anyhow!("无法读取模板目录 {}: {}", dir.display(), e))? {
+            let path = entry?.path();
+            let contents = std::fs::read(&path).map_err(|e| anyhow!("无法读取模板 {}: {}", path.display(), e))?;
+            files.push((path, contents));
+        }
+
+        Self::from_png_files(files.iter().map(|(path, contents)| (path.as_path(), contents.as_slice())), min_score)
+    }
+
+    /// Every png of `files` is a template, labeled by its file name without the extension
+    pub fn from_png_files<'a, I: IntoIterator<Item = (&'a Path, &'a [u8])>>(files: I, min_score: f64) -> Result<Self> {
+        let mut templates = Vec::new();
+        for (path, contents) in files {
+            if path.extension().map_or(true, |ext| !ext.eq_ignore_ascii_case("png")) {
+                continue;
+            }
+            let label = match path.file_stem() {
+                Some(v) => v.to_string_lossy().to_string(),
+                None => continue,
+            };
+            let template = image::load_from_memory(contents)
+                .map_err(|e| anyhow!("无法读取模板 {}: {}", path.display(), e))?
+                .to_luma8();
+            templates.push((label, template));
+        }
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(Self::new(templates, min_score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::{fixture, fixture_path};
+
+    #[test]
+    fn test_classify() {
+        let classifier = TemplateClassifier::load_dir(fixture_path("templates"), 0.8).unwrap();
+        let sample = fixture("cross_sample.png").to_rgb8();
+        let result = classifier.classify(&sample);
+
+        assert_eq!(classifier.len(), 2);
+        assert_eq!(result.label.as_deref(), Some("cross"));
+        assert!(result.confidence > 0.3);
+    }
+
+    #[test]
+    fn test_no_match() {
+        let classifier = TemplateClassifier::load_dir(fixture_path("templates"), 0.8).unwrap();
+        let scene = fixture("scene.png").to_rgb8();
+
+        assert_eq!(classifier.classify(&scene).label, None);
+    }
+}
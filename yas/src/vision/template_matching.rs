@@ -0,0 +1,204 @@
+use image::imageops::{resize, FilterType};
+use image::GrayImage;
+
+use crate::positioning::{Pos, Rect};
+
+/// The longer side of a template in the coarse search
+const COARSE_SIZE: f64 = 48.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateMatch {
+    /// the top left corner of the matched region
+    pub pos: Pos<i32>,
+    /// the scale applied to the template
+    pub scale: f64,
+    /// normalized cross correlation, in [-1, 1]
+    pub score: f64,
+}
+
+/// Prefix sums of pixel values and their squares, to get the statistics of any window in O(1)
+struct IntegralImage {
+    width: usize,
+    sum: Vec<f64>,
+    sum2: Vec<f64>,
+}
+
+impl IntegralImage {
+    fn new(image: &GrayImage) -> Self {
+        let width = image.width() as usize + 1;
+        let height = image.height() as usize + 1;
+        let mut sum = vec![0.0; width * height];
+        let mut sum2 = vec![0.0; width * height];
+
+        for y in 1..height {
+            for x in 1..width {
+                let v = image.get_pixel(x as u32 - 1, y as u32 - 1)[0] as f64;
+                let i = y * width + x;
+                sum[i] = v + sum[i - 1] + sum[i - width] - sum[i - width - 1];
+                sum2[i] = v * v + sum2[i - 1] + sum2[i - width] - sum2[i - width - 1];
+            }
+        }
+
+        IntegralImage { width, sum, sum2 }
+    }
+
+    fn window(&self, x: usize, y: usize, w: usize, h: usize) -> (f64, f64) {
+        let a = y * self.width + x;
+        let b = y * self.width + x + w;
+        let c = (y + h) * self.width + x;
+        let d = (y + h) * self.width + x + w;
+        (
+            self.sum[d] - self.sum[b] - self.sum[c] + self.sum[a],
+            self.sum2[d] - self.sum2[b] - self.sum2[c] + self.sum2[a],
+        )
+    }
+}
+
+/// Find the best position of `template` in `image`, only top left corners inside `search` are tried
+fn match_template_in(image: &GrayImage, template: &GrayImage, search: Rect<i32>) -> Option<(Pos<i32>, f64)> {
+    let (iw, ih) = (image.width() as i32, image.height() as i32);
+    let (tw, th) = (template.width() as i32, template.height() as i32);
+    if tw == 0 || th == 0 || tw > iw || th > ih {
+        return None;
+    }
+
+    let x0 = search.left.max(0);
+    let y0 = search.top.max(0);
+    let x1 = (search.left + search.width).min(iw - tw);
+    let y1 = (search.top + search.height).min(ih - th);
+    if x0 > x1 || y0 > y1 {
+        return None;
+    }
+
+    let n = (tw * th) as f64;
+    let template_mean = template.pixels().map(|p| p[0] as f64).sum::<f64>() / n;
+    let zero_mean = template.pixels().map(|p| p[0] as f64 - template_mean).collect::<Vec<_>>();
+    let template_norm = zero_mean.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if template_norm == 0.0 {
+        return None;
+    }
+
+    let integral = IntegralImage::new(image);
+    let pixels = image.as_raw();
+    let stride = iw as usize;
+
+    let mut best: Option<(Pos<i32>, f64)> = None;
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            let (sum, sum2) = integral.window(x as usize, y as usize, tw as usize, th as usize);
+            let window_norm = (sum2 - sum * sum / n).max(0.0).sqrt();
+            if window_norm == 0.0 {
+                continue;
+            }
+
+            let mut dot = 0.0;
+            for ty in 0..th as usize {
+                let row = (y as usize + ty) * stride + x as usize;
+                let t_row = ty * tw as usize;
+                for tx in 0..tw as usize {
+                    dot += pixels[row + tx] as f64 * zero_mean[t_row + tx];
+                }
+            }
+
+            let score = dot / (template_norm * window_norm);
+            if best.map_or(true, |b| score > b.1) {
+                best = Some((Pos { x, y }, score));
+            }
+        }
+    }
+
+    best
+}
+
+/// Exhaustive normalized cross correlation, returns the top left corner and the score
+pub fn match_template(image: &GrayImage, template: &GrayImage) -> Option<(Pos<i32>, f64)> {
+    let search = Rect {
+        left: 0,
+        top: 0,
+        width: image.width() as i32,
+        height: image.height() as i32,
+    };
+    match_template_in(image, template, search)
+}
+
+fn resize_by(image: &GrayImage, factor: f64) -> GrayImage {
+    let width = ((image.width() as f64 * factor).round() as u32).max(1);
+    let height = ((image.height() as f64 * factor).round() as u32).max(1);
+    resize(image, width, height, FilterType::Triangle)
+}
+
+/// Match the template under several scales
+/// each scale is searched on downscaled images first, then the best one is refined in full resolution
+pub fn match_template_multi_scale(image: &GrayImage, template: &GrayImage, scales: &[f64]) -> Option<TemplateMatch> {
+    let mut best: Option<(TemplateMatch, f64)> = None;
+
+    for &scale in scales.iter() {
+        let tw = template.width() as f64 * scale;
+        let th = template.height() as f64 * scale;
+        if tw < 4.0 || th < 4.0 || tw > image.width() as f64 || th > image.height() as f64 {
+            continue;
+        }
+
+        let factor = (COARSE_SIZE / tw.max(th)).min(1.0);
+        let coarse_image = resize_by(image, factor);
+        let coarse_template = resize_by(template, scale * factor);
+        if let Some((pos, score)) = match_template(&coarse_image, &coarse_template) {
+            if best.map_or(true, |b| score > b.0.score) {
+                let pos = Pos {
+                    x: (pos.x as f64 / factor) as i32,
+                    y: (pos.y as f64 / factor) as i32,
+                };
+                best = Some((TemplateMatch { pos, scale, score }, factor));
+            }
+        }
+    }
+
+    let (coarse, factor) = best?;
+    let template = resize_by(template, coarse.scale);
+    let radius = (1.0 / factor).ceil() as i32 + 1;
+    let search = Rect {
+        left: coarse.pos.x - radius,
+        top: coarse.pos.y - radius,
+        width: radius * 2,
+        height: radius * 2,
+    };
+
+    let (pos, score) = match_template_in(image, &template, search)?;
+    Some(TemplateMatch { pos, scale: coarse.scale, score })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::fixture;
+
+    fn gray(name: &str) -> GrayImage {
+        fixture(name).to_luma8()
+    }
+
+    #[test]
+    fn test_match_template() {
+        let (pos, score) = match_template(&gray("scene.png"), &gray("ring_template.png")).unwrap();
+
+        assert_eq!(pos, Pos { x: 56, y: 26 });
+        assert!(score > 0.99);
+    }
+
+    #[test]
+    fn test_match_template_multi_scale() {
+        let result = match_template_multi_scale(
+            &gray("scene_scaled.png"),
+            &gray("ring_template.png"),
+            &[1.0, 1.25, 1.5, 1.75],
+        ).unwrap();
+
+        assert_eq!(result.scale, 1.5);
+        assert!((result.pos.x - 84).abs() <= 1 && (result.pos.y - 39).abs() <= 1, "{:?}", result.pos);
+        assert!(result.score > 0.9);
+    }
+
+    #[test]
+    fn test_template_larger_than_image() {
+        assert!(match_template(&gray("ring_template.png"), &gray("scene.png")).is_none());
+    }
+}
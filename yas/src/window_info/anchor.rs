@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::positioning::{Pos, Rect, Scalable, Size};
+use crate::window_info::WindowInfoType;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum HorizontalAnchor {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VerticalAnchor {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Which edge of the window a layout entry sticks to, when the aspect ratio changes
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Anchor {
+    #[serde(default)]
+    pub horizontal: HorizontalAnchor,
+    #[serde(default)]
+    pub vertical: VerticalAnchor,
+}
+
+/// Map a coordinate so that its distance to the anchor point scales with `factor`,
+/// `ratio` is the position of the anchor point, 0 for the start edge and 1 for the end edge
+fn map_coordinate(value: f64, from: f64, to: f64, factor: f64, ratio: f64) -> f64 {
+    to * ratio + (value - from * ratio) * factor
+}
+
+impl HorizontalAnchor {
+    fn ratio(&self) -> f64 {
+        match *self {
+            HorizontalAnchor::Left => 0.0,
+            HorizontalAnchor::Center => 0.5,
+            HorizontalAnchor::Right => 1.0,
+        }
+    }
+}
+
+impl VerticalAnchor {
+    fn ratio(&self) -> f64 {
+        match *self {
+            VerticalAnchor::Top => 0.0,
+            VerticalAnchor::Center => 0.5,
+            VerticalAnchor::Bottom => 1.0,
+        }
+    }
+}
+
+impl Anchor {
+    /// The UI is scaled uniformly to fit into the window, so the smaller factor is used
+    pub fn factor(from: Size<usize>, to: Size<usize>) -> f64 {
+        let fx = to.width as f64 / from.width as f64;
+        let fy = to.height as f64 / from.height as f64;
+        fx.min(fy)
+    }
+
+    fn map_x(&self, x: f64, from: Size<usize>, to: Size<usize>, factor: f64) -> f64 {
+        map_coordinate(x, from.width as f64, to.width as f64, factor, self.horizontal.ratio())
+    }
+
+    fn map_y(&self, y: f64, from: Size<usize>, to: Size<usize>, factor: f64) -> f64 {
+        map_coordinate(y, from.height as f64, to.height as f64, factor, self.vertical.ratio())
+    }
+
+    /// Derive a value recorded under window size `from` for window size `to`
+    pub fn apply(&self, value: WindowInfoType, from: Size<usize>, to: Size<usize>) -> WindowInfoType {
+        let factor = Self::factor(from, to);
+        match value {
+            WindowInfoType::Pos(pos) => WindowInfoType::Pos(Pos {
+                x: self.map_x(pos.x, from, to, factor),
+                y: self.map_y(pos.y, from, to, factor),
+            }),
+            WindowInfoType::Rect(rect) => WindowInfoType::Rect(Rect {
+                left: self.map_x(rect.left, from, to, factor),
+                top: self.map_y(rect.top, from, to, factor),
+                width: rect.width * factor,
+                height: rect.height * factor,
+            }),
+            _ => value.scale(factor),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use crate::game_info::{Platform, UI};
 use crate::positioning::Size;
-use crate::window_info::WindowInfoType;
+use crate::window_info::{Anchor, WindowInfoType};
 use crate::window_info::WindowInfoRepository;
 
 /// Which is a format, where the whole file are recorded under a certain resolution
@@ -11,15 +14,57 @@ pub struct WindowInfoTemplatePerSize {
     pub current_resolution: Size<usize>,
     pub platform: Platform,
     pub ui: UI,
-    pub data: HashMap<String, WindowInfoType>
+    pub data: HashMap<String, WindowInfoType>,
+    /// anchors of the keys, used when the window has a different aspect ratio
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub anchors: HashMap<String, Anchor>,
 }
 
 impl WindowInfoTemplatePerSize {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<WindowInfoTemplatePerSize> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)?;
+        serde_json::from_str(&s).map_err(|e| anyhow!("无法解析窗口信息文件 {}: {}", path.display(), e))
+    }
+
     pub fn inject_into_window_info_repo(&self, repo: &mut WindowInfoRepository) {
         for (name, value) in self.data.iter() {
             repo.add(name, self.current_resolution, self.ui, self.platform, *value);
         }
+        for (name, anchor) in self.anchors.iter() {
+            repo.set_anchor(name, *anchor);
+        }
+    }
+}
+
+/// Load window info from a json file, or from every json file in a directory
+pub fn load_window_info_repo_from_path<P: AsRef<Path>>(path: P) -> Result<WindowInfoRepository> {
+    let path = path.as_ref();
+    let mut result = WindowInfoRepository::new();
+
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if file.extension().map_or(false, |ext| ext == "json") {
+                files.push(file);
+            }
+        }
+        // later files override earlier ones, keep it deterministic
+        files.sort();
+        files
+    } else if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        return Err(anyhow!("窗口信息路径不存在: {}", path.display()));
+    };
+
+    for file in files.iter() {
+        let f = WindowInfoTemplatePerSize::from_file(file)?;
+        f.inject_into_window_info_repo(&mut result);
     }
+
+    Ok(result)
 }
 
 pub macro load_window_info_repo($($filename:literal),+ $(,)?) {
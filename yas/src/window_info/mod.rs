@@ -2,8 +2,14 @@ mod window_info_repository;
 mod window_info_type;
 mod load_window_info;
 mod from_window_info_repository;
+mod window_info_config;
+mod anchor;
+mod window_info_keys;
 
 pub use from_window_info_repository::FromWindowInfoRepository;
-pub use window_info_repository::WindowInfoRepository;
+pub use window_info_repository::{WindowInfoRepository, WindowInfoSource};
 pub use window_info_type::WindowInfoType;
-pub use load_window_info::load_window_info_repo;
\ No newline at end of file
+pub use load_window_info::{load_window_info_repo, load_window_info_repo_from_path, WindowInfoTemplatePerSize};
+pub use window_info_config::WindowInfoConfig;
+pub use anchor::{Anchor, HorizontalAnchor, VerticalAnchor};
+pub use window_info_keys::{WindowInfoKey, WindowInfoKeys};
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use log::info;
+
+use crate::auto_layout::{detect_layout, LandmarkSet, DEFAULT_MIN_SCORE};
+use crate::capture::{Capturer, GenericCapturer};
+use crate::game_info::GameInfo;
+use crate::window_info::{load_window_info_repo_from_path, WindowInfoRepository};
+
+#[derive(Clone, clap::Args)]
+pub struct WindowInfoConfig {
+    /// A window info json file, or a directory of them, which overrides the built-in window info
+    #[arg(id = "window-info", long = "window-info", help = "窗口信息文件或目录，覆盖内置的窗口信息", value_name = "PATH")]
+    pub window_info: Option<PathBuf>,
+
+    /// Print the window info resolved for the current window and exit
+    #[arg(id = "dump-window-info", long = "dump-window-info", help = "输出当前窗口大小下生效的窗口信息并退出")]
+    pub dump_window_info: bool,
+
+    /// Detect the layout of the current window by the landmarks of `--landmarks`
+    #[arg(id = "auto-layout", long = "auto-layout", help = "使用地标模板自动检测界面布局，需要同时指定 --landmarks", requires = "landmarks")]
+    pub auto_layout: bool,
+
+    /// A directory of landmark templates exported by `yas_calibrate --export-landmarks`
+    #[arg(id = "landmarks", long = "landmarks", help = "地标模板目录，由 yas_calibrate --export-landmarks 导出", value_name = "DIR")]
+    pub landmarks: Option<PathBuf>,
+
+    /// Where the window info detected by `--auto-layout` is saved, so that it can be loaded by `--window-info` next time
+    #[arg(id = "save-window-info", long = "save-window-info", help = "将自动检测的窗口信息保存至文件，之后可使用 --window-info 加载", value_name = "PATH")]
+    pub save_window_info: Option<PathBuf>,
+}
+
+impl WindowInfoConfig {
+    /// Merge the window info files given by `--window-info` over the built-in repo
+    pub fn load_repository(&self, mut builtin: WindowInfoRepository) -> Result<WindowInfoRepository> {
+        if let Some(path) = self.window_info.as_ref() {
+            info!("加载窗口信息: {}", path.display());
+            let repo = load_window_info_repo_from_path(path)?;
+            builtin.merge_inplace(&repo);
+        }
+
+        Ok(builtin)
+    }
+
+    /// Detect the layout of the current window by landmarks, and merge it into the repo
+    pub fn apply_auto_layout(&self, repo: &mut WindowInfoRepository, game_info: &GameInfo) -> Result<()> {
+        if !self.auto_layout {
+            return Ok(());
+        }
+
+        let dir = self.landmarks.as_ref()
+            .ok_or(anyhow!("--auto-layout 需要使用 --landmarks 指定地标模板目录"))?;
+        info!("自动检测界面布局，地标模板: {}", dir.display());
+        let landmarks = LandmarkSet::load(dir)?;
+        let frame = GenericCapturer::new()?.capture_rect(game_info.window)?;
+        let template = detect_layout(&frame, &landmarks, repo, DEFAULT_MIN_SCORE)?;
+        template.inject_into_window_info_repo(repo);
+
+        if let Some(path) = self.save_window_info.as_ref() {
+            std::fs::write(path, serde_json::to_string_pretty(&template)?)?;
+            info!("自动检测的窗口信息已保存至 {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Print the resolved window info as json, returns whether it is dumped
+    pub fn dump_if_required(&self, repo: &WindowInfoRepository, game_info: &GameInfo) -> Result<bool> {
+        if !self.dump_window_info {
+            return Ok(false);
+        }
+
+        let resolved = repo.resolve(
+            game_info.window.to_rect_usize().size(),
+            game_info.ui,
+            game_info.platform,
+        );
+        println!("{}", serde_json::to_string_pretty(&resolved)?);
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,12 @@
+/// A window info key read by a window info struct
+#[derive(Debug, Clone, Copy)]
+pub struct WindowInfoKey {
+    pub name: &'static str,
+    /// optional keys are allowed to be missing
+    pub optional: bool,
+}
+
+/// List the window info keys of a struct, implemented by `#[derive(YasWindowInfo)]`
+pub trait WindowInfoKeys {
+    fn window_info_keys() -> Vec<WindowInfoKey>;
+}
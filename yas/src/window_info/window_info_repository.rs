@@ -1,10 +1,39 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use crate::game_info::{Platform, UI};
 use crate::positioning::{Pos, Scalable, Size};
 
-use crate::window_info::WindowInfoType;
+use crate::window_info::{Anchor, WindowInfoTemplatePerSize, WindowInfoType};
+
+/// Where a resolved window info value comes from
+#[derive(Debug, Clone, Copy)]
+pub enum WindowInfoSource {
+    /// recorded under exactly the same window size
+    Exact,
+    /// scaled from a window size with the same aspect ratio
+    Scaled { from: Size<usize>, factor: f64 },
+    /// derived by anchor from the nearest window size
+    Anchored { from: Size<usize>, anchor: Anchor },
+    /// taken as it is from the nearest window size, for values which do not depend on the size
+    Invariant { from: Size<usize> },
+}
+
+impl Display for WindowInfoSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            WindowInfoSource::Exact => write!(f, "精确匹配"),
+            WindowInfoSource::Scaled { from, factor } =>
+                write!(f, "由 {}x{} 等比缩放（{:.3}）", from.width, from.height, factor),
+            WindowInfoSource::Anchored { from, anchor } =>
+                write!(f, "由 {}x{} 按锚点 {:?}/{:?} 推导", from.width, from.height, anchor.horizontal, anchor.vertical),
+            WindowInfoSource::Invariant { from } =>
+                write!(f, "沿用 {}x{} 的值", from.width, from.height),
+        }
+    }
+}
 
 /// Maps a window-info-key to a list of entries
 /// where entries consist of a size where the value is recorded, and accordingly a value
@@ -12,12 +41,17 @@ use crate::window_info::WindowInfoType;
 pub struct WindowInfoRepository {
     /// window info key -> (window size, ui, platform)
     pub data: HashMap<String, HashMap<(Size<usize>, UI, Platform), WindowInfoType>>,
+
+    /// window info key -> anchor, keys without an anchor are only scaled within the same aspect ratio
+    #[serde(default)]
+    pub anchors: HashMap<String, Anchor>,
 }
 
 impl WindowInfoRepository {
     pub fn new() -> WindowInfoRepository {
         WindowInfoRepository {
             data: HashMap::new(),
+            anchors: HashMap::new(),
         }
     }
 
@@ -35,6 +69,10 @@ impl WindowInfoRepository {
             .insert((size, ui, platform), WindowInfoType::Pos(value));
     }
 
+    pub fn set_anchor(&mut self, name: &str, anchor: Anchor) {
+        self.anchors.insert(String::from(name), anchor);
+    }
+
     pub fn merge_inplace(&mut self, other: &WindowInfoRepository) {
         for (key, data) in other.data.iter() {
             if self.data.contains_key(key) {
@@ -45,6 +83,9 @@ impl WindowInfoRepository {
                 self.data.insert(key.clone(), data.clone());
             }
         }
+        for (key, anchor) in other.anchors.iter() {
+            self.anchors.insert(key.clone(), *anchor);
+        }
     }
 
     pub fn merge(&self, other: &WindowInfoRepository) -> WindowInfoRepository {
@@ -64,27 +105,141 @@ impl WindowInfoRepository {
         None
     }
 
+    /// Find the value of a key for a window size, and where it comes from
+    /// 1. the exact window size
+    /// 2. the biggest window size with the same aspect ratio, scaled
+    /// 3. the nearest window size, derived by the anchor of the key, or as it is if the value is invariant
+    pub fn resolve_value(&self, name: &str, window_size: Size<usize>, ui: UI, platform: Platform) -> Option<(WindowInfoType, WindowInfoSource)> {
+        let entries = self.data.get(name)?;
+        if let Some(value) = entries.get(&(window_size, ui, platform)) {
+            return Some((*value, WindowInfoSource::Exact));
+        }
+
+        let candidates = entries.iter()
+            .filter(|(k, _)| k.1 == ui && k.2 == platform)
+            .map(|(k, v)| (k.0, *v))
+            .collect::<Vec<_>>();
+
+        // the biggest size has the least rounding error
+        let same_ratio = candidates.iter()
+            .filter(|(size, _)| size.width * window_size.height == size.height * window_size.width)
+            .max_by_key(|(size, _)| size.width);
+        if let Some(&(size, value)) = same_ratio {
+            let factor = window_size.width as f64 / size.width as f64;
+            return Some((value.scale(factor), WindowInfoSource::Scaled { from: size, factor }));
+        }
+
+        let distance = |size: &Size<usize>| {
+            let ratio = (size.width as f64 / size.height as f64).ln()
+                - (window_size.width as f64 / window_size.height as f64).ln();
+            let scale = (size.height as f64 / window_size.height as f64).ln();
+            (ratio.abs(), scale.abs())
+        };
+        let nearest = candidates.iter()
+            .min_by(|a, b| {
+                let (a, b) = (distance(&a.0), distance(&b.0));
+                a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+            })?;
+        if let WindowInfoType::InvariantInt(_) = nearest.1 {
+            return Some((nearest.1, WindowInfoSource::Invariant { from: nearest.0 }));
+        }
+
+        let anchor = *self.anchors.get(name)?;
+        Some((
+            anchor.apply(nearest.1, nearest.0, window_size),
+            WindowInfoSource::Anchored { from: nearest.0, anchor }
+        ))
+    }
+
     /// Get window info by name and size
-    /// if window size does not exists exactly, this function will search for the same resolution family and scale the result
+    /// if window size does not exists exactly, this function will search for the same resolution family and scale the result,
+    /// or derive it from the nearest window size if the key has an anchor
     pub fn get_auto_scale<T>(&self, name: &str, window_size: Size<usize>, ui: UI, platform: Platform) -> Option<T> where WindowInfoType: TryInto<T> {
-        if self.data.contains_key(name) {
-            if self.data[name].contains_key(&(window_size, ui, platform)) {
-                return self.data[name][&(window_size, ui, platform)].try_into().ok();
-            } else {
-                // todo find a biggest size which can be scaled, this will reduce error
-                // find if a resolution can be scaled
-                for (k, value) in self.data[name].iter() {
-                    let size = &k.0;
-                    if size.width * window_size.height == size.height * window_size.width
-                        && k.1 == ui && k.2 == platform
-                    {
-                        let factor: f64 = window_size.width as f64 / size.width as f64;
-                        return value.scale(factor).try_into().ok();
-                    }
+        let (value, source) = self.resolve_value(name, window_size, ui, platform)?;
+        debug!("窗口信息 {}: {}", name, source);
+        value.try_into().ok()
+    }
+
+    /// Describe why a key cannot be resolved, used in error messages
+    pub fn describe_missing(&self, name: &str, window_size: Size<usize>, ui: UI, platform: Platform) -> String {
+        let sizes = match self.data.get(name) {
+            None => return format!("cannot find window info key \"{}\"", name),
+            Some(entries) => entries.keys()
+                .filter(|k| k.1 == ui && k.2 == platform)
+                .map(|k| format!("{}x{}", k.0.width, k.0.height))
+                .collect::<Vec<_>>(),
+        };
+
+        format!(
+            "cannot find window info key \"{}\" for {}x{} ({:?}, {:?}), recorded sizes: [{}]{}",
+            name, window_size.width, window_size.height, ui, platform, sizes.join(", "),
+            if self.anchors.contains_key(name) { "" } else { ", and the key has no anchor" }
+        )
+    }
+
+    /// Log how the window info of the current window is resolved
+    pub fn log_resolution_summary(&self, window_size: Size<usize>, ui: UI, platform: Platform) {
+        let mut exact = 0;
+        let mut scaled = 0;
+        let mut anchored = 0;
+        let mut invariant = 0;
+        let mut missing = Vec::new();
+        let mut sources = HashSet::new();
+
+        let mut names = self.data.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            match self.resolve_value(name, window_size, ui, platform) {
+                Some((_, WindowInfoSource::Exact)) => exact += 1,
+                Some((_, WindowInfoSource::Scaled { from, .. })) => {
+                    scaled += 1;
+                    sources.insert((from.width, from.height));
+                },
+                Some((_, WindowInfoSource::Anchored { from, .. })) => {
+                    anchored += 1;
+                    sources.insert((from.width, from.height));
+                },
+                Some((_, WindowInfoSource::Invariant { from })) => {
+                    invariant += 1;
+                    sources.insert((from.width, from.height));
+                },
+                None => missing.push(name.as_str()),
+            }
+        }
+
+        let mut sources = sources.into_iter().map(|(w, h)| format!("{}x{}", w, h)).collect::<Vec<_>>();
+        sources.sort();
+        info!(
+            "窗口信息：精确匹配 {} 项，等比缩放 {} 项，锚点推导 {} 项，直接沿用 {} 项，缺失 {} 项，来源: [{}]",
+            exact, scaled, anchored, invariant, missing.len(), sources.join(", ")
+        );
+        if anchored > 0 {
+            warn!("当前窗口比例没有对应的窗口信息，部分位置按锚点推导，可能不准确，可使用 --dump-window-info 检查");
+        }
+        if !missing.is_empty() {
+            warn!("无法推导的窗口信息: {}", missing.join(", "));
+        }
+    }
+
+    /// Resolve every key for a window size, the result can be saved and loaded again as a window info file
+    pub fn resolve(&self, window_size: Size<usize>, ui: UI, platform: Platform) -> WindowInfoTemplatePerSize {
+        let mut data = HashMap::new();
+        let mut anchors = HashMap::new();
+        for name in self.data.keys() {
+            if let Some((value, _)) = self.resolve_value(name, window_size, ui, platform) {
+                data.insert(name.clone(), value);
+                if let Some(anchor) = self.anchors.get(name) {
+                    anchors.insert(name.clone(), *anchor);
                 }
             }
         }
 
-        None
+        WindowInfoTemplatePerSize {
+            current_resolution: window_size,
+            platform,
+            ui,
+            data,
+            anchors,
+        }
     }
 }
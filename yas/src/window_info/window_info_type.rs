@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use crate::positioning::{Pos, Rect, Scalable, Size};
 use anyhow::anyhow;
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum WindowInfoType {
     Rect(Rect<f64>),
     Pos(Pos<f64>),
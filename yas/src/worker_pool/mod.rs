@@ -0,0 +1,5 @@
+mod ordered_pool;
+mod page_retry;
+
+pub use ordered_pool::{ordered_worker_pool, OrderedReceiver, PoolSender};
+pub use page_retry::{PageRetry, RetryItem, ScanFeedback};
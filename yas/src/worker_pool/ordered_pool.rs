@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SendError, SyncSender};
+
+/// The input end of an ordered worker pool, blocks when the queue is full
+pub struct PoolSender<I> {
+    tx: SyncSender<(usize, I)>,
+    next_index: usize,
+    closed: Arc<AtomicBool>,
+}
+
+impl<I> PoolSender<I> {
+    /// Fails once the receiver is dropped, e.g. the consumer stopped early
+    pub fn send(&mut self, item: I) -> Result<(), SendError<I>> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(SendError(item));
+        }
+
+        match self.tx.send((self.next_index, item)) {
+            Ok(()) => {
+                self.next_index += 1;
+                Ok(())
+            },
+            Err(SendError((_, item))) => Err(SendError(item)),
+        }
+    }
+}
+
+/// The output end of an ordered worker pool, yields the outputs in the order of the inputs
+/// it ends once the sender is dropped and every input is processed
+pub struct OrderedReceiver<O> {
+    rx: Receiver<(usize, O)>,
+    next_index: usize,
+    pending: BTreeMap<usize, O>,
+    closed: Arc<AtomicBool>,
+}
+
+impl<O> Iterator for OrderedReceiver<O> {
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        loop {
+            if let Some(output) = self.pending.remove(&self.next_index) {
+                self.next_index += 1;
+                return Some(output);
+            }
+
+            match self.rx.recv() {
+                Ok((index, output)) => {
+                    self.pending.insert(index, output);
+                },
+                // a worker panicked and an output is missing, skip it rather than dropping the rest
+                Err(_) => {
+                    let (index, output) = self.pending.pop_first()?;
+                    self.next_index = index + 1;
+                    return Some(output);
+                },
+            }
+        }
+    }
+}
+
+impl<O> Drop for OrderedReceiver<O> {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Release);
+    }
+}
+
+/// Process items with one thread per worker, e.g. one OCR session per thread
+/// at most `capacity` items are queued, so the producer is slowed down to the speed of the workers
+pub fn ordered_worker_pool<W, I, O, F>(
+    workers: Vec<W>,
+    capacity: usize,
+    process: F,
+) -> (PoolSender<I>, OrderedReceiver<O>)
+where
+    W: Send + 'static,
+    I: Send + 'static,
+    O: Send + 'static,
+    F: Fn(&mut W, I) -> O + Send + Sync + 'static,
+{
+    let (input_tx, input_rx) = mpsc::sync_channel::<(usize, I)>(capacity);
+    let (output_tx, output_rx) = mpsc::channel::<(usize, O)>();
+    let input_rx = Arc::new(Mutex::new(input_rx));
+    let process = Arc::new(process);
+    let closed = Arc::new(AtomicBool::new(false));
+
+    for mut worker in workers.into_iter() {
+        let input_rx = input_rx.clone();
+        let output_tx = output_tx.clone();
+        let process = process.clone();
+
+        std::thread::spawn(move || loop {
+            // the lock is released before processing, so the workers run in parallel
+            let message = input_rx.lock().unwrap().recv();
+            let (index, item) = match message {
+                Ok(v) => v,
+                Err(_) => break,
+            };
+
+            if output_tx.send((index, process(&mut worker, item))).is_err() {
+                break;
+            }
+        });
+    }
+
+    let sender = PoolSender {
+        tx: input_tx,
+        next_index: 0,
+        closed: closed.clone(),
+    };
+    let receiver = OrderedReceiver {
+        rx: output_rx,
+        next_index: 0,
+        pending: BTreeMap::new(),
+        closed,
+    };
+
+    (sender, receiver)
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use log::error;
+
+/// Sent back to the capturing thread once an item is recognized
+pub struct ScanFeedback {
+    pub index: usize,
+    pub attempt: usize,
+    /// whether the item should be captured again
+    pub retry: bool,
+}
+
+/// An item of the current page to select and capture again
+pub struct RetryItem {
+    pub index: usize,
+    /// the attempt of the capture again, starting from 1
+    pub attempt: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Tracks the recognition of the items sent from the current page, so that those which fail
+/// are captured again before the page is scrolled
+///
+/// The feedback is only waited for once an item of the page has failed,
+/// an item which fails after its page is scrolled cannot be captured again
+pub struct PageRetry {
+    feedback: Receiver<ScanFeedback>,
+    /// the positions of the items sent in the current page, by index
+    cells: HashMap<usize, (usize, usize)>,
+    /// the items of the current page which are not recognized yet
+    pending: HashSet<usize>,
+    failed: Vec<ScanFeedback>,
+    disconnected: bool,
+}
+
+impl PageRetry {
+    pub fn new(feedback: Receiver<ScanFeedback>) -> Self {
+        PageRetry {
+            feedback,
+            cells: HashMap::new(),
+            pending: HashSet::new(),
+            failed: Vec::new(),
+            disconnected: false,
+        }
+    }
+
+    /// Record that the item at `row` and `col` of the current page is sent
+    pub fn sent(&mut self, index: usize, row: usize, col: usize) {
+        self.cells.insert(index, (row, col));
+        self.pending.insert(index);
+    }
+
+    fn handle(&mut self, feedback: ScanFeedback) {
+        if !self.cells.contains_key(&feedback.index) {
+            if feedback.retry {
+                error!("第 {} 个物品识别失败，已翻页，无法重新截图", feedback.index + 1);
+            }
+            return;
+        }
+
+        self.pending.remove(&feedback.index);
+        if feedback.retry {
+            self.failed.push(feedback);
+        }
+    }
+
+    /// Take the feedback received so far without waiting
+    pub fn poll(&mut self) {
+        loop {
+            match self.feedback.try_recv() {
+                Ok(v) => self.handle(v),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// The next item of the current page to capture again, None once the page can be left
+    /// it waits for the rest of the page only when an item of it has failed
+    pub fn next_retry(&mut self) -> Option<RetryItem> {
+        self.poll();
+        let mut waiting = !self.failed.is_empty();
+
+        loop {
+            if let Some(feedback) = self.failed.pop() {
+                let (row, col) = self.cells[&feedback.index];
+                return Some(RetryItem {
+                    index: feedback.index,
+                    attempt: feedback.attempt + 1,
+                    row,
+                    col,
+                });
+            }
+
+            if !waiting || self.pending.is_empty() || self.disconnected {
+                return None;
+            }
+            match self.feedback.recv() {
+                Ok(v) => self.handle(v),
+                // the recognition has stopped
+                Err(_) => self.disconnected = true,
+            }
+            waiting = true;
+        }
+    }
+
+    /// Forget the items of the current page, before it is scrolled
+    pub fn end_page(&mut self) {
+        self.cells.clear();
+        self.pending.clear();
+        self.failed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn feedback(index: usize, attempt: usize, retry: bool) -> ScanFeedback {
+        ScanFeedback { index, attempt, retry }
+    }
+
+    #[test]
+    fn test_no_wait_without_failure() {
+        let (tx, rx) = mpsc::channel();
+        let mut retry = PageRetry::new(rx);
+        retry.sent(0, 0, 0);
+        retry.sent(1, 0, 1);
+        tx.send(feedback(0, 0, false)).unwrap();
+
+        // item 1 is not recognized yet, but nothing has failed, so the page is left at once
+        assert!(retry.next_retry().is_none());
+    }
+
+    #[test]
+    fn test_retry_failed_items() {
+        let (tx, rx) = mpsc::channel();
+        let mut retry = PageRetry::new(rx);
+        retry.sent(0, 0, 0);
+        retry.sent(1, 0, 1);
+        tx.send(feedback(0, 0, true)).unwrap();
+
+        let item = retry.next_retry().unwrap();
+        assert_eq!((item.index, item.attempt, item.row, item.col), (0, 1, 0, 0));
+        retry.sent(item.index, item.row, item.col);
+
+        // a failure has been reported, so the rest of the page is waited for
+        tx.send(feedback(1, 0, true)).unwrap();
+        tx.send(feedback(0, 1, false)).unwrap();
+        let item = retry.next_retry().unwrap();
+        assert_eq!((item.index, item.attempt), (1, 1));
+        retry.sent(item.index, item.row, item.col);
+
+        tx.send(feedback(1, 1, false)).unwrap();
+        assert!(retry.next_retry().is_none());
+    }
+
+    #[test]
+    fn test_failure_after_page_end() {
+        let (tx, rx) = mpsc::channel();
+        let mut retry = PageRetry::new(rx);
+        retry.sent(0, 0, 0);
+        assert!(retry.next_retry().is_none());
+        retry.end_page();
+
+        retry.sent(1, 0, 0);
+        tx.send(feedback(0, 0, true)).unwrap();
+        assert!(retry.next_retry().is_none());
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let (tx, rx) = mpsc::channel();
+        let mut retry = PageRetry::new(rx);
+        retry.sent(0, 0, 0);
+        retry.sent(1, 0, 1);
+        tx.send(feedback(0, 0, true)).unwrap();
+        drop(tx);
+
+        assert!(retry.next_retry().is_some());
+        retry.sent(0, 0, 0);
+        assert!(retry.next_retry().is_none());
+    }
+}